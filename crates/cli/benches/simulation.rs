@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId,
+    Criterion, SamplingMode,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use sora_cli::{generate::generate_dataset, simulation::Simulation};
+
+/// Users and offices of the simulated datasets
+const SIZES: [(usize, usize); 4] = [(50, 10), (100, 20), (200, 40), (400, 80)];
+/// About the size of a real deployment, each run taking several seconds
+const LARGE_SIZES: [(usize, usize); 1] = [(10000, 2000)];
+const CONTRACT_DENSITY: f64 = 0.3;
+
+fn simulate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simulate");
+    group.sample_size(10);
+    bench_sizes(&mut group, &SIZES);
+    group.finish();
+
+    // as few samples as criterion allows, each one being a single run
+    let mut group = c.benchmark_group("simulate_large");
+    group
+        .sample_size(10)
+        .sampling_mode(SamplingMode::Flat)
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(90));
+    bench_sizes(&mut group, &LARGE_SIZES);
+    group.finish();
+}
+
+fn bench_sizes(group: &mut BenchmarkGroup<WallTime>, sizes: &[(usize, usize)]) {
+    let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let simulation = Simulation::new(today, 24, 10, None).unwrap();
+
+    for &(users, offices) in sizes {
+        // the same dataset on every run
+        let dataset = generate_dataset(
+            &mut StdRng::seed_from_u64(42),
+            users,
+            offices,
+            CONTRACT_DENSITY,
+            today,
+        )
+        .unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{users} users, {offices} offices")),
+            &dataset,
+            |b, dataset| {
+                b.iter_batched(
+                    || dataset.contracts.clone(),
+                    |contracts| {
+                        simulation
+                            .simulate(
+                                dataset.users.iter().collect(),
+                                dataset.offices.iter().collect(),
+                                vec![],
+                                vec![],
+                                vec![],
+                                contracts,
+                            )
+                            .unwrap()
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+}
+
+criterion_group!(benches, simulate);
+criterion_main!(benches);
@@ -0,0 +1,176 @@
+use std::fs;
+
+use chrono::{NaiveDate, Utc};
+use sora_model::{
+    contract::{Contract, ContractId, ContractState, TerminationPolicy},
+    indexation::IndexTable,
+    office::OfficeId,
+};
+use sqlx::PgPool;
+
+use crate::{
+    persistence::{load_contract, load_office, persist_contract},
+    ContractCommand, DEFAULT_INDEX_VALUES,
+};
+
+pub async fn contract(pool: &PgPool, command: ContractCommand) -> color_eyre::Result<()> {
+    match command {
+        ContractCommand::Sign { id, reason } => {
+            transition(pool, id, ContractState::Signed, reason).await
+        }
+        ContractCommand::Activate { id, reason } => {
+            transition(pool, id, ContractState::Active, reason).await
+        }
+        ContractCommand::Terminate {
+            id,
+            reason,
+            notice_months,
+            penalty_months,
+            index_values,
+        } => {
+            let policy = TerminationPolicy::new(notice_months, penalty_months);
+            let index_table = match index_values {
+                Some(path) => IndexTable::from_csv(&fs::read_to_string(path)?)?,
+                None => IndexTable::from_csv(DEFAULT_INDEX_VALUES)?,
+            };
+
+            terminate(pool, id, reason, &policy, &index_table).await
+        }
+        ContractCommand::Expire { id, reason } => {
+            transition(pool, id, ContractState::Expired, reason).await
+        }
+        ContractCommand::Renew { id, reason } => {
+            transition(pool, id, ContractState::Renewed, reason).await
+        }
+        ContractCommand::Extend { id, until, reason } => extend(pool, id, until, reason).await,
+        ContractCommand::ChangeRent {
+            id,
+            rent,
+            on,
+            reason,
+        } => change_rent(pool, id, rent, on, reason).await,
+        ContractCommand::Move {
+            id,
+            office,
+            on,
+            reason,
+        } => change_office(pool, id, office, on, reason).await,
+    }
+}
+
+async fn transition(
+    pool: &PgPool,
+    id: ContractId,
+    to: ContractState,
+    reason: Option<String>,
+) -> color_eyre::Result<()> {
+    let mut contract = find_contract(pool, id).await?;
+
+    log::info!("Contract {id} is currently {}", contract.state());
+
+    contract.transition(to, Utc::now(), reason)?;
+    persist_contract(&contract, pool).await?;
+
+    log::info!("Contract {id} is now {to}");
+
+    Ok(())
+}
+
+async fn terminate(
+    pool: &PgPool,
+    id: ContractId,
+    reason: Option<String>,
+    policy: &TerminationPolicy,
+    index_table: &IndexTable,
+) -> color_eyre::Result<()> {
+    let mut contract = find_contract(pool, id).await?;
+
+    let termination = contract.terminate(Utc::now(), reason, policy, index_table)?;
+    persist_contract(&contract, pool).await?;
+
+    log::info!(
+        "Contract {id} is terminated, office {} will be freed on {} and {}€ are owed in penalties",
+        contract.office(),
+        termination.on(),
+        *termination.penalty() as f32 / 100.0
+    );
+
+    Ok(())
+}
+
+async fn extend(
+    pool: &PgPool,
+    id: ContractId,
+    until: NaiveDate,
+    reason: Option<String>,
+) -> color_eyre::Result<()> {
+    let mut contract = find_contract(pool, id).await?;
+
+    contract.extend(until, reason)?;
+    persist_contract(&contract, pool).await?;
+
+    log::info!("Contract {id} now ends on {until}");
+
+    Ok(())
+}
+
+async fn change_rent(
+    pool: &PgPool,
+    id: ContractId,
+    rent: usize,
+    on: NaiveDate,
+    reason: Option<String>,
+) -> color_eyre::Result<()> {
+    let mut contract = find_contract(pool, id).await?;
+
+    contract.change_rent(rent, on, reason)?;
+    persist_contract(&contract, pool).await?;
+
+    log::info!(
+        "Contract {id} rent is {}€/month from {on}",
+        rent as f32 / 100.0
+    );
+
+    Ok(())
+}
+
+async fn change_office(
+    pool: &PgPool,
+    id: ContractId,
+    office: OfficeId,
+    on: NaiveDate,
+    reason: Option<String>,
+) -> color_eyre::Result<()> {
+    let mut contract = find_contract(pool, id).await?;
+
+    let current = load_office(contract.office(), pool)
+        .await?
+        .ok_or(ContractCommandError::NoSuchOffice(*contract.office()))?;
+    let office = load_office(&office, pool)
+        .await?
+        .ok_or(ContractCommandError::NoSuchOffice(office))?;
+
+    contract.change_office(&current, &office, on, reason)?;
+    persist_contract(&contract, pool).await?;
+
+    log::info!(
+        "Contract {id} guest moves to office {} on {on}",
+        office.id()
+    );
+
+    Ok(())
+}
+
+async fn find_contract(pool: &PgPool, id: ContractId) -> color_eyre::Result<Contract> {
+    Ok(load_contract(&id, pool)
+        .await?
+        .ok_or(ContractCommandError::NoSuchContract(id))?)
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ContractCommandError {
+    #[error("No contract found with id {0}")]
+    NoSuchContract(ContractId),
+    #[error("No office found with id {0}")]
+    NoSuchOffice(OfficeId),
+}
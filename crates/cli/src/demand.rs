@@ -0,0 +1,731 @@
+use std::{collections::HashMap, fmt::Display, num::NonZeroUsize, str::FromStr, time::Instant};
+
+use chrono::{Days, Months, NaiveDate};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use sora_model::{
+    calendar::OfficeCalendar,
+    contract::{Contract, CONTRACT_DURATION_MINIMUM_MONTHS},
+    indexation::IndexTable,
+    interval::DateRange,
+    office::Office,
+    user::{Role, User, UserId},
+};
+use sqlx::PgPool;
+
+use crate::{
+    exploration::SimulationInput,
+    persistence::{load_contracts, load_office_windows, load_offices, load_users},
+    simulation::{rentable_offices, seed_calendar},
+};
+
+/// Average days in a calendar month
+const DAYS_PER_MONTH: f64 = 365.25 / 12.0;
+
+/// Highest arrival rate, in guests per month; Arrivals are all drawn upfront, so the rate bounds the
+/// memory a run takes
+pub const ARRIVALS_PER_MONTH_MAXIMUM: f64 = 10000.0;
+
+/// Longest stay of an arriving guest, durations drawn beyond it being cut down to it
+const DURATION_MAXIMUM_MONTHS: u32 = 1200;
+
+/// How guests arrive over time
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrivalProcess {
+    /// Independent arrivals, the time between two of them following an exponential distribution
+    #[default]
+    Poisson,
+    /// Arrivals evenly spaced in time
+    Regular,
+}
+
+impl Display for ArrivalProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Poisson => write!(f, "poisson"),
+            Self::Regular => write!(f, "regular"),
+        }
+    }
+}
+
+/// Months arriving guests rent an office for, at least the minimum contract duration; Parsed from
+/// "fixed:12", "uniform:4-24" or "exponential:12" (the mean)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DurationDistribution {
+    Fixed(u32),
+    Uniform { min: u32, max: u32 },
+    Exponential { mean: f64 },
+}
+
+impl DurationDistribution {
+    fn sample(&self, rng: &mut impl Rng) -> u32 {
+        let months = match self {
+            Self::Fixed(months) => *months,
+            Self::Uniform { min, max } => rng.gen_range(*min..=*max),
+            Self::Exponential { mean } => exponential(rng, *mean).round() as u32,
+        };
+
+        months.clamp(CONTRACT_DURATION_MINIMUM_MONTHS, DURATION_MAXIMUM_MONTHS)
+    }
+}
+
+impl FromStr for DurationDistribution {
+    type Err = DemandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DemandError::InvalidDurations(s.to_string());
+        let (kind, parameters) = s.split_once(':').ok_or_else(invalid)?;
+        let months = |value: &str| value.trim().parse::<u32>().map_err(|_| invalid());
+
+        let distribution = match kind.trim() {
+            "fixed" => Self::Fixed(months(parameters)?),
+            "uniform" => {
+                let (min, max) = parameters.split_once('-').ok_or_else(invalid)?;
+                Self::Uniform {
+                    min: months(min)?,
+                    max: months(max)?,
+                }
+            }
+            "exponential" => Self::Exponential {
+                mean: parameters.trim().parse().map_err(|_| invalid())?,
+            },
+            _ => return Err(invalid()),
+        };
+
+        let maximum = DURATION_MAXIMUM_MONTHS;
+        match distribution {
+            Self::Fixed(months) if months == 0 || months > maximum => Err(invalid()),
+            Self::Uniform { min, max } if min == 0 || min > max || max > maximum => Err(invalid()),
+            Self::Exponential { mean } if !(mean > 0.0 && mean <= maximum as f64) => Err(invalid()),
+            _ => Ok(distribution),
+        }
+    }
+}
+
+impl Display for DurationDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fixed(months) => write!(f, "fixed:{months}"),
+            Self::Uniform { min, max } => write!(f, "uniform:{min}-{max}"),
+            Self::Exponential { mean } => write!(f, "exponential:{mean}"),
+        }
+    }
+}
+
+fn check_arrival_rate(arrivals_per_month: f64) -> Result<f64, DemandError> {
+    if !(arrivals_per_month > 0.0 && arrivals_per_month <= ARRIVALS_PER_MONTH_MAXIMUM) {
+        return Err(DemandError::InvalidArrivalRate(arrivals_per_month));
+    }
+
+    Ok(arrivals_per_month)
+}
+
+/// Parses the `--arrivals-per-month` argument, rejecting rates out of bounds before any run
+pub fn parse_arrivals_per_month(s: &str) -> Result<f64, DemandError> {
+    check_arrival_rate(
+        s.trim()
+            .parse()
+            .map_err(|_| DemandError::UnparseableArrivalRate(s.to_string()))?,
+    )
+}
+
+/// A draw from the exponential distribution of the given mean
+fn exponential(rng: &mut impl Rng, mean: f64) -> f64 {
+    -mean * (1.0 - rng.gen::<f64>()).ln()
+}
+
+/// Guests arriving over a period, each one looking for an office from their arrival date on
+#[derive(Debug, Clone)]
+pub struct DemandModel {
+    period: DateRange,
+    process: ArrivalProcess,
+    arrivals_per_month: f64,
+    durations: DurationDistribution,
+    /// Positions each guest reserves
+    positions: usize,
+}
+
+impl DemandModel {
+    pub fn new(
+        start: NaiveDate,
+        duration_in_months: u32,
+        process: ArrivalProcess,
+        arrivals_per_month: f64,
+        durations: DurationDistribution,
+        positions: usize,
+    ) -> Result<Self, DemandError> {
+        check_arrival_rate(arrivals_per_month)?;
+
+        if positions == 0 {
+            return Err(DemandError::NoPositions);
+        }
+
+        // the last guests may stay the longest duration past the end of the period
+        let end = start
+            .checked_add_months(Months::new(duration_in_months))
+            .filter(|end| {
+                end.checked_add_months(Months::new(DURATION_MAXIMUM_MONTHS))
+                    .is_some()
+            })
+            .ok_or(DemandError::InvalidPeriod {
+                start,
+                months: duration_in_months,
+            })?;
+
+        Ok(Self {
+            period: DateRange::new(start, end),
+            process,
+            arrivals_per_month,
+            durations,
+            positions,
+        })
+    }
+
+    /// Arrival dates over the period, in order
+    fn arrivals(&self, rng: &mut impl Rng) -> Vec<NaiveDate> {
+        let mean_interval = DAYS_PER_MONTH / self.arrivals_per_month;
+        let days = self.period.days() as f64;
+
+        let mut arrivals = Vec::new();
+        let mut elapsed = match self.process {
+            ArrivalProcess::Poisson => exponential(rng, mean_interval),
+            ArrivalProcess::Regular => 0.0,
+        };
+        while elapsed < days {
+            arrivals.push(
+                self.period
+                    .start()
+                    .checked_add_days(Days::new(elapsed as u64))
+                    .unwrap(),
+            );
+
+            elapsed += match self.process {
+                ArrivalProcess::Poisson => exponential(rng, mean_interval),
+                ArrivalProcess::Regular => mean_interval,
+            };
+        }
+
+        arrivals
+    }
+
+    /// Matches each arrival, as it comes, with the smallest office having the guest's positions
+    /// left for their whole stay, next to the existing contracts booked in `calendar`; Guests
+    /// finding none are rejected
+    pub fn run(
+        &self,
+        calendar: &OfficeCalendar,
+        input: &SimulationInput,
+        seed: u64,
+    ) -> DemandOutcome {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut calendar = calendar.clone();
+
+        let hosts = input
+            .users
+            .iter()
+            .filter(|user| user.role().can_host())
+            .map(|user| (*user.id(), *user))
+            .collect::<HashMap<UserId, &User>>();
+
+        let mut offices = rentable_offices(&input.offices)
+            .into_iter()
+            .filter_map(|office| Some((office, *hosts.get(office.owner())?)))
+            .collect::<Vec<_>>();
+        offices.sort_by_key(|(office, _)| *office.available_positions());
+
+        let arrivals = self.arrivals(&mut rng);
+        let mut rejected = 0;
+        let mut revenue = 0;
+        for arrival in arrivals.iter() {
+            let end = arrival
+                .checked_add_months(Months::new(self.durations.sample(&mut rng)))
+                .unwrap();
+            let guest = User::new("Arriving".to_string(), "Guest".to_string(), Role::Guest);
+
+            let contract = offices
+                .iter()
+                .filter(|(office, _)| *office.available_positions() >= self.positions)
+                .find(|(office, _)| {
+                    calendar
+                        .is_available(office.id(), self.positions, DateRange::new(*arrival, end))
+                        .unwrap_or_default()
+                })
+                .and_then(|(office, host)| {
+                    Contract::for_office(office, host, &guest, self.positions, *arrival, end).ok()
+                });
+
+            match contract {
+                Some(contract) if calendar.book(&contract).is_ok() => {
+                    revenue += contract.total_rent(&IndexTable::default());
+                }
+                _ => rejected += 1,
+            }
+        }
+
+        DemandOutcome {
+            seed,
+            arrivals: arrivals.len(),
+            rejected,
+            occupancy_rate: self.occupancy_rate(&calendar, &offices),
+            revenue: revenue as f64 / 100.0,
+        }
+    }
+
+    /// Share of the position days of the offices open over the period which are booked
+    fn occupancy_rate(&self, calendar: &OfficeCalendar, offices: &[(&Office, &User)]) -> f64 {
+        let (mut booked, mut open) = (0, 0);
+
+        for (office, _) in offices {
+            let (Ok(occupancy), Ok(closures)) = (
+                calendar.occupancy(office.id(), self.period),
+                calendar.closures(office.id(), self.period),
+            ) else {
+                continue;
+            };
+
+            for (period, remaining) in occupancy {
+                if closures.overlaps(&period) {
+                    continue;
+                }
+
+                let positions = *office.available_positions();
+                booked += (positions - remaining.min(positions)) * period.days();
+                open += positions * period.days();
+            }
+        }
+
+        if open == 0 {
+            0.0
+        } else {
+            booked as f64 / open as f64
+        }
+    }
+
+    /// Runs the model once per seed following `seed`
+    pub fn monte_carlo(
+        &self,
+        input: &SimulationInput,
+        runs: NonZeroUsize,
+        seed: u64,
+        parallel: bool,
+    ) -> DemandReport {
+        let seeds = (0..runs.get() as u64).map(|run| seed.wrapping_add(run));
+        let calendar = existing_calendar(input);
+
+        let outcomes = if parallel {
+            seeds
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .map(|seed| self.run(&calendar, input, seed))
+                .collect()
+        } else {
+            seeds.map(|seed| self.run(&calendar, input, seed)).collect()
+        };
+
+        DemandReport { outcomes }
+    }
+}
+
+/// The existing contracts and windows arrivals are matched next to
+fn existing_calendar(input: &SimulationInput) -> OfficeCalendar {
+    seed_calendar(
+        input.offices.iter().copied(),
+        input.contracts.iter(),
+        input.windows.iter().copied(),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DemandOutcome {
+    pub seed: u64,
+    pub arrivals: usize,
+    pub rejected: usize,
+    /// Share of the open position days booked over the period, from 0 to 1
+    pub occupancy_rate: f64,
+    /// Rent owed over the accepted guests' contracts, in euros excluding VAT
+    pub revenue: f64,
+}
+
+impl DemandOutcome {
+    /// Share of the arrivals finding no office, from 0 to 1
+    pub fn rejection_rate(&self) -> f64 {
+        if self.arrivals == 0 {
+            0.0
+        } else {
+            self.rejected as f64 / self.arrivals as f64
+        }
+    }
+}
+
+/// Outcomes of the runs, in seed order
+#[derive(Debug, Clone)]
+pub struct DemandReport {
+    pub outcomes: Vec<DemandOutcome>,
+}
+
+impl DemandReport {
+    pub fn arrivals(&self) -> Estimate {
+        Estimate::new(self.outcomes.iter().map(|outcome| outcome.arrivals as f64))
+    }
+
+    pub fn occupancy_rate(&self) -> Estimate {
+        Estimate::new(self.outcomes.iter().map(|outcome| outcome.occupancy_rate))
+    }
+
+    pub fn rejection_rate(&self) -> Estimate {
+        Estimate::new(self.outcomes.iter().map(DemandOutcome::rejection_rate))
+    }
+
+    pub fn revenue(&self) -> Estimate {
+        Estimate::new(self.outcomes.iter().map(|outcome| outcome.revenue))
+    }
+}
+
+/// Mean of a non empty sample, with its 95% confidence interval under the normal approximation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Estimate {
+    fn new(samples: impl IntoIterator<Item = f64>) -> Self {
+        let samples = samples.into_iter().collect::<Vec<_>>();
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+
+        let margin = if samples.len() < 2 {
+            0.0
+        } else {
+            let variance = samples
+                .iter()
+                .map(|sample| (sample - mean).powi(2))
+                .sum::<f64>()
+                / (count - 1.0);
+
+            1.96 * (variance / count).sqrt()
+        };
+
+        Self {
+            mean,
+            lower: mean - margin,
+            upper: mean + margin,
+        }
+    }
+
+    fn percent(&self) -> Self {
+        Self {
+            mean: self.mean * 100.0,
+            lower: self.lower * 100.0,
+            upper: self.upper * 100.0,
+        }
+    }
+}
+
+impl Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2} (95% CI {:.2} to {:.2})",
+            self.mean, self.lower, self.upper
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum DemandError {
+    #[error("Guests must arrive at a positive rate of at most {ARRIVALS_PER_MONTH_MAXIMUM} per month, got {0}")]
+    InvalidArrivalRate(f64),
+    #[error("Invalid arrival rate {0:?}, expected a number of guests per month")]
+    UnparseableArrivalRate(String),
+    #[error("Invalid duration distribution {0:?}, expected \"fixed:12\", \"uniform:4-24\" or \"exponential:12\" (months, up to {DURATION_MAXIMUM_MONTHS})")]
+    InvalidDurations(String),
+    #[error("Guests arriving over {months} months from {start} would stay beyond the last supported date")]
+    InvalidPeriod { start: NaiveDate, months: u32 },
+    #[error("Guests must reserve at least one position")]
+    NoPositions,
+}
+
+pub async fn demand(
+    model: DemandModel,
+    runs: NonZeroUsize,
+    seed: Option<u64>,
+    parallel: bool,
+    pool: &PgPool,
+) -> color_eyre::Result<()> {
+    let users = load_users(pool).await?;
+    let offices = load_offices(pool).await?;
+    let windows = load_office_windows(None, pool).await?;
+    let contracts = load_contracts(*model.period.start(), *model.period.end(), pool).await?;
+
+    let input = SimulationInput {
+        users: users.iter().collect(),
+        offices: offices.iter().collect(),
+        windows: windows.iter().collect(),
+        teams: Vec::new(),
+        preferences: Vec::new(),
+        contracts,
+    };
+
+    let seed = seed.unwrap_or_else(rand::random);
+    let started_at = Instant::now();
+    let report = model.monte_carlo(&input, runs, seed, parallel);
+
+    println!(
+        "Ran {runs} simulations of demand from {} to {} in {:.2?}, seeds {seed} onwards:",
+        model.period.start(),
+        model.period.end(),
+        started_at.elapsed()
+    );
+    println!(
+        "> {} arrivals per month ({}), staying {} months and reserving {} positions",
+        model.arrivals_per_month, model.process, model.durations, model.positions
+    );
+    println!("> Arrivals: {}", report.arrivals());
+    println!(
+        "> Occupancy rate (%): {}",
+        report.occupancy_rate().percent()
+    );
+    println!(
+        "> Rejection rate (%): {}",
+        report.rejection_rate().percent()
+    );
+    println!("> Revenue (€ excluding VAT): {}", report.revenue());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+    use sora_model::test_support::{self, date};
+
+    use super::*;
+
+    /// An office of 40 positions at 500€ each
+    fn create_office(host: &User) -> Office {
+        test_support::office_with_positions(host, 40)
+    }
+
+    fn input<'a>(host: &'a User, office: &'a Office) -> SimulationInput<'a> {
+        SimulationInput {
+            users: vec![host],
+            offices: vec![office],
+            windows: Vec::new(),
+            teams: Vec::new(),
+            preferences: Vec::new(),
+            contracts: Vec::new(),
+        }
+    }
+
+    #[rstest]
+    #[case("fixed:12", Ok(DurationDistribution::Fixed(12)))]
+    #[case("uniform:4-24", Ok(DurationDistribution::Uniform { min: 4, max: 24 }))]
+    #[case("exponential:6.5", Ok(DurationDistribution::Exponential { mean: 6.5 }))]
+    #[case("fixed:0", Err(()))]
+    #[case("uniform:24-4", Err(()))]
+    #[case("exponential:-1", Err(()))]
+    #[case("fixed:4000000000", Err(()))]
+    #[case("uniform:4-4000000000", Err(()))]
+    #[case("exponential:1e300", Err(()))]
+    #[case("normal:12", Err(()))]
+    #[case("12", Err(()))]
+    fn test_parse_durations(
+        #[case] input: &str,
+        #[case] expected: Result<DurationDistribution, ()>,
+    ) {
+        assert_eq!(
+            expected,
+            DurationDistribution::from_str(input).map_err(|_| ())
+        );
+    }
+
+    #[rstest]
+    #[case("6", Ok(6.0))]
+    #[case("10000", Ok(10000.0))]
+    #[case("10001", Err(()))]
+    #[case("1e300", Err(()))]
+    #[case("0", Err(()))]
+    #[case("NaN", Err(()))]
+    #[case("many", Err(()))]
+    fn test_parse_arrivals_per_month(#[case] input: &str, #[case] expected: Result<f64, ()>) {
+        assert_eq!(expected, parse_arrivals_per_month(input).map_err(|_| ()));
+    }
+
+    #[test]
+    fn test_durations_last_at_least_the_minimum() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for distribution in [
+            DurationDistribution::Fixed(1),
+            DurationDistribution::Uniform { min: 1, max: 6 },
+            DurationDistribution::Exponential { mean: 2.0 },
+        ] {
+            assert!(
+                (0..100).all(|_| distribution.sample(&mut rng) >= CONTRACT_DURATION_MINIMUM_MONTHS)
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(ArrivalProcess::Regular, 24.0, 0.0)]
+    #[case(ArrivalProcess::Poisson, 24.0, 1.5)]
+    fn test_arrivals(
+        #[case] process: ArrivalProcess,
+        #[case] expected: f64,
+        #[case] tolerance: f64,
+    ) {
+        let model = DemandModel::new(
+            date(2025, 1, 1),
+            24,
+            process,
+            1.0,
+            DurationDistribution::Fixed(12),
+            10,
+        )
+        .unwrap();
+
+        let arrivals = (0..100)
+            .map(|seed| {
+                let arrivals = model.arrivals(&mut StdRng::seed_from_u64(seed));
+                assert!(arrivals.is_sorted());
+                assert!(arrivals
+                    .iter()
+                    .all(|arrival| model.period.contains_date(*arrival)));
+                arrivals.len() as f64
+            })
+            .sum::<f64>()
+            / 100.0;
+
+        assert!((arrivals - expected).abs() <= tolerance, "{arrivals}");
+    }
+
+    #[test]
+    fn test_arrivals_are_rejected_once_offices_are_full() {
+        let host = User::new("Jean".to_string(), "Dupont".to_string(), Role::Host);
+        let office = create_office(&host);
+        // a guest a month, each one taking the whole office for a year
+        let model = DemandModel::new(
+            date(2025, 1, 1),
+            24,
+            ArrivalProcess::Regular,
+            1.0,
+            DurationDistribution::Fixed(12),
+            40,
+        )
+        .unwrap();
+
+        let input = input(&host, &office);
+
+        let outcome = model.run(&existing_calendar(&input), &input, 0);
+
+        assert_eq!(24, outcome.arrivals);
+        assert_eq!(22, outcome.rejected);
+        assert_eq!(1.0, outcome.occupancy_rate);
+        // 2 years of 40 positions at 500€
+        assert_eq!(480000.0, outcome.revenue);
+    }
+
+    #[test]
+    fn test_conflicting_existing_contracts_are_booked() {
+        let host = User::new("Jean".to_string(), "Dupont".to_string(), Role::Host);
+        let office = create_office(&host);
+        let contract = |start, end| {
+            let guest = User::new("Jeanne".to_string(), "Durand".to_string(), Role::Guest);
+            Contract::for_office(&office, &host, &guest, 40, start, end).unwrap()
+        };
+        let mut input = input(&host, &office);
+        input.contracts = vec![
+            contract(date(2025, 1, 1), date(2025, 7, 1)),
+            contract(date(2025, 4, 1), date(2026, 1, 1)),
+        ];
+
+        let calendar = existing_calendar(&input);
+
+        let after_the_first = DateRange::new(date(2025, 10, 1), date(2025, 12, 1));
+        assert!(!calendar
+            .is_available(office.id(), 1, after_the_first)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_monte_carlo() {
+        let host = User::new("Jean".to_string(), "Dupont".to_string(), Role::Host);
+        let office = create_office(&host);
+        let model = DemandModel::new(
+            date(2025, 1, 1),
+            24,
+            ArrivalProcess::Poisson,
+            0.5,
+            DurationDistribution::Uniform { min: 4, max: 8 },
+            40,
+        )
+        .unwrap();
+        let input = input(&host, &office);
+        let runs = NonZeroUsize::new(50).unwrap();
+
+        let report = model.monte_carlo(&input, runs, 42, false);
+        assert_eq!(
+            report.outcomes,
+            model.monte_carlo(&input, runs, 42, true).outcomes
+        );
+        assert_eq!(
+            model.run(&existing_calendar(&input), &input, 45),
+            report.outcomes[3]
+        );
+
+        for estimate in [report.occupancy_rate(), report.rejection_rate()] {
+            assert!(0.0 < estimate.lower && estimate.lower < estimate.mean);
+            assert!(estimate.mean < estimate.upper && estimate.upper < 1.0);
+        }
+    }
+
+    #[rstest]
+    #[case(&[2.0], 2.0, 2.0, 2.0)]
+    #[case(&[1.0, 2.0, 3.0, 4.0], 2.5, 1.234825, 3.765175)]
+    fn test_estimate(
+        #[case] samples: &[f64],
+        #[case] mean: f64,
+        #[case] lower: f64,
+        #[case] upper: f64,
+    ) {
+        let estimate = Estimate::new(samples.iter().copied());
+
+        assert_eq!(mean, estimate.mean);
+        assert!((lower - estimate.lower).abs() < 1e-6);
+        assert!((upper - estimate.upper).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_model() {
+        let model = |months, rate, positions| {
+            DemandModel::new(
+                date(2025, 1, 1),
+                months,
+                ArrivalProcess::Poisson,
+                rate,
+                DurationDistribution::Fixed(12),
+                positions,
+            )
+            .map(|_| ())
+        };
+
+        assert_eq!(
+            Err(DemandError::InvalidArrivalRate(0.0)),
+            model(24, 0.0, 10)
+        );
+        assert_eq!(Err(DemandError::NoPositions), model(24, 1.0, 0));
+        assert_eq!(
+            Err(DemandError::InvalidArrivalRate(1e12)),
+            model(24, 1e12, 10)
+        );
+        assert_eq!(
+            Err(DemandError::InvalidPeriod {
+                start: date(2025, 1, 1),
+                months: u32::MAX
+            }),
+            model(u32::MAX, 1.0, 10)
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use std::{collections::HashMap, fmt::Display, num::NonZeroUsize};
+
+use rayon::prelude::*;
+use sora_model::{
+    contract::Contract,
+    indexation::IndexTable,
+    interval::IntervalSet,
+    office::{Office, OfficeWindow},
+    preference::GuestPreferences,
+    team::Team,
+    user::{User, UserId},
+};
+
+use crate::simulation::{Simulation, Strategy};
+
+/// What the best of several simulations is chosen on
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Objective {
+    /// Days guests spend in an office, up to their target
+    #[default]
+    OfficeDays,
+    /// Guests getting all of their target days in office
+    SatisfiedGuests,
+    /// Rent owed over the contracts, in euros excluding VAT
+    Revenue,
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OfficeDays => write!(f, "office days"),
+            Self::SatisfiedGuests => write!(f, "satisfied guests"),
+            Self::Revenue => write!(f, "revenue"),
+        }
+    }
+}
+
+/// Simulations to run on the same data, each one going through the strategies in turn and using
+/// the seed following the previous run's
+#[derive(Debug, Clone)]
+pub struct Runs {
+    pub count: NonZeroUsize,
+    /// Whether runs are spread across cores
+    pub parallel: bool,
+    /// Seed of the first run; Guests are taken in the given order without one
+    pub seed: Option<u64>,
+    pub strategies: Vec<Strategy>,
+    pub objective: Objective,
+}
+
+impl Runs {
+    /// Strategy and seed of each run
+    fn settings(&self) -> Vec<(Strategy, Option<u64>)> {
+        let strategies = if self.strategies.is_empty() {
+            vec![Strategy::default()]
+        } else {
+            self.strategies.clone()
+        };
+
+        (0..self.count.get())
+            .map(|run| {
+                (
+                    strategies[run % strategies.len()],
+                    self.seed.map(|seed| seed.wrapping_add(run as u64)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Data every run starts from
+pub struct SimulationInput<'a> {
+    pub users: Vec<&'a User>,
+    pub offices: Vec<&'a Office>,
+    pub windows: Vec<&'a OfficeWindow>,
+    pub teams: Vec<&'a Team>,
+    pub preferences: Vec<&'a GuestPreferences>,
+    pub contracts: Vec<Contract>,
+}
+
+impl SimulationInput<'_> {
+    pub fn simulate(&self, simulation: &Simulation) -> color_eyre::Result<Vec<Contract>> {
+        simulation.simulate(
+            self.users.clone(),
+            self.offices.clone(),
+            self.windows.clone(),
+            self.teams.clone(),
+            self.preferences.clone(),
+            self.contracts.clone(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunOutcome {
+    pub run: usize,
+    pub strategy: Strategy,
+    pub seed: Option<u64>,
+    pub score: f64,
+    /// Whether every guest got their target days in office
+    pub feasible: bool,
+}
+
+impl RunOutcome {
+    /// The simulation this run went through, to get its contracts back
+    pub fn simulation(&self, base: &Simulation) -> Simulation {
+        let simulation = base.clone().with_strategy(self.strategy);
+
+        match self.seed {
+            Some(seed) => simulation.with_seed(seed),
+            None => simulation,
+        }
+    }
+}
+
+/// Outcomes of the runs, in run order
+#[derive(Debug, Clone)]
+pub struct Exploration {
+    pub outcomes: Vec<RunOutcome>,
+}
+
+impl Exploration {
+    /// The highest scoring run, the earliest one on ties
+    pub fn best(&self) -> &RunOutcome {
+        self.outcomes
+            .iter()
+            .reduce(|best, outcome| {
+                if outcome.score > best.score {
+                    outcome
+                } else {
+                    best
+                }
+            })
+            .expect("There is always at least one run")
+    }
+
+    /// Share of the runs getting every guest their target days in office, from 0 to 1
+    pub fn feasibility_rate(&self) -> f64 {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.feasible)
+            .count() as f64
+            / self.outcomes.len() as f64
+    }
+
+    pub fn distribution(&self) -> ScoreDistribution {
+        ScoreDistribution::new(self.outcomes.iter().map(|outcome| outcome.score))
+    }
+
+    /// Distribution of the scores of each strategy, in the order they were first used
+    pub fn by_strategy(&self) -> Vec<(Strategy, ScoreDistribution)> {
+        let mut strategies = Vec::<Strategy>::new();
+        for outcome in self.outcomes.iter() {
+            if !strategies.contains(&outcome.strategy) {
+                strategies.push(outcome.strategy);
+            }
+        }
+
+        strategies
+            .into_iter()
+            .map(|strategy| {
+                let scores = self
+                    .outcomes
+                    .iter()
+                    .filter(|outcome| outcome.strategy == strategy)
+                    .map(|outcome| outcome.score);
+
+                (strategy, ScoreDistribution::new(scores))
+            })
+            .collect()
+    }
+}
+
+/// Runs the simulation once per run, scoring each solution under the objective; Contracts are not
+/// kept, since each run can be replayed from its strategy and seed
+pub fn explore(
+    simulation: &Simulation,
+    input: &SimulationInput,
+    runs: &Runs,
+    index_table: &IndexTable,
+) -> color_eyre::Result<Exploration> {
+    let run = |(run, (strategy, seed)): (usize, (Strategy, Option<u64>))| {
+        let mut outcome = RunOutcome {
+            run,
+            strategy,
+            seed,
+            score: 0.0,
+            feasible: false,
+        };
+
+        // the progress of every run would drown the summary, only the best one's is replayed loudly
+        let contracts = input.simulate(
+            &outcome
+                .simulation(simulation)
+                .with_quiet(runs.count.get() > 1),
+        )?;
+        (outcome.score, outcome.feasible) = evaluate(
+            simulation,
+            &input.users,
+            &contracts,
+            runs.objective,
+            index_table,
+        );
+
+        Ok::<_, color_eyre::Report>(outcome)
+    };
+
+    let settings = runs.settings().into_iter().enumerate();
+    let outcomes = if runs.parallel {
+        settings
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(run)
+            .collect::<color_eyre::Result<Vec<_>>>()?
+    } else {
+        settings.map(run).collect::<color_eyre::Result<Vec<_>>>()?
+    };
+
+    Ok(Exploration { outcomes })
+}
+
+/// Score of a solution under the objective, and whether every guest got their target days
+pub fn evaluate(
+    simulation: &Simulation,
+    users: &[&User],
+    contracts: &[Contract],
+    objective: Objective,
+    index_table: &IndexTable,
+) -> (f64, bool) {
+    let target_days = simulation.target_days_in_office();
+
+    let mut occupancies = HashMap::<UserId, IntervalSet>::new();
+    for contract in contracts {
+        occupancies
+            .entry(*contract.guest())
+            .or_default()
+            .insert(contract.period());
+    }
+
+    let office_days = users
+        .iter()
+        .filter(|user| user.role().can_rent())
+        .map(|user| {
+            occupancies
+                .get(user.id())
+                .map(IntervalSet::total_days)
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let feasible = office_days.iter().all(|days| *days >= target_days);
+
+    let score = match objective {
+        Objective::OfficeDays => office_days
+            .iter()
+            .map(|days| (*days).min(target_days))
+            .sum::<usize>() as f64,
+        Objective::SatisfiedGuests => office_days
+            .iter()
+            .filter(|days| **days >= target_days)
+            .count() as f64,
+        Objective::Revenue => {
+            contracts
+                .iter()
+                .map(|contract| contract.total_rent(index_table))
+                .sum::<usize>() as f64
+                / 100.0
+        }
+    };
+
+    (score, feasible)
+}
+
+/// Summary of a non empty set of scores, percentiles being taken by nearest rank
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreDistribution {
+    pub runs: usize,
+    pub min: f64,
+    pub p10: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl ScoreDistribution {
+    fn new(scores: impl IntoIterator<Item = f64>) -> Self {
+        let mut scores = scores.into_iter().collect::<Vec<_>>();
+        scores.sort_by(f64::total_cmp);
+
+        let percentile = |percent: usize| {
+            let rank = (percent * scores.len()).div_ceil(100).max(1);
+            scores[rank - 1]
+        };
+
+        Self {
+            runs: scores.len(),
+            min: scores[0],
+            p10: percentile(10),
+            median: percentile(50),
+            p90: percentile(90),
+            max: scores[scores.len() - 1],
+            mean: scores.iter().sum::<f64>() / scores.len() as f64,
+        }
+    }
+}
+
+impl Display for ScoreDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.0}, p10 {:.0}, median {:.0}, p90 {:.0}, max {:.0}, mean {:.1} over {} runs",
+            self.min, self.p10, self.median, self.p90, self.max, self.mean, self.runs
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+
+    use crate::{
+        generate::{generate_dataset, Dataset},
+        verify::{verify, Violation},
+    };
+
+    use super::*;
+
+    fn dataset() -> Dataset {
+        generate_dataset(
+            &mut StdRng::seed_from_u64(42),
+            60,
+            12,
+            0.3,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn input(dataset: &Dataset) -> SimulationInput<'_> {
+        SimulationInput {
+            users: dataset.users.iter().collect(),
+            offices: dataset.offices.iter().collect(),
+            windows: Vec::new(),
+            teams: Vec::new(),
+            preferences: Vec::new(),
+            contracts: dataset.contracts.clone(),
+        }
+    }
+
+    fn runs(count: usize, parallel: bool) -> Runs {
+        Runs {
+            count: NonZeroUsize::new(count).unwrap(),
+            parallel,
+            seed: Some(7),
+            strategies: vec![Strategy::Earliest, Strategy::Spread],
+            objective: Objective::OfficeDays,
+        }
+    }
+
+    /// Guest, office, period and positions of each contract, independently of generated ids
+    fn assignments(dataset: &Dataset, contracts: &[Contract]) -> Vec<(usize, usize, String)> {
+        let mut assignments = contracts
+            .iter()
+            .map(|contract| {
+                (
+                    dataset
+                        .users
+                        .iter()
+                        .position(|user| user.id() == contract.guest())
+                        .unwrap(),
+                    dataset
+                        .offices
+                        .iter()
+                        .position(|office| office.id() == contract.office())
+                        .unwrap(),
+                    format!("{} {}", contract.period(), contract.positions()),
+                )
+            })
+            .collect::<Vec<_>>();
+        assignments.sort();
+        assignments
+    }
+
+    #[test]
+    fn test_runs_are_reproducible() {
+        let dataset = dataset();
+        let input = input(&dataset);
+        let simulation =
+            Simulation::new(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), 24, 10, None).unwrap();
+        let index_table = IndexTable::default();
+
+        let sequential = explore(&simulation, &input, &runs(8, false), &index_table).unwrap();
+        let parallel = explore(&simulation, &input, &runs(8, true), &index_table).unwrap();
+        assert_eq!(sequential.outcomes, parallel.outcomes);
+        assert_eq!(
+            vec![
+                (Strategy::Earliest, Some(7)),
+                (Strategy::Spread, Some(8)),
+                (Strategy::Earliest, Some(9)),
+            ],
+            sequential.outcomes[..3]
+                .iter()
+                .map(|outcome| (outcome.strategy, outcome.seed))
+                .collect::<Vec<_>>()
+        );
+
+        // the best run's contracts are found again from its strategy and seed
+        let best = sequential.best();
+        let replays = [(); 2].map(|_| input.simulate(&best.simulation(&simulation)).unwrap());
+        assert_eq!(
+            assignments(&dataset, &replays[0]),
+            assignments(&dataset, &replays[1])
+        );
+        assert_eq!(
+            (best.score, best.feasible),
+            evaluate(
+                &simulation,
+                &input.users,
+                &replays[0],
+                Objective::OfficeDays,
+                &index_table
+            )
+        );
+        assert!(sequential
+            .outcomes
+            .iter()
+            .all(|outcome| outcome.score <= best.score));
+
+        let violations = verify(
+            &best.simulation(&simulation),
+            &input.users,
+            &input.offices,
+            &replays[0],
+        );
+        assert!(violations.iter().all(Violation::is_missed_target));
+        assert_eq!(best.feasible, violations.is_empty());
+    }
+
+    #[rstest]
+    #[case(&[3.0], [3.0, 3.0, 3.0, 3.0, 3.0, 3.0])]
+    #[case(&[5.0, 1.0, 4.0, 2.0, 3.0], [1.0, 1.0, 3.0, 5.0, 5.0, 3.0])]
+    #[case(
+        &[10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0],
+        [1.0, 1.0, 5.0, 9.0, 10.0, 5.5]
+    )]
+    fn test_score_distribution(#[case] scores: &[f64], #[case] expected: [f64; 6]) {
+        let distribution = ScoreDistribution::new(scores.iter().copied());
+
+        assert_eq!(scores.len(), distribution.runs);
+        assert_eq!(
+            expected,
+            [
+                distribution.min,
+                distribution.p10,
+                distribution.median,
+                distribution.p90,
+                distribution.max,
+                distribution.mean
+            ]
+        );
+    }
+
+    #[test]
+    fn test_best_run() {
+        let outcome = |run, score, feasible| RunOutcome {
+            run,
+            strategy: Strategy::Earliest,
+            seed: Some(run as u64),
+            score,
+            feasible,
+        };
+        let exploration = Exploration {
+            outcomes: vec![
+                outcome(0, 10.0, false),
+                outcome(1, 30.0, true),
+                outcome(2, 30.0, true),
+                outcome(3, 20.0, true),
+            ],
+        };
+
+        assert_eq!(1, exploration.best().run);
+        assert_eq!(0.75, exploration.feasibility_rate());
+    }
+}
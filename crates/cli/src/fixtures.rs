@@ -1,18 +1,34 @@
-use crate::persistence::{persist_contract, persist_office};
-use chrono::{Days, Utc};
+use std::collections::HashSet;
+
+use crate::{
+    persistence::{
+        is_dev_database, load_tax_rule_set, persist_contract, persist_guest_preferences,
+        persist_office, persist_tax_opt_in, persist_tax_rule, persist_team, persist_user,
+    },
+    scenario::Scenario,
+};
+use chrono::{Datelike, Days, NaiveDate, Utc};
 use fake::{
-    faker::{address::fr_fr::*, name::fr_fr::*},
+    faker::{address::fr_fr::*, company::fr_fr::*, name::fr_fr::*},
     Fake,
 };
-use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
+use rand::{
+    rngs::ThreadRng,
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
 use sora_model::{
-    contract::{Contract, CONTRACT_DURATION_MINIMUM_DAYS},
-    id::Identifier,
+    address::{is_valid_french_postal_code, Address},
+    calendar::OfficeCalendar,
+    contract::{Contract, ContractState, RentNegotiation},
+    geo::Coordinates,
     office::{Office, OfficeSplit},
-    user::User,
-    Object,
+    preference::{GuestPreferences, Location, OfficeSize, Preference, Strictness},
+    tax::TaxRule,
+    team::{Headcount, Team},
+    user::{Role, User},
 };
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 
 const MINIMUM_USERS_TO_GENERATE: usize = 2;
 const MAXIMUM_USERS_TO_GENERATE: usize = 6;
@@ -20,91 +36,173 @@ const MAXIMUM_USERS_TO_GENERATE: usize = 6;
 const MINIMUM_OFFICES_TO_GENERATE: usize = 3;
 const MAXIMUM_OFFICES_TO_GENERATE: usize = 5;
 
+const MINIMUM_TEAM_HEADCOUNT: usize = 5;
+const MAXIMUM_TEAM_HEADCOUNT: usize = 60;
+
+/// Monthly budgets excluding VAT, in cents
+const MINIMUM_GUEST_BUDGET: usize = 500000;
+const MAXIMUM_GUEST_BUDGET: usize = 5000000;
+
+/// Chances for a guest to have preferences, and for each of them to be required
+const GUEST_PREFERENCES_PROBABILITY: f64 = 0.5;
+const REQUIRED_PREFERENCE_PROBABILITY: f64 = 0.3;
+
+/// Farthest offices are generated from their city's center
+const MAXIMUM_DISTANCE_FROM_CITY_CENTER_KM: f64 = 3.0;
+
+const FRENCH_CITIES: &str = include_str!("../data/french_cities.csv");
+
+/// Discount granted on fake contracts, in basis points
+const MAXIMUM_FIXTURE_DISCOUNT: usize = 1500;
+
+/// Random picks of a guest, an office and dates tried for each fake contract, before giving up as
+/// offices and guests are fully booked
+const MAXIMUM_CONTRACT_ATTEMPTS: usize = 20;
+
+/// French standard VAT rate, in basis points
+const STANDARD_TAX_RATE: usize = 2000;
+
+/// How many of each entity to create, random counts being picked for users and offices when not
+/// given
+#[derive(Debug)]
+pub struct FixtureCounts {
+    pub users: Option<usize>,
+    pub offices: Option<usize>,
+    /// Offices subdivided in two
+    pub splits: usize,
+    pub contracts: usize,
+}
+
+/// What happens to the data already in database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingData {
+    /// Fixtures are added next to it
+    Keep,
+    /// Deleted first, which only happens on databases marked as development ones unless
+    /// `confirmed`
+    Delete { confirmed: bool },
+}
+
 pub async fn create_fixtures(
     pool: &PgPool,
     rng: &mut ThreadRng,
-    subdivide_offices: bool,
+    counts: FixtureCounts,
+    dual_roles: bool,
+    existing_data: ExistingData,
 ) -> color_eyre::Result<()> {
     log::info!("Creating database fixtures");
-    log::info!("First deleting existing data");
 
-    sqlx::query!("truncate table contracts, offices, users")
-        .execute(pool)
-        .await?;
+    let users_to_generate = counts
+        .users
+        .unwrap_or_else(|| rng.gen_range(MINIMUM_USERS_TO_GENERATE..=MAXIMUM_USERS_TO_GENERATE));
+    let offices_to_generate = counts.offices.unwrap_or_else(|| {
+        rng.gen_range(
+            MINIMUM_OFFICES_TO_GENERATE
+                ..=(MAXIMUM_OFFICES_TO_GENERATE - if counts.splits > 0 { 2 } else { 0 }),
+        )
+        .max(counts.splits)
+    });
 
-    log::info!("All existing data deleted");
+    if users_to_generate < MINIMUM_USERS_TO_GENERATE {
+        return Err(FixtureError::TooFewUsers(users_to_generate).into());
+    }
 
-    let users_to_generate = rng.gen_range(MINIMUM_USERS_TO_GENERATE..=MAXIMUM_USERS_TO_GENERATE);
-    let offices_to_generate = rng.gen_range(
-        MINIMUM_OFFICES_TO_GENERATE
-            ..=(MAXIMUM_OFFICES_TO_GENERATE - subdivide_offices.then_some(2).unwrap_or_default()),
-    );
+    if counts.splits > offices_to_generate {
+        return Err(FixtureError::TooManySplits {
+            splits: counts.splits,
+            offices: offices_to_generate,
+        }
+        .into());
+    }
+
+    let mut tx = pool.begin().await?;
+    prepare_database(&mut tx, existing_data).await?;
 
     let mut users = Vec::<User>::new();
     let mut offices = Vec::<Office>::new();
 
-    for _ in 0..users_to_generate {
+    for index in 0..users_to_generate {
+        // at least one host and one guest
+        let role = match index {
+            0 => Role::Host,
+            1 => Role::Guest,
+            _ if dual_roles => *[Role::Host, Role::Guest, Role::HostAndGuest]
+                .choose(rng)
+                .unwrap(),
+            _ => *[Role::Host, Role::Guest].choose(rng).unwrap(),
+        };
+
         let user = User::new(
             FirstName().fake_with_rng(rng),
             LastName().fake_with_rng(rng),
+            role,
         );
 
         users.push(user);
     }
 
-    for _ in 0..offices_to_generate {
-        let office = Office::new_real(
-            SecondaryAddress().fake_with_rng(rng),
-            format!(
-                "{} {}, {} {}",
-                BuildingNumber().fake_with_rng::<String, ThreadRng>(rng),
-                StreetName().fake_with_rng::<String, ThreadRng>(rng),
-                CityName().fake_with_rng::<String, ThreadRng>(rng),
-                ZipCode().fake_with_rng::<String, ThreadRng>(rng)
-            ),
-            rng.gen_range(-90.0..90.0),
-            rng.gen_range(-180.0..180.0),
-            *users.choose(rng).unwrap().id(),
-            rng.gen_range(80..180),
-            rng.gen_range(500..1000),
-            rng.gen_range(30000..80000),
-        )
-        .unwrap();
+    let hosts = users
+        .iter()
+        .filter(|user| user.role().can_host())
+        .collect::<Vec<_>>();
+
+    let cities = french_cities()?;
 
-        offices.push(office);
+    for _ in 0..offices_to_generate {
+        let owner = hosts.choose(rng).unwrap();
+        offices.push(random_office(rng, &cities, owner)?);
     }
 
     for user in users.iter() {
-        sqlx::query!(
-            r#"
-            insert into users (
-                id, created_at, first_name, last_name
-            ) values (
-                $1::uuid,
-                $2::timestamptz,
-                $3::varchar,
-                $4::varchar
-            );
-        "#,
-            user.id().uuid(),
-            user.created_at(),
-            user.first_name(),
-            user.last_name(),
-        )
-        .execute(pool)
-        .await?;
+        persist_user(user, &mut *tx).await?;
     }
 
     log::info!("Created {} users", users.len());
 
     for office in offices.iter() {
-        persist_office(office, pool).await?;
+        persist_office(office, &mut *tx).await?;
     }
 
     log::info!("Created {} offices", offices.len());
 
-    if subdivide_offices {
-        let office_to_subdivide = offices.choose(rng).unwrap();
+    let mut teams_count = 0;
+    for guest in users.iter().filter(|user| user.role().can_rent()) {
+        persist_team(&create_team(rng, guest)?, &mut *tx).await?;
+        teams_count += 1;
+    }
+
+    log::info!("Created {teams_count} guest teams");
+
+    let mut preferences_count = 0;
+    for guest in users.iter().filter(|user| user.role().can_rent()) {
+        if rng.gen_bool(GUEST_PREFERENCES_PROBABILITY) {
+            persist_guest_preferences(&create_guest_preferences(rng, guest, &offices)?, &mut *tx)
+                .await?;
+            preferences_count += 1;
+        }
+    }
+
+    log::info!("Created preferences for {preferences_count} guests");
+
+    persist_standard_tax_rule(&mut tx).await?;
+
+    let office_owners = offices
+        .iter()
+        .map(|office| *office.owner())
+        .collect::<HashSet<_>>();
+
+    let mut tax_opt_ins_count = 0;
+    for host in office_owners {
+        if rng.gen_bool(0.5) {
+            persist_tax_opt_in(&host, &mut *tx).await?;
+            tax_opt_ins_count += 1;
+        }
+    }
+
+    log::info!("{tax_opt_ins_count} hosts opted in to VAT");
+
+    let mut office_subdivisions = Vec::<Office>::new();
+    for office_to_subdivide in offices.choose_multiple(rng, counts.splits) {
         let (available_positions_1, available_positions_2) = (
             (*office_to_subdivide.available_positions() as f32 / 2.0).floor() as usize,
             (*office_to_subdivide.available_positions() as f32 / 2.0).ceil() as usize,
@@ -115,57 +213,455 @@ pub async fn create_fixtures(
             (*office_to_subdivide.surface() as f32 / 2.0).ceil() as usize,
         );
 
-        let office_subdivisions = office_to_subdivide.split(vec![
+        office_subdivisions.extend(office_to_subdivide.split(vec![
             OfficeSplit::new(available_positions_1, surface_1)?,
             OfficeSplit::new(available_positions_2, surface_2)?,
-        ])?;
-
-        let office_subdivisions_count = office_subdivisions.len();
-        for office_subdivision in office_subdivisions {
-            persist_office(&office_subdivision, pool).await?;
-        }
+        ])?);
+    }
 
-        log::info!("Created {office_subdivisions_count} office subdivisions");
+    for office_subdivision in office_subdivisions.iter() {
+        persist_office(office_subdivision, &mut *tx).await?;
     }
 
-    log::info!("Creating a fake contract");
+    log::info!("Created {} office subdivisions", office_subdivisions.len());
 
-    let (guest, office) = {
-        loop {
-            let guest = users.choose(rng).unwrap();
+    let guests = users
+        .iter()
+        .filter(|user| user.role().can_rent())
+        .collect::<Vec<_>>();
+    let rentable_offices = offices
+        .iter()
+        .chain(office_subdivisions.iter())
+        .collect::<Vec<_>>();
 
-            let office = offices
+    // new offices only, so that none of them is booked beyond its positions
+    let mut calendar = OfficeCalendar::new(rentable_offices.iter().copied());
+    let mut contracts_count = 0;
+
+    for _ in 0..counts.contracts {
+        let contract = (0..MAXIMUM_CONTRACT_ATTEMPTS).find_map(|_| {
+            let guest = guests.choose(rng)?;
+            let office = rentable_offices
                 .iter()
-                .rev()
-                .find(|office| office.owner() != guest.id());
+                .filter(|office| office.owner() != guest.id())
+                .choose(rng)?;
+            let host = hosts.iter().find(|host| host.id() == office.owner())?;
+
+            // starts no earlier than the shortest contract signed today would end
+            let start = Contract::minimum_end(Utc::now().date_naive())
+                .checked_add_days(Days::new(rng.gen_range(0..365 + 365 / 2)))
+                .unwrap();
+
+            let contract = Contract::negotiated_for_office(
+                office,
+                host,
+                guest,
+                rng.gen_range(1..=*office.available_positions()),
+                start,
+                Contract::minimum_end(start),
+                RentNegotiation::Discount(rng.gen_range(0..=MAXIMUM_FIXTURE_DISCOUNT)),
+            )
+            .ok()?;
+            calendar.book(&contract).ok()?;
+
+            Some(contract)
+        });
+
+        let Some(mut contract) = contract else {
+            log::warn!("No guest and office free at the same time left for another fake contract");
+            break;
+        };
+
+        contract.transition(
+            ContractState::Signed,
+            Utc::now(),
+            Some("Fixture".to_string()),
+        )?;
+
+        persist_contract(&contract, &mut *tx).await?;
+        contracts_count += 1;
+    }
+
+    tx.commit().await?;
+
+    log::info!("Created {contracts_count} fake contracts");
+
+    Ok(())
+}
+
+/// Creates exactly what the scenario describes, along with the standard tax rule
+pub async fn create_scenario_fixtures(
+    pool: &PgPool,
+    scenario: &Scenario,
+    existing_data: ExistingData,
+) -> color_eyre::Result<()> {
+    log::info!("Creating database fixtures from a scenario");
+
+    // built first, so that an invalid scenario leaves existing data untouched
+    let fixtures = scenario.build(Utc::now().date_naive(), Utc::now())?;
+
+    let mut tx = pool.begin().await?;
+    prepare_database(&mut tx, existing_data).await?;
+
+    for user in fixtures.users.iter() {
+        persist_user(user, &mut *tx).await?;
+    }
+
+    log::info!("Created {} users", fixtures.users.len());
+
+    for office in fixtures.offices.iter() {
+        persist_office(office, &mut *tx).await?;
+    }
+
+    log::info!("Created {} offices", fixtures.offices.len());
+
+    persist_standard_tax_rule(&mut tx).await?;
+
+    for contract in fixtures.contracts.iter() {
+        persist_contract(contract, &mut *tx).await?;
+    }
+
+    tx.commit().await?;
+
+    log::info!("Created {} contracts", fixtures.contracts.len());
+
+    Ok(())
+}
+
+/// Deletes existing data if asked to, and allowed to; Meant to run in the transaction creating the
+/// new data, so that existing data is only deleted along with it
+pub(crate) async fn prepare_database(
+    conn: &mut PgConnection,
+    existing_data: ExistingData,
+) -> color_eyre::Result<()> {
+    let ExistingData::Delete { confirmed } = existing_data else {
+        log::info!("Keeping existing data");
+        return Ok(());
+    };
 
-            if let Some(office) = office {
-                break (guest, office);
-            }
+    if !confirmed && !is_dev_database(&mut *conn).await? {
+        return Err(FixtureError::NotADevDatabase.into());
+    }
+
+    log::info!("First deleting existing data");
+
+    sqlx::query!(
+        "truncate table contract_amendments, contract_occupancies, contract_transitions, contracts, guest_preferences, office_windows, offices, simulation_run_extensions, simulation_runs, tax_opt_ins, tax_rules, team_headcounts, teams, users"
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    log::info!("All existing data deleted");
+
+    Ok(())
+}
+
+/// Unless tax rules already exist
+pub(crate) async fn persist_standard_tax_rule(conn: &mut PgConnection) -> color_eyre::Result<()> {
+    if !load_tax_rule_set(&mut *conn).await?.rules().is_empty() {
+        return Ok(());
+    }
+
+    persist_tax_rule(
+        &TaxRule::new(
+            STANDARD_TAX_RATE,
+            NaiveDate::from_ymd_opt(2014, 1, 1).unwrap(),
+            None,
+        )?,
+        conn,
+    )
+    .await?;
+
+    log::info!("Created the standard tax rule");
+
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum FixtureError {
+    #[error("At least {MINIMUM_USERS_TO_GENERATE} users are needed, a host and a guest, but {0} were asked for")]
+    TooFewUsers(usize),
+    #[error("The contract density ({0}) must be between 0 and 1")]
+    InvalidContractDensity(f64),
+    #[error("Cannot subdivide {splits} offices out of {offices}")]
+    TooManySplits { splits: usize, offices: usize },
+    #[error("Refusing to delete the data of a database not marked as a development one. Mark it with `insert into dev_database_marker default values`, keep its data with --append, or pass --yes-destroy-data")]
+    NotADevDatabase,
+}
+
+/// An office owned by `owner`, somewhere in one of the `cities`
+pub(crate) fn random_office<R: Rng>(
+    rng: &mut R,
+    cities: &[City],
+    owner: &User,
+) -> color_eyre::Result<Office> {
+    let city = cities.choose(rng).unwrap();
+    // uniformly spread over a disc around the city's center
+    let coordinates = city.center.offset(
+        MAXIMUM_DISTANCE_FROM_CITY_CENTER_KM * rng.gen::<f64>().sqrt(),
+        rng.gen_range(0.0..360.0),
+    );
+
+    Ok(Office::new_real(
+        SecondaryAddress().fake_with_rng(rng),
+        Address::french(
+            Some(BuildingNumber().fake_with_rng(rng)),
+            StreetName().fake_with_rng(rng),
+            city.postal_code.clone(),
+            city.name.clone(),
+        )?,
+        *coordinates.latitude(),
+        *coordinates.longitude(),
+        *owner.id(),
+        rng.gen_range(80..180),
+        rng.gen_range(500..1000),
+        rng.gen_range(30000..80000),
+    )?)
+}
+
+pub(crate) struct City {
+    name: String,
+    postal_code: String,
+    center: Coordinates,
+}
+
+/// Parses the bundled `name,postal_code,latitude,longitude` dataset
+pub(crate) fn french_cities() -> color_eyre::Result<Vec<City>> {
+    let mut lines = FRENCH_CITIES
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .skip(1);
+
+    lines.try_fold(Vec::new(), |mut cities, (line_number, line)| {
+        let malformed = || color_eyre::eyre::eyre!("Malformed city on line {line_number}: {line}");
+
+        let [name, postal_code, latitude, longitude] = line
+            .split(',')
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| malformed())?;
+
+        if !is_valid_french_postal_code(postal_code) {
+            return Err(malformed());
+        }
+
+        cities.push(City {
+            name: name.to_string(),
+            postal_code: postal_code.to_string(),
+            center: Coordinates::new(
+                latitude.parse().map_err(|_| malformed())?,
+                longitude.parse().map_err(|_| malformed())?,
+            )?,
+        });
+
+        Ok(cities)
+    })
+}
+
+/// A team growing (or shrinking) a little every year, from this year on
+fn create_team(rng: &mut ThreadRng, guest: &User) -> color_eyre::Result<Team> {
+    let this_year = Utc::now().year();
+    let mut people = rng.gen_range(MINIMUM_TEAM_HEADCOUNT..=MAXIMUM_TEAM_HEADCOUNT);
+
+    let headcounts = (this_year..this_year + 3)
+        .map(|year| {
+            let headcount = Headcount::new(NaiveDate::from_ymd_opt(year, 1, 1).unwrap(), people);
+            people = (people as f32 * rng.gen_range(0.8..2.0)).ceil() as usize;
+
+            headcount
+        })
+        .collect();
+
+    Ok(Team::new(
+        CompanyName().fake_with_rng(rng),
+        guest,
+        headcounts,
+    )?)
+}
+
+/// Some preferences, the location being around one of the offices
+fn create_guest_preferences(
+    rng: &mut ThreadRng,
+    guest: &User,
+    offices: &[Office],
+) -> color_eyre::Result<GuestPreferences> {
+    let strictness = |rng: &mut ThreadRng| {
+        if rng.gen_bool(REQUIRED_PREFERENCE_PROBABILITY) {
+            Strictness::Required
+        } else {
+            Strictness::Preferred
         }
     };
 
-    let start = rng
-        .gen_range(CONTRACT_DURATION_MINIMUM_DAYS..(365 * 2 - CONTRACT_DURATION_MINIMUM_DAYS))
-        as u64;
-    let end = start + CONTRACT_DURATION_MINIMUM_DAYS as u64;
-
-    let contract = Contract::new(
-        *office.owner(),
-        *guest.id(),
-        *office.id(),
-        office.position_price() * office.available_positions(),
-        Utc::now()
-            .checked_add_days(Days::new(start))
-            .unwrap()
-            .date_naive(),
-        Utc::now()
-            .checked_add_days(Days::new(end))
+    let location = match offices.choose(rng) {
+        Some(office) if rng.gen_bool(0.5) => Some(Preference::new(
+            Location::new(office.coordinates(), rng.gen_range(1.0..50.0)),
+            strictness(rng),
+        )),
+        _ => None,
+    };
+
+    let budget = rng.gen_bool(0.5).then(|| {
+        Preference::new(
+            rng.gen_range(MINIMUM_GUEST_BUDGET..=MAXIMUM_GUEST_BUDGET),
+            strictness(rng),
+        )
+    });
+
+    let office_size = rng.gen_bool(0.5).then(|| {
+        let min = rng.gen_range(1..=80);
+        Preference::new(
+            OfficeSize::new(min, rng.gen_range(min..=180)),
+            strictness(rng),
+        )
+    });
+
+    Ok(GuestPreferences::new(guest, location, budget, office_size)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_french_cities() {
+        let cities = french_cities().unwrap();
+
+        assert_eq!(53, cities.len());
+        // all in metropolitan France, but Saint-Denis of La Réunion
+        assert_eq!(
+            1,
+            cities
+                .iter()
+                .filter(|city| !(41.0..51.5).contains(city.center.latitude())
+                    || !(-5.5..10.0).contains(city.center.longitude()))
+                .count()
+        );
+    }
+}
+
+#[cfg(test)]
+mod guard_test {
+    use sqlx::PgPool;
+
+    use super::*;
+
+    fn counts() -> FixtureCounts {
+        FixtureCounts {
+            users: Some(3),
+            offices: Some(4),
+            splits: 2,
+            contracts: 3,
+        }
+    }
+
+    async fn count(table: &str, pool: &PgPool) -> i64 {
+        sqlx::query_scalar(&format!("select count(*) from {table}"))
+            .fetch_one(pool)
+            .await
             .unwrap()
-            .date_naive(),
-    )?;
+    }
 
-    persist_contract(&contract, &office, pool).await?;
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_only_deletes_marked_databases(pool: PgPool) {
+        let rng = &mut rand::thread_rng();
+        let delete = ExistingData::Delete { confirmed: false };
 
-    Ok(())
+        create_fixtures(&pool, rng, counts(), false, ExistingData::Keep)
+            .await
+            .unwrap();
+        assert_eq!(3, count("users", &pool).await);
+        assert_eq!(8, count("offices", &pool).await);
+        assert_eq!(1, count("tax_rules", &pool).await);
+
+        assert!(create_fixtures(&pool, rng, counts(), false, delete)
+            .await
+            .is_err());
+        assert_eq!(3, count("users", &pool).await);
+
+        create_fixtures(&pool, rng, counts(), false, ExistingData::Keep)
+            .await
+            .unwrap();
+        assert_eq!(6, count("users", &pool).await);
+        assert_eq!(1, count("tax_rules", &pool).await);
+
+        sqlx::query("insert into dev_database_marker default values")
+            .execute(&pool)
+            .await
+            .unwrap();
+        create_fixtures(&pool, rng, counts(), false, delete)
+            .await
+            .unwrap();
+        assert_eq!(3, count("users", &pool).await);
+
+        sqlx::query("truncate dev_database_marker")
+            .execute(&pool)
+            .await
+            .unwrap();
+        create_fixtures(
+            &pool,
+            rng,
+            counts(),
+            false,
+            ExistingData::Delete { confirmed: true },
+        )
+        .await
+        .unwrap();
+        assert_eq!(3, count("users", &pool).await);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_failures_keep_existing_data(pool: PgPool) {
+        let rng = &mut rand::thread_rng();
+        create_fixtures(&pool, rng, counts(), false, ExistingData::Keep)
+            .await
+            .unwrap();
+
+        // fails once existing data is deleted and the scenario's users and offices are inserted
+        sqlx::query(
+            "alter table contracts add constraint no_new_contracts check (false) not valid",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let scenario =
+            Scenario::from_toml(include_str!("../scenarios/split_office_fully_booked.toml"))
+                .unwrap();
+
+        assert!(create_scenario_fixtures(
+            &pool,
+            &scenario,
+            ExistingData::Delete { confirmed: true }
+        )
+        .await
+        .is_err());
+        assert_eq!(3, count("users", &pool).await);
+        assert_eq!(8, count("offices", &pool).await);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_invalid_counts(pool: PgPool) {
+        let rng = &mut rand::thread_rng();
+
+        for counts in [
+            FixtureCounts {
+                users: Some(1),
+                ..counts()
+            },
+            FixtureCounts {
+                splits: 5,
+                ..counts()
+            },
+        ] {
+            assert!(
+                create_fixtures(&pool, rng, counts, false, ExistingData::Keep)
+                    .await
+                    .is_err()
+            );
+        }
+        assert_eq!(0, count("users", &pool).await);
+    }
 }
@@ -0,0 +1,241 @@
+use std::time::Instant;
+
+use chrono::{Days, NaiveDate, Utc};
+use fake::{
+    faker::name::fr_fr::{FirstName, LastName},
+    Fake,
+};
+use rand::{seq::SliceRandom, Rng};
+use sora_model::{
+    calendar::OfficeCalendar,
+    contract::{Contract, ContractState},
+    office::Office,
+    user::{Role, User},
+};
+use sqlx::PgPool;
+
+use crate::{
+    fixtures::{
+        french_cities, persist_standard_tax_rule, prepare_database, random_office, ExistingData,
+        FixtureError,
+    },
+    persistence::{persist_contracts, persist_offices, persist_users},
+};
+
+/// Latest start of generated contracts, in days after today
+const MAXIMUM_CONTRACT_START_DAYS: u64 = 365;
+
+/// Users, offices and signed contracts in large numbers, e.g. to benchmark the simulation
+#[derive(Debug)]
+pub struct Dataset {
+    pub users: Vec<User>,
+    pub offices: Vec<Office>,
+    pub contracts: Vec<Contract>,
+}
+
+/// `users` hosts and guests, `offices` offices owned by the hosts and contracts for about
+/// `contract_density` of the offices, starting within a year from `today`. Contracts which would
+/// overlap another one of the same guest are left out
+pub fn generate_dataset<R: Rng>(
+    rng: &mut R,
+    users: usize,
+    offices: usize,
+    contract_density: f64,
+    today: NaiveDate,
+) -> color_eyre::Result<Dataset> {
+    if users < 2 {
+        return Err(FixtureError::TooFewUsers(users).into());
+    }
+
+    if !(0.0..=1.0).contains(&contract_density) {
+        return Err(FixtureError::InvalidContractDensity(contract_density).into());
+    }
+
+    let users = (0..users)
+        .map(|index| {
+            // at least one host and one guest
+            let role = match index {
+                0 => Role::Host,
+                1 => Role::Guest,
+                _ => *[Role::Host, Role::Guest].choose(rng).unwrap(),
+            };
+
+            User::new(
+                FirstName().fake_with_rng(rng),
+                LastName().fake_with_rng(rng),
+                role,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let (hosts, guests): (Vec<_>, Vec<_>) = users.iter().partition(|user| user.role().can_host());
+
+    let cities = french_cities()?;
+    let offices = (0..offices)
+        .map(|_| {
+            let owner = hosts.choose(rng).unwrap();
+            random_office(rng, &cities, owner)
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    let mut calendar = OfficeCalendar::new(offices.iter());
+    let mut contracts = Vec::new();
+
+    for office in offices.iter() {
+        if !rng.gen_bool(contract_density) {
+            continue;
+        }
+
+        let host = hosts
+            .iter()
+            .find(|host| host.id() == office.owner())
+            .unwrap();
+        let guest = guests.choose(rng).unwrap();
+        let start = today
+            .checked_add_days(Days::new(rng.gen_range(0..=MAXIMUM_CONTRACT_START_DAYS)))
+            .unwrap();
+
+        let mut contract = Contract::for_office(
+            office,
+            host,
+            guest,
+            rng.gen_range(1..=*office.available_positions()),
+            start,
+            Contract::minimum_end(start),
+        )?;
+
+        if calendar.book(&contract).is_err() {
+            continue;
+        }
+
+        contract.transition(
+            ContractState::Signed,
+            Utc::now(),
+            Some("Generated".to_string()),
+        )?;
+        contracts.push(contract);
+    }
+
+    Ok(Dataset {
+        users,
+        offices,
+        contracts,
+    })
+}
+
+/// Generates a dataset and writes it with batched inserts
+pub async fn generate(
+    pool: &PgPool,
+    rng: &mut impl Rng,
+    users: usize,
+    offices: usize,
+    contract_density: f64,
+    existing_data: ExistingData,
+) -> color_eyre::Result<()> {
+    let started = Instant::now();
+    let dataset = generate_dataset(
+        rng,
+        users,
+        offices,
+        contract_density,
+        Utc::now().date_naive(),
+    )?;
+
+    log::info!(
+        "Generated {} users, {} offices and {} contracts in {:?}",
+        dataset.users.len(),
+        dataset.offices.len(),
+        dataset.contracts.len(),
+        started.elapsed()
+    );
+
+    let mut tx = pool.begin().await?;
+    prepare_database(&mut tx, existing_data).await?;
+
+    let started = Instant::now();
+    persist_users(&dataset.users, &mut *tx).await?;
+    log::info!("Inserted users in {:?}", started.elapsed());
+
+    let started = Instant::now();
+    persist_offices(&dataset.offices, &mut *tx).await?;
+    log::info!("Inserted offices in {:?}", started.elapsed());
+
+    persist_standard_tax_rule(&mut tx).await?;
+
+    let started = Instant::now();
+    persist_contracts(&dataset.contracts, &mut *tx).await?;
+    log::info!("Inserted contracts in {:?}", started.elapsed());
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::rstest;
+    use sora_model::interval::IntervalSet;
+
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(0.5)]
+    #[case(1.0)]
+    fn test_generate_dataset(#[case] contract_density: f64) {
+        let dataset = generate_dataset(
+            &mut StdRng::seed_from_u64(42),
+            100,
+            40,
+            contract_density,
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(100, dataset.users.len());
+        assert_eq!(40, dataset.offices.len());
+        assert!(dataset.contracts.len() <= 40);
+        assert_eq!(contract_density == 0.0, dataset.contracts.is_empty());
+
+        for contract in dataset.contracts.iter() {
+            assert_eq!(ContractState::Signed, *contract.state());
+
+            let guest_contracts = dataset
+                .contracts
+                .iter()
+                .filter(|other| other.guest() == contract.guest())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                guest_contracts
+                    .iter()
+                    .map(|contract| contract.period().days())
+                    .sum::<usize>(),
+                guest_contracts
+                    .iter()
+                    .map(|contract| contract.period())
+                    .collect::<IntervalSet>()
+                    .total_days()
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(1, 0.5)]
+    #[case(10, -0.1)]
+    #[case(10, 1.5)]
+    fn test_invalid_dataset(#[case] users: usize, #[case] contract_density: f64) {
+        assert!(generate_dataset(
+            &mut StdRng::seed_from_u64(42),
+            users,
+            5,
+            contract_density,
+            today()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use chrono::NaiveDate;
+use sora_model::{
+    contract::ContractId,
+    geo::Distance,
+    indexation::RentIndex,
+    office::{OfficeId, OfficeWindowId, WindowKind},
+    tax::TaxRuleId,
+    user::UserId,
+};
+use std::{num::NonZeroUsize, path::PathBuf};
+
+use crate::{
+    demand::{parse_arrivals_per_month, ArrivalProcess, DurationDistribution},
+    exploration::Objective,
+    runs::SimulationRunId,
+    simulation::Strategy,
+};
+
+pub mod contract;
+pub mod demand;
+pub mod exploration;
+pub mod fixtures;
+pub mod generate;
+pub mod office;
+pub mod persistence;
+pub mod runs;
+pub mod scenario;
+pub mod show;
+pub mod simulation;
+pub mod tax;
+pub mod verify;
+
+pub const DEFAULT_INDEX_VALUES: &str = include_str!("../data/rent_indices.csv");
+
+#[derive(clap::Parser, Debug)]
+#[command(version)]
+pub struct CliArguments {
+    #[command(subcommand)]
+    pub subcommand: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    /// Create in-database fixtures; Will truncate existing data unless --append is given
+    CreateFixtures {
+        /// Whether or not to subdivide a generated office, same as `--splits 1`
+        #[arg(long, short)]
+        subdivide: bool,
+        /// Whether or not some generated users can both host and rent offices
+        #[arg(long)]
+        dual_roles: bool,
+        /// TOML file describing the exact users, offices, splits and contracts to create instead
+        /// of random ones (see `crates/cli/scenarios`)
+        #[arg(
+            long,
+            conflicts_with_all = ["subdivide", "dual_roles", "users", "offices", "splits", "contracts"]
+        )]
+        scenario: Option<PathBuf>,
+        /// Add fixtures next to the existing data instead of deleting it first
+        #[arg(long)]
+        append: bool,
+        /// Delete existing data even if the database is not marked as a development one
+        #[arg(long, conflicts_with = "append")]
+        yes_destroy_data: bool,
+        /// Users to generate, at least 2; Defaults to a random count
+        #[arg(long)]
+        users: Option<usize>,
+        /// Real offices to generate; Defaults to a random count
+        #[arg(long)]
+        offices: Option<usize>,
+        /// Generated offices to subdivide in two
+        #[arg(long, conflicts_with = "subdivide")]
+        splits: Option<usize>,
+        /// Signed contracts to generate, fewer being created once offices or guests are fully booked
+        #[arg(long, default_value_t = 1)]
+        contracts: usize,
+    },
+    /// Generate a large synthetic dataset, e.g. to see how the simulation and queries behave at
+    /// scale; Will truncate existing data unless --append is given
+    Generate {
+        #[arg(long)]
+        users: usize,
+        #[arg(long)]
+        offices: usize,
+        /// Share of the offices rented by a signed contract, from 0 to 1
+        #[arg(long, default_value_t = 0.5)]
+        contract_density: f64,
+        /// Add the dataset next to the existing data instead of deleting it first
+        #[arg(long)]
+        append: bool,
+        /// Delete existing data even if the database is not marked as a development one
+        #[arg(long, conflicts_with = "append")]
+        yes_destroy_data: bool,
+    },
+    /// View one or multiple entities
+    Show {
+        /// Select which data should be displayed. Can be an ID prefix ("usr", "ofc"), or a table
+        /// name ("users", "offices")
+        #[arg(long, short)]
+        filter: Option<String>,
+        /// Only show offices in this city
+        #[arg(long)]
+        city: Option<String>,
+        /// Only show offices whose postal code starts with this prefix (e.g. "75" for Paris)
+        #[arg(long)]
+        postal_code: Option<String>,
+    },
+    /// Simulate rentals for all the offices in database
+    #[command(args_conflicts_with_subcommands = true)]
+    Simulate {
+        #[command(subcommand)]
+        command: Option<SimulateCommand>,
+        /// Should generated contracts be persisted in database, as a simulation run they can be
+        /// discarded with
+        #[arg(long, short)]
+        persist: bool,
+        /// Name of the persisted run; Defaults to the day it ran
+        #[arg(long, requires = "persist")]
+        name: Option<String>,
+        /// Revise generated contracts yearly against this index ("ILAT", "ILC", "ICC")
+        #[arg(long, short)]
+        index: Option<RentIndex>,
+        /// CSV file of index values (`index,year,quarter,value`); Defaults to bundled sample values
+        #[arg(long)]
+        index_values: Option<PathBuf>,
+        /// Positions each guest reserves, guests sharing offices as long as positions are left
+        #[arg(long, default_value_t = 10)]
+        guest_positions: usize,
+        /// Simulations to run, keeping the best solution under the objective
+        #[arg(long, default_value = "1")]
+        runs: NonZeroUsize,
+        /// Spread runs across cores
+        #[arg(long)]
+        parallel: bool,
+        /// Seed guests are shuffled with, each run using the one following the previous run's;
+        /// Defaults to a random one for several runs, guests being taken in database order for a
+        /// single run
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Strategies runs go through in turn, e.g. "earliest,spread"
+        #[arg(
+            long = "strategy",
+            value_enum,
+            value_delimiter = ',',
+            default_value = "earliest"
+        )]
+        strategies: Vec<Strategy>,
+        /// What the best solution is chosen on
+        #[arg(long, value_enum, default_value_t = Objective::default())]
+        objective: Objective,
+        /*
+                /// Rental duration in months
+                #[arg(long, short, default_value_t = 24)]
+                duration: usize,
+        */
+    },
+    /// Move a contract through its lifecycle (draft -> signed -> active -> terminated, expired or
+    /// renewed), or amend its terms
+    Contract {
+        #[command(subcommand)]
+        command: ContractCommand,
+    },
+    /// Manage when offices are on the market
+    Office {
+        #[command(subcommand)]
+        command: OfficeCommand,
+    },
+    /// Manage the VAT rules applied to rents, and the hosts opting in to them
+    Tax {
+        #[command(subcommand)]
+        command: TaxCommand,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum SimulateCommand {
+    /// Monte Carlo simulation of guests arriving over time, each one being matched to an office as
+    /// they come, for capacity planning
+    Demand {
+        /// Runs to estimate occupancy, rejection and revenue over
+        #[arg(long, default_value = "200")]
+        runs: NonZeroUsize,
+        #[arg(long)]
+        parallel: bool,
+        /// Seed of the first run, each run using the one following the previous run's; Defaults
+        /// to a random one
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Months guests arrive over
+        #[arg(long, default_value_t = 24)]
+        months: u32,
+        #[arg(long, value_enum, default_value_t = ArrivalProcess::default())]
+        arrival_process: ArrivalProcess,
+        /// Average arrivals per month, at most 10000
+        #[arg(long, default_value_t = 4.0, value_parser = parse_arrivals_per_month)]
+        arrivals_per_month: f64,
+        /// Months each guest stays: "fixed:12", "uniform:4-24" or "exponential:12" (the mean),
+        /// at least the minimum contract duration
+        #[arg(long, default_value = "uniform:4-24")]
+        durations: DurationDistribution,
+        /// Positions each guest reserves
+        #[arg(long, default_value_t = 10)]
+        guest_positions: usize,
+    },
+    /// Check the stored contracts against the invariants of a simulation's solution: no office
+    /// booked beyond its positions, no real office booked along with its splits, no guest in two
+    /// offices at once, minimum durations, hosts owning their offices, contracts within the
+    /// simulated period and guests getting their target days
+    Verify {
+        /// First day of the simulated period (YYYY-MM-DD); Defaults to today
+        #[arg(long)]
+        start: Option<NaiveDate>,
+        /// Months of the simulated period
+        #[arg(long, default_value_t = 24)]
+        months: u32,
+        /// Verify over the period of this persisted run instead
+        #[arg(long, conflicts_with_all = ["start", "months"])]
+        run: Option<SimulationRunId>,
+        /// Only report guests missing their target days, without failing
+        #[arg(long)]
+        allow_missed_targets: bool,
+    },
+    /// List the simulation runs persisted with `simulate --persist`
+    Runs,
+    /// Parameters, outcome and contracts of a persisted simulation run
+    ShowRun { id: SimulationRunId },
+    /// Delete a persisted run's contracts and restore the contracts it extended, as long as its
+    /// contracts are still drafts
+    DiscardRun { id: SimulationRunId },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum ContractCommand {
+    /// Confirm a draft contract, e.g. one generated by a simulation
+    Sign {
+        id: ContractId,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// Mark a signed contract as started
+    Activate {
+        id: ContractId,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// Give notice to end an active contract before its end date, the office being freed once the
+    /// notice period is over
+    Terminate {
+        id: ContractId,
+        #[arg(long, short)]
+        reason: Option<String>,
+        #[arg(long, default_value_t = 3)]
+        notice_months: u32,
+        /// Months of rent owed for leaving early, capped by the rent left to pay
+        #[arg(long, default_value_t = 3)]
+        penalty_months: u32,
+        /// CSV file of index values the penalty of an indexed contract is revised with; Defaults
+        /// to bundled sample values
+        #[arg(long)]
+        index_values: Option<PathBuf>,
+    },
+    /// Mark an active contract as ended on its end date
+    Expire {
+        id: ContractId,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// Mark an active contract as ended and followed by a new one
+    Renew {
+        id: ContractId,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// Push a contract's end date back
+    Extend {
+        id: ContractId,
+        /// New end date (YYYY-MM-DD)
+        #[arg(long)]
+        until: NaiveDate,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// Change a contract's monthly rent, in cents, from the given date
+    ChangeRent {
+        id: ContractId,
+        #[arg(long)]
+        rent: usize,
+        #[arg(long)]
+        on: NaiveDate,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// Move a contract's guest to another office of the same real office, from the given date
+    Move {
+        id: ContractId,
+        #[arg(long)]
+        office: OfficeId,
+        #[arg(long)]
+        on: NaiveDate,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum OfficeCommand {
+    /// List an office over a period ("listing"), the office then only being rentable during its
+    /// listing periods, or take it off the market ("blackout"), e.g. for renovations
+    AddWindow {
+        office: OfficeId,
+        #[arg(long)]
+        kind: WindowKind,
+        /// First day of the window (YYYY-MM-DD)
+        #[arg(long)]
+        start: NaiveDate,
+        /// Day following the last day of the window (YYYY-MM-DD)
+        #[arg(long)]
+        end: NaiveDate,
+        #[arg(long, short)]
+        reason: Option<String>,
+    },
+    /// List the windows of every office, or of the given office
+    Windows {
+        #[arg(long)]
+        office: Option<OfficeId>,
+    },
+    RemoveWindow {
+        id: OfficeWindowId,
+    },
+    /// Offices around a location, nearest first, and when they have enough positions left
+    Near {
+        #[arg(long, allow_negative_numbers = true)]
+        lat: f32,
+        #[arg(long, allow_negative_numbers = true)]
+        lon: f32,
+        /// Farthest distance, e.g. "5km" or "500m"
+        #[arg(long, default_value = "5km")]
+        radius: Distance,
+        #[arg(long, default_value_t = 1)]
+        min_positions: usize,
+        /// Only look for offices in this city
+        #[arg(long)]
+        city: Option<String>,
+        /// Only look for offices whose postal code starts with this prefix
+        #[arg(long)]
+        postal_code: Option<String>,
+        /// First day of the period availability is looked for over (YYYY-MM-DD); Defaults to
+        /// today
+        #[arg(long)]
+        start: Option<NaiveDate>,
+        /// Day following the last day of the period (YYYY-MM-DD); Defaults to a year after its
+        /// start
+        #[arg(long)]
+        end: Option<NaiveDate>,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum TaxCommand {
+    /// Add a VAT rate, effective on dates no other rule is
+    AddRule {
+        /// Rate in basis points, e.g. 2000 for 20%
+        #[arg(long)]
+        rate: usize,
+        /// First day the rate applies on (YYYY-MM-DD)
+        #[arg(long)]
+        start: NaiveDate,
+        /// Day following the last day the rate applies on (YYYY-MM-DD); Applies until replaced
+        /// without one
+        #[arg(long)]
+        end: Option<NaiveDate>,
+    },
+    /// List the rules, and the hosts opted in to VAT
+    Rules,
+    RemoveRule {
+        id: TaxRuleId,
+    },
+    /// Have a host's rents taxed under the rules
+    OptIn {
+        host: UserId,
+    },
+    OptOut {
+        host: UserId,
+    },
+}
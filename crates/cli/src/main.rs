@@ -1,23 +1,42 @@
 // CLI usages examples
 // sora-cli create-fixtures
+// sora-cli create-fixtures --append --users 10 --offices 20 --splits 3 --contracts 15
+// sora-cli create-fixtures --scenario crates/cli/scenarios/guest_owns_every_office.toml
+// sora-cli generate --users 10000 --offices 2000 --contract-density 0.3
 // sora-cli show
 // sora-cli show --filter="user"
 // sora-cli show --filter="agr-22795DC7-E972-44D7-A74B-553EA6589044"
 // sora-cli simulate --duration 2
+// sora-cli simulate --runs 200 --parallel --strategy earliest,preferred,spread --seed 42
+// sora-cli simulate demand --runs 500 --arrivals-per-month 6 --durations exponential:12
+// sora-cli simulate verify --allow-missed-targets
+// sora-cli simulate --persist --name "Spread, ILC" --strategy spread --index ILC
+// sora-cli simulate runs
+// sora-cli simulate show-run sim-0192F3A4-6B1E-7C2D-9A8B-3E4F5A6B7C8D
+// sora-cli simulate discard-run sim-0192F3A4-6B1E-7C2D-9A8B-3E4F5A6B7C8D
 #![feature(iter_map_windows)]
+#![allow(unused_features)]
 
+use chrono::Utc;
 use clap::Parser;
-use fixtures::create_fixtures;
-use show::show;
-use simulation::simulate;
+use sora_cli::{
+    contract::contract,
+    demand::{demand, DemandModel},
+    exploration::Runs,
+    fixtures::{create_fixtures, create_scenario_fixtures, ExistingData, FixtureCounts},
+    generate::generate,
+    office::office,
+    runs::{discard_run, list_runs, show_run},
+    scenario::Scenario,
+    show::show,
+    simulation::simulate,
+    tax::tax,
+    verify::verify_stored,
+    CliArguments, Command, SimulateCommand, DEFAULT_INDEX_VALUES,
+};
+use sora_model::indexation::IndexTable;
 use sqlx::postgres::PgPool;
-use std::env;
-
-mod fixtures;
-mod persistence;
-mod range;
-mod show;
-mod simulation;
+use std::{env, fs};
 
 #[tokio::main]
 pub async fn main() -> color_eyre::Result<()> {
@@ -33,45 +52,161 @@ pub async fn main() -> color_eyre::Result<()> {
     let mut rng = rand::thread_rng();
 
     match args {
-        Command::CreateFixtures { subdivide } => create_fixtures(&pool, &mut rng, subdivide).await,
-        Command::Show { filter } => show(&pool, filter).await,
+        Command::CreateFixtures {
+            subdivide,
+            dual_roles,
+            scenario,
+            append,
+            yes_destroy_data,
+            users,
+            offices,
+            splits,
+            contracts,
+        } => {
+            let existing_data = if append {
+                ExistingData::Keep
+            } else {
+                ExistingData::Delete {
+                    confirmed: yes_destroy_data,
+                }
+            };
+
+            match scenario {
+                Some(path) => {
+                    create_scenario_fixtures(
+                        &pool,
+                        &Scenario::from_toml(&fs::read_to_string(path)?)?,
+                        existing_data,
+                    )
+                    .await
+                }
+                None => {
+                    let counts = FixtureCounts {
+                        users,
+                        offices,
+                        splits: splits.unwrap_or(if subdivide { 1 } else { 0 }),
+                        contracts,
+                    };
+
+                    create_fixtures(&pool, &mut rng, counts, dual_roles, existing_data).await
+                }
+            }
+        }
+        Command::Generate {
+            users,
+            offices,
+            contract_density,
+            append,
+            yes_destroy_data,
+        } => {
+            let existing_data = if append {
+                ExistingData::Keep
+            } else {
+                ExistingData::Delete {
+                    confirmed: yes_destroy_data,
+                }
+            };
+
+            generate(
+                &pool,
+                &mut rng,
+                users,
+                offices,
+                contract_density,
+                existing_data,
+            )
+            .await
+        }
+        Command::Show {
+            filter,
+            city,
+            postal_code,
+        } => show(&pool, filter, city, postal_code).await,
         Command::Simulate {
-            /* duration */ persist,
-        } => simulate(/*duration*/ 24, persist, &pool).await,
-    }
-}
+            command:
+                Some(SimulateCommand::Demand {
+                    runs,
+                    parallel,
+                    seed,
+                    months,
+                    arrival_process,
+                    arrivals_per_month,
+                    durations,
+                    guest_positions,
+                }),
+            ..
+        } => {
+            let model = DemandModel::new(
+                Utc::now().date_naive(),
+                months,
+                arrival_process,
+                arrivals_per_month,
+                durations,
+                guest_positions,
+            )?;
 
-#[derive(clap::Parser, Debug)]
-#[command(version)]
-pub struct CliArguments {
-    #[command(subcommand)]
-    subcommand: Command,
-}
+            demand(model, runs, seed, parallel, &pool).await
+        }
+        Command::Simulate {
+            command:
+                Some(SimulateCommand::Verify {
+                    start,
+                    months,
+                    run,
+                    allow_missed_targets,
+                }),
+            ..
+        } => verify_stored(start, months, run, allow_missed_targets, &pool).await,
+        Command::Simulate {
+            command: Some(SimulateCommand::Runs),
+            ..
+        } => list_runs(&pool).await,
+        Command::Simulate {
+            command: Some(SimulateCommand::ShowRun { id }),
+            ..
+        } => show_run(&pool, id).await,
+        Command::Simulate {
+            command: Some(SimulateCommand::DiscardRun { id }),
+            ..
+        } => discard_run(&pool, id).await,
+        Command::Simulate {
+            command: None,
+            /* duration */ persist,
+            name,
+            index,
+            index_values,
+            guest_positions,
+            runs,
+            parallel,
+            seed,
+            strategies,
+            objective,
+        } => {
+            let index_table = match index_values {
+                Some(path) => IndexTable::from_csv(&fs::read_to_string(path)?)?,
+                None => IndexTable::from_csv(DEFAULT_INDEX_VALUES)?,
+            };
 
-#[derive(clap::Subcommand, Debug)]
-pub enum Command {
-    /// Create in-database fixtures; Will truncate existing data
-    CreateFixtures {
-        /// Whether or not to subdivide generated offices
-        #[arg(long, short)]
-        subdivide: bool,
-    },
-    /// View one or multiple entities
-    Show {
-        /// Select which data should be displayed. Can be an ID prefix ("usr", "ofc"), or a table
-        /// name ("users", "offices")
-        #[arg(long, short)]
-        filter: Option<String>,
-    },
-    /// Simulate rentals for all the offices in database
-    Simulate {
-        /// Should generated contracts be persisted in database
-        #[arg(long, short)]
-        persist: bool,
-        /*
-                /// Rental duration in months
-                #[arg(long, short, default_value_t = 24)]
-                duration: usize,
-        */
-    },
+            simulate(
+                /*duration*/ 24,
+                persist,
+                name,
+                index,
+                index_table,
+                guest_positions,
+                Runs {
+                    count: runs,
+                    parallel,
+                    seed,
+                    strategies,
+                    objective,
+                },
+                &pool,
+            )
+            .await
+        }
+        Command::Contract { command } => contract(&pool, command).await,
+        Command::Office { command } => office(&pool, command).await,
+        Command::Tax { command } => tax(&pool, command).await,
+    }
 }
@@ -0,0 +1,187 @@
+use chrono::{Months, NaiveDate, Utc};
+use sora_model::{
+    geo::{Coordinates, Distance, SpatialIndex},
+    interval::DateRange,
+    office::{OfficeId, OfficeWindow, OfficeWindowId, WindowKind},
+};
+use sqlx::PgPool;
+
+use crate::{
+    persistence::{
+        delete_office_window, load_contracts, load_office, load_office_windows, load_offices,
+        persist_office_window,
+    },
+    simulation::seed_calendar,
+    OfficeCommand,
+};
+
+pub async fn office(pool: &PgPool, command: OfficeCommand) -> color_eyre::Result<()> {
+    match command {
+        OfficeCommand::AddWindow {
+            office,
+            kind,
+            start,
+            end,
+            reason,
+        } => add_window(pool, office, kind, DateRange::new(start, end), reason).await,
+        OfficeCommand::Windows { office } => windows(pool, office).await,
+        OfficeCommand::RemoveWindow { id } => remove_window(pool, id).await,
+        OfficeCommand::Near {
+            lat,
+            lon,
+            radius,
+            min_positions,
+            city,
+            postal_code,
+            start,
+            end,
+        } => {
+            let start = start.unwrap_or_else(|| Utc::now().date_naive());
+            let end = match end {
+                Some(end) => end,
+                None => start
+                    .checked_add_months(Months::new(12))
+                    .ok_or(OfficeCommandError::NoYearAfter(start))?,
+            };
+
+            near(
+                pool,
+                Coordinates::new(lat, lon)?,
+                radius,
+                min_positions,
+                city.as_deref(),
+                postal_code.as_deref(),
+                DateRange::new(start, end),
+            )
+            .await
+        }
+    }
+}
+
+async fn add_window(
+    pool: &PgPool,
+    office: OfficeId,
+    kind: WindowKind,
+    period: DateRange,
+    reason: Option<String>,
+) -> color_eyre::Result<()> {
+    let office = load_office(&office, pool)
+        .await?
+        .ok_or(OfficeCommandError::NoSuchOffice(office))?;
+
+    let window = OfficeWindow::new(&office, kind, period, reason)?;
+    persist_office_window(&window, pool).await?;
+
+    log::info!(
+        "Added {kind} window {} to office {} over {period}",
+        window.id(),
+        office.id()
+    );
+
+    if kind == WindowKind::Blackout {
+        for contract in load_contracts(*period.start(), *period.end(), pool)
+            .await?
+            .iter()
+            .filter(|contract| contract.office() == office.id())
+        {
+            log::warn!(
+                "Contract {} still occupies the office during the blackout",
+                contract.id()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn windows(pool: &PgPool, office: Option<OfficeId>) -> color_eyre::Result<()> {
+    for window in load_office_windows(office.as_ref(), pool).await? {
+        println!(
+            "{} {} of office {} over {}{}",
+            window.id(),
+            window.kind(),
+            window.office(),
+            window.period(),
+            window
+                .reason()
+                .as_ref()
+                .map(|reason| format!(" ({reason})"))
+                .unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+async fn remove_window(pool: &PgPool, id: OfficeWindowId) -> color_eyre::Result<()> {
+    if !delete_office_window(&id, pool).await? {
+        return Err(OfficeCommandError::NoSuchWindow(id).into());
+    }
+
+    log::info!("Removed window {id}");
+
+    Ok(())
+}
+
+async fn near(
+    pool: &PgPool,
+    center: Coordinates,
+    radius: Distance,
+    min_positions: usize,
+    city: Option<&str>,
+    postal_code: Option<&str>,
+    period: DateRange,
+) -> color_eyre::Result<()> {
+    if period.is_empty() {
+        return Err(OfficeCommandError::EmptyPeriod(period).into());
+    }
+
+    let offices = load_offices(pool).await?;
+
+    let contracts = load_contracts(*period.start(), *period.end(), pool).await?;
+    let windows = load_office_windows(None, pool).await?;
+    let calendar = seed_calendar(offices.iter(), contracts.iter(), windows.iter());
+
+    let index = offices
+        .iter()
+        .filter(|office| *office.available_positions() >= min_positions)
+        .filter(|office| office.address().matches(city, postal_code))
+        .map(|office| (office.coordinates(), office))
+        .collect::<SpatialIndex<_>>();
+
+    let nearby = index.within(&center, radius);
+    if nearby.is_empty() {
+        log::info!("No office of at least {min_positions} positions within {radius}");
+    }
+
+    for (distance, office) in nearby {
+        println!(
+            "Office {} ({}, {} positions), {distance} away",
+            office.id(),
+            office.address(),
+            office.available_positions()
+        );
+
+        let availabilities = calendar.office_availabilities(office.id(), min_positions, period)?;
+        if availabilities.is_empty() {
+            println!("> Never has {min_positions} positions left over {period}");
+        }
+        for availability in availabilities.ranges() {
+            println!("> Has {min_positions} positions left over {availability}");
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+enum OfficeCommandError {
+    #[error("Period {0} is empty")]
+    EmptyPeriod(DateRange),
+    #[error("No year of availabilities can follow {0}, pass an end date")]
+    NoYearAfter(NaiveDate),
+    #[error("No office found with id {0}")]
+    NoSuchOffice(OfficeId),
+    #[error("No window found with id {0}")]
+    NoSuchWindow(OfficeWindowId),
+}
@@ -1,14 +1,339 @@
-use sora_model::{contract::Contract, id::Identifier, office::Office, Object};
-use sqlx::{postgres::PgQueryResult, PgPool, Postgres};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    str::FromStr,
+};
 
-pub async fn persist_contract<'a, Executor>(
-    contract: &Contract,
-    office: &Office,
-    pool: Executor,
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use color_eyre::eyre::eyre;
+use sora_model::{
+    address::Address,
+    contract::{
+        AmendmentChange, Contract, ContractAmendment, ContractAmendmentId, ContractError,
+        ContractId, ContractState, ContractTransition, Termination,
+    },
+    geo::Coordinates,
+    id::Identifier,
+    indexation::{IndexationClause, IndexationError, Quarter, RentIndex},
+    interval::DateRange,
+    office::{
+        Office, OfficeId, OfficeSplitId, OfficeWindow, OfficeWindowId, RealOfficeId, WindowKind,
+    },
+    preference::{GuestPreferences, Location, OfficeSize, Preference, Strictness},
+    tax::{TaxRule, TaxRuleId, TaxRuleSet},
+    team::{Headcount, Team, TeamId},
+    user::{Role, User, UserId},
+    Object,
+};
+use sqlx::{postgres::PgQueryResult, PgConnection, PgPool, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    exploration::Objective,
+    runs::{value_name, RunChanges, RunExtension, SimulationRun, SimulationRunId},
+    simulation::Strategy,
+};
+
+pub async fn persist_user<'a, Connection>(
+    user: &User,
+    conn: Connection,
+) -> Result<PgQueryResult, sqlx::Error>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+
+    sqlx::query!(
+        r#"
+            insert into users (
+                id, created_at, first_name, last_name, role
+            ) values (
+                $1::uuid,
+                $2::timestamptz,
+                $3::varchar,
+                $4::varchar,
+                $5::varchar
+            );
+        "#,
+        user.id().uuid(),
+        user.created_at(),
+        user.first_name(),
+        user.last_name(),
+        user.role().to_string(),
+    )
+    .execute(&mut *conn)
+    .await
+}
+
+/// Rows inserted per query by batched inserts
+const BATCH_SIZE: usize = 5000;
+
+/// Inserts users by batches, in a single transaction
+pub async fn persist_users<'a, Connection>(
+    users: &[User],
+    conn: Connection,
+) -> Result<(), sqlx::Error>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut tx = conn.begin().await?;
+
+    for batch in users.chunks(BATCH_SIZE) {
+        sqlx::query!(
+            r#"
+            insert into users (id, created_at, first_name, last_name, role)
+            select * from unnest(
+                $1::uuid[],
+                $2::timestamptz[],
+                $3::varchar[],
+                $4::varchar[],
+                $5::varchar[]
+            )"#,
+            &batch
+                .iter()
+                .map(|user| *user.id().uuid())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|user| *user.created_at())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|user| user.first_name().clone())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|user| user.last_name().clone())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|user| user.role().to_string())
+                .collect::<Vec<_>>(),
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+pub async fn load_users(pool: &PgPool) -> color_eyre::Result<Vec<User>> {
+    sqlx::query!("select * from users order by created_at asc")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|user| {
+            Ok(User::new_unchecked(
+                UserId::from(user.id),
+                user.created_at,
+                user.first_name,
+                user.last_name,
+                Role::from_str(&user.role)?,
+            ))
+        })
+        .collect()
+}
+
+/// Inserts the team along with its headcounts
+pub async fn persist_team<'a, Connection>(team: &Team, conn: Connection) -> Result<(), sqlx::Error>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut tx = conn.begin().await?;
+
+    sqlx::query!(
+        r#"
+            insert into teams (
+                id, created_at, name, guest_id
+            ) values (
+                $1::uuid,
+                $2::timestamptz,
+                $3::varchar,
+                $4::uuid
+            );
+        "#,
+        team.uuid(),
+        team.created_at(),
+        team.name(),
+        team.guest().uuid(),
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for headcount in team.headcounts() {
+        sqlx::query!(
+            r#"
+                insert into team_headcounts (
+                    team_id, "from", people
+                ) values (
+                    $1::uuid,
+                    $2::date,
+                    $3::integer
+                );
+            "#,
+            team.uuid(),
+            headcount.from(),
+            *headcount.people() as i32,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+pub async fn load_teams(pool: &PgPool) -> Result<Vec<Team>, sqlx::Error> {
+    let mut headcounts = HashMap::<Uuid, Vec<Headcount>>::new();
+    for headcount in sqlx::query!(r#"select * from team_headcounts order by "from" asc"#)
+        .fetch_all(pool)
+        .await?
+    {
+        headcounts
+            .entry(headcount.team_id)
+            .or_default()
+            .push(Headcount::new(headcount.from, headcount.people as usize));
+    }
+
+    Ok(sqlx::query!("select * from teams order by created_at asc")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|team| {
+            Team::new_unchecked(
+                TeamId::from(team.id),
+                team.created_at,
+                team.name,
+                UserId::from(team.guest_id),
+                headcounts.remove(&team.id).unwrap_or_default(),
+            )
+        })
+        .collect())
+}
+
+pub async fn persist_guest_preferences<'a, Connection>(
+    preferences: &GuestPreferences,
+    conn: Connection,
 ) -> Result<PgQueryResult, sqlx::Error>
 where
-    Executor: sqlx::Executor<'a, Database = Postgres>,
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+    let is_required = |strictness: &Strictness| *strictness == Strictness::Required;
+    let location = preferences.location().as_ref();
+    let budget = preferences.budget().as_ref();
+    let office_size = preferences.office_size().as_ref();
+
+    sqlx::query!(
+        r#"
+            insert into guest_preferences (
+                guest_id,
+                latitude,
+                longitude,
+                max_distance_km,
+                location_required,
+                budget,
+                budget_required,
+                min_positions,
+                max_positions,
+                office_size_required
+            ) values (
+                $1::uuid,
+                $2::real,
+                $3::real,
+                $4::double precision,
+                $5::boolean,
+                $6::integer,
+                $7::boolean,
+                $8::integer,
+                $9::integer,
+                $10::boolean
+            );
+        "#,
+        preferences.guest().uuid(),
+        location.map(|location| *location.value().coordinates().latitude()),
+        location.map(|location| *location.value().coordinates().longitude()),
+        location.map(|location| *location.value().max_distance_km()),
+        location.map(|location| is_required(location.strictness())),
+        budget.map(|budget| *budget.value() as i32),
+        budget.map(|budget| is_required(budget.strictness())),
+        office_size.map(|size| *size.value().min() as i32),
+        office_size.map(|size| *size.value().max() as i32),
+        office_size.map(|size| is_required(size.strictness())),
+    )
+    .execute(&mut *conn)
+    .await
+}
+
+pub async fn load_guest_preferences(pool: &PgPool) -> Result<Vec<GuestPreferences>, sqlx::Error> {
+    let strictness = |required: bool| {
+        if required {
+            Strictness::Required
+        } else {
+            Strictness::Preferred
+        }
+    };
+
+    Ok(sqlx::query!("select * from guest_preferences")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let location = match (
+                row.latitude,
+                row.longitude,
+                row.max_distance_km,
+                row.location_required,
+            ) {
+                (Some(latitude), Some(longitude), Some(max_distance_km), Some(required)) => {
+                    Some(Preference::new(
+                        Location::new(
+                            Coordinates::new_unchecked(latitude, longitude),
+                            max_distance_km,
+                        ),
+                        strictness(required),
+                    ))
+                }
+                _ => None,
+            };
+            let budget = row
+                .budget
+                .zip(row.budget_required)
+                .map(|(budget, required)| Preference::new(budget as usize, strictness(required)));
+            let office_size = match (
+                row.min_positions,
+                row.max_positions,
+                row.office_size_required,
+            ) {
+                (Some(min), Some(max), Some(required)) => Some(Preference::new(
+                    OfficeSize::new(min as usize, max as usize),
+                    strictness(required),
+                )),
+                _ => None,
+            };
+
+            GuestPreferences::new_unchecked(
+                UserId::from(row.guest_id),
+                location,
+                budget,
+                office_size,
+            )
+        })
+        .collect())
+}
+
+/// Inserts the contract along with its transitions and amendments; Only its amended terms, state,
+/// termination and new transitions or amendments are written if it already exists
+pub async fn persist_contract<'a, Connection>(
+    contract: &Contract,
+    conn: Connection,
+) -> Result<(), PersistenceError>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
 {
+    let mut tx = conn.begin().await?;
+    let indexation = contract.indexation().as_ref();
+
     sqlx::query!(
         r#"
     insert into contracts (
@@ -17,9 +342,17 @@ where
         host_id,
         guest_id,
         office_id,
+        positions,
         rent,
         start,
-        "end"
+        "end",
+        indexation_index,
+        indexation_reference_year,
+        indexation_reference_quarter,
+        indexation_anniversary,
+        state,
+        terminated_on,
+        termination_penalty
     ) values (
         $1::uuid,
         $2::timestamptz,
@@ -27,30 +360,837 @@ where
         $4::uuid,
         $5::uuid,
         $6::integer,
-        $7::date,
-        $8::date
-    ) on conflict do nothing"#,
+        $7::integer,
+        $8::date,
+        $9::date,
+        $10::varchar,
+        $11::integer,
+        $12::integer,
+        $13::date,
+        $14::varchar,
+        $15::date,
+        $16::integer
+    ) on conflict (id) do update set
+        office_id = excluded.office_id,
+        rent = excluded.rent,
+        "end" = excluded."end",
+        state = excluded.state,
+        terminated_on = excluded.terminated_on,
+        termination_penalty = excluded.termination_penalty"#,
         *contract.uuid(),
         *contract.created_at(),
         *contract.host().uuid(),
         *contract.guest().uuid(),
         *contract.office().uuid(),
-        (office.available_positions() * office.position_price()) as i64,
+        integer(*contract.positions(), "positions")?,
+        integer(*contract.rent(), "rent")?,
         contract.start(),
         contract.end(),
+        indexation.map(|indexation| indexation.index().to_string()),
+        indexation.map(|indexation| indexation.reference().year()),
+        indexation.map(|indexation| indexation.reference().quarter() as i32),
+        indexation.map(|indexation| *indexation.anniversary()),
+        contract.state().to_string(),
+        contract.termination().map(|termination| *termination.on()),
+        contract
+            .termination()
+            .map(|termination| integer(*termination.penalty(), "termination_penalty"))
+            .transpose()?,
     )
-    .execute(pool)
+    .execute(&mut *tx)
     .await
+    .map_err(|err| PersistenceError::from_contract_error(err, contract))?;
+
+    for (position, transition) in contract.transitions().iter().enumerate() {
+        sqlx::query!(
+            r#"
+        insert into contract_transitions (
+            contract_id,
+            position,
+            "from",
+            "to",
+            at,
+            reason
+        ) values (
+            $1::uuid,
+            $2::integer,
+            $3::varchar,
+            $4::varchar,
+            $5::timestamptz,
+            $6::varchar
+        ) on conflict do nothing"#,
+            *contract.uuid(),
+            position as i32,
+            transition.from().to_string(),
+            transition.to().to_string(),
+            transition.at(),
+            transition.reason().as_deref(),
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (position, amendment) in contract.amendments().iter().enumerate() {
+        let (previous_end, end, previous_rent, rent, previous_office, office) =
+            match *amendment.change() {
+                AmendmentChange::Extension { previous_end, end } => {
+                    (Some(previous_end), Some(end), None, None, None, None)
+                }
+                AmendmentChange::Rent {
+                    previous_rent,
+                    rent,
+                } => (
+                    None,
+                    None,
+                    Some(integer(previous_rent, "previous_rent")?),
+                    Some(integer(rent, "rent")?),
+                    None,
+                    None,
+                ),
+                AmendmentChange::Office {
+                    previous_office,
+                    office,
+                } => (
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(*previous_office.uuid()),
+                    Some(*office.uuid()),
+                ),
+            };
+
+        sqlx::query!(
+            r#"
+        insert into contract_amendments (
+            id,
+            created_at,
+            contract_id,
+            position,
+            effective_on,
+            kind,
+            previous_end,
+            "end",
+            previous_rent,
+            rent,
+            previous_office_id,
+            office_id,
+            reason
+        ) values (
+            $1::uuid,
+            $2::timestamptz,
+            $3::uuid,
+            $4::integer,
+            $5::date,
+            $6::varchar,
+            $7::date,
+            $8::date,
+            $9::integer,
+            $10::integer,
+            $11::uuid,
+            $12::uuid,
+            $13::varchar
+        ) on conflict do nothing"#,
+            *amendment.uuid(),
+            *amendment.created_at(),
+            *contract.uuid(),
+            position as i32,
+            amendment.effective_on(),
+            amendment.change().kind(),
+            previous_end,
+            end,
+            previous_rent,
+            rent,
+            previous_office,
+            office,
+            amendment.reason().as_deref(),
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    persist_occupancies(&[contract], &mut tx).await?;
+
+    Ok(tx.commit().await?)
 }
 
-pub async fn persist_office(office: &Office, pool: &PgPool) -> Result<PgQueryResult, sqlx::Error> {
+/// Inserts new contracts and their transitions by batches, in a single transaction; Amended
+/// contracts are inserted one by one
+pub async fn persist_contracts<'a, Connection>(
+    contracts: &[Contract],
+    conn: Connection,
+) -> Result<(), PersistenceError>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut tx = conn.begin().await?;
+    let (amended, contracts) = contracts
+        .iter()
+        .partition::<Vec<_>, _>(|contract| !contract.amendments().is_empty());
+
+    for batch in contracts.chunks(BATCH_SIZE) {
+        let indexations = batch
+            .iter()
+            .map(|contract| contract.indexation().as_ref())
+            .collect::<Vec<_>>();
+
+        sqlx::query!(
+            r#"
+            insert into contracts (
+                id,
+                created_at,
+                host_id,
+                guest_id,
+                office_id,
+                positions,
+                rent,
+                start,
+                "end",
+                indexation_index,
+                indexation_reference_year,
+                indexation_reference_quarter,
+                indexation_anniversary,
+                state,
+                terminated_on,
+                termination_penalty
+            )
+            select * from unnest(
+                $1::uuid[],
+                $2::timestamptz[],
+                $3::uuid[],
+                $4::uuid[],
+                $5::uuid[],
+                $6::integer[],
+                $7::integer[],
+                $8::date[],
+                $9::date[],
+                $10::varchar[],
+                $11::integer[],
+                $12::integer[],
+                $13::date[],
+                $14::varchar[],
+                $15::date[],
+                $16::integer[]
+            )"#,
+            &batch
+                .iter()
+                .map(|contract| *contract.uuid())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| *contract.created_at())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| *contract.host().uuid())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| *contract.guest().uuid())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| *contract.office().uuid())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| integer(*contract.positions(), "positions"))
+                .collect::<Result<Vec<_>, _>>()?,
+            &batch
+                .iter()
+                .map(|contract| integer(*contract.rent(), "rent"))
+                .collect::<Result<Vec<_>, _>>()?,
+            &batch
+                .iter()
+                .map(|contract| *contract.start())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| *contract.end())
+                .collect::<Vec<_>>(),
+            &indexations
+                .iter()
+                .map(|indexation| indexation.map(|indexation| indexation.index().to_string()))
+                .collect::<Vec<_>>() as &[Option<String>],
+            &indexations
+                .iter()
+                .map(|indexation| indexation.map(|indexation| indexation.reference().year()))
+                .collect::<Vec<_>>() as &[Option<i32>],
+            &indexations
+                .iter()
+                .map(|indexation| {
+                    indexation.map(|indexation| indexation.reference().quarter() as i32)
+                })
+                .collect::<Vec<_>>() as &[Option<i32>],
+            &indexations
+                .iter()
+                .map(|indexation| indexation.map(|indexation| *indexation.anniversary()))
+                .collect::<Vec<_>>() as &[Option<NaiveDate>],
+            &batch
+                .iter()
+                .map(|contract| contract.state().to_string())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|contract| contract.termination().map(|termination| *termination.on()))
+                .collect::<Vec<_>>() as &[Option<NaiveDate>],
+            &batch
+                .iter()
+                .map(|contract| {
+                    contract
+                        .termination()
+                        .map(|termination| integer(*termination.penalty(), "termination_penalty"))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>, _>>()? as &[Option<i32>],
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let transitions = batch
+            .iter()
+            .flat_map(|contract| {
+                contract
+                    .transitions()
+                    .iter()
+                    .enumerate()
+                    .map(|(position, transition)| (*contract.uuid(), position as i32, transition))
+            })
+            .collect::<Vec<_>>();
+
+        sqlx::query!(
+            r#"
+            insert into contract_transitions (contract_id, position, "from", "to", at, reason)
+            select * from unnest(
+                $1::uuid[],
+                $2::integer[],
+                $3::varchar[],
+                $4::varchar[],
+                $5::timestamptz[],
+                $6::varchar[]
+            )"#,
+            &transitions
+                .iter()
+                .map(|(contract, _, _)| *contract)
+                .collect::<Vec<_>>(),
+            &transitions
+                .iter()
+                .map(|(_, position, _)| *position)
+                .collect::<Vec<_>>(),
+            &transitions
+                .iter()
+                .map(|(_, _, transition)| transition.from().to_string())
+                .collect::<Vec<_>>(),
+            &transitions
+                .iter()
+                .map(|(_, _, transition)| transition.to().to_string())
+                .collect::<Vec<_>>(),
+            &transitions
+                .iter()
+                .map(|(_, _, transition)| *transition.at())
+                .collect::<Vec<_>>(),
+            &transitions
+                .iter()
+                .map(|(_, _, transition)| transition.reason().clone())
+                .collect::<Vec<_>>() as &[Option<String>],
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        persist_occupancies(batch, &mut tx).await?;
+    }
+
+    for contract in amended {
+        persist_contract(contract, &mut *tx).await?;
+    }
+
+    Ok(tx.commit().await?)
+}
+
+/// Replaces the offices the contracts occupy over time, see the `contract_occupancies` table;
+/// Constraints violations are only mapped for a single contract
+async fn persist_occupancies(
+    contracts: &[&Contract],
+    conn: &mut PgConnection,
+) -> Result<(), PersistenceError> {
+    let occupancies = contracts
+        .iter()
+        .flat_map(|contract| {
+            contract
+                .occupancies()
+                .into_iter()
+                .map(|(office, period)| (*contract, office, period))
+        })
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        "delete from contract_occupancies where contract_id = any($1::uuid[])",
+        &contracts
+            .iter()
+            .map(|contract| *contract.uuid())
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *conn)
+    .await?;
+
     sqlx::query!(
+        r#"
+        insert into contract_occupancies (
+            contract_id, office_id, real_office_id, is_split_office, positions, during
+        )
+        select
+            occupancies.contract_id,
+            occupancies.office_id,
+            coalesce(offices.parent_office_id, offices.id),
+            offices.parent_office_id is not null,
+            occupancies.positions,
+            daterange(occupancies."from", occupancies.until)
+        from unnest($1::uuid[], $2::uuid[], $3::integer[], $4::date[], $5::date[])
+            as occupancies(contract_id, office_id, positions, "from", until)
+        inner join offices on offices.id = occupancies.office_id"#,
+        &occupancies
+            .iter()
+            .map(|(contract, _, _)| *contract.uuid())
+            .collect::<Vec<_>>(),
+        &occupancies
+            .iter()
+            .map(|(_, office, _)| *office.uuid())
+            .collect::<Vec<_>>(),
+        &occupancies
+            .iter()
+            .map(|(contract, _, _)| integer(*contract.positions(), "positions"))
+            .collect::<Result<Vec<_>, _>>()?,
+        &occupancies
+            .iter()
+            .map(|(_, _, period)| *period.start())
+            .collect::<Vec<_>>(),
+        &occupancies
+            .iter()
+            .map(|(_, _, period)| *period.end())
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|err| match contracts {
+        [contract] => PersistenceError::from_contract_error(err, contract),
+        _ => PersistenceError::Database(err),
+    })?;
+
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PersistenceError {
+    #[error("Contract {0} overlaps another contract of the same guest")]
+    GuestOverlap(ContractId),
+    #[error("Contract {0} books an office while its real office or one of its splits is booked")]
+    RelatedOfficeOverlap(ContractId),
+    #[error("Contract {0} books more positions than its office has left")]
+    NotEnoughPositions(ContractId),
+    #[error("{value} is too large to be stored as {column}")]
+    OutOfRange { column: &'static str, value: usize },
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl PersistenceError {
+    /// Maps the `contracts` and `contract_occupancies` tables constraints violations
+    fn from_contract_error(err: sqlx::Error, contract: &Contract) -> Self {
+        match err
+            .as_database_error()
+            .and_then(|database_error| database_error.constraint())
+        {
+            Some("contracts_guest_overlap") => Self::GuestOverlap(*contract.id()),
+            Some("contract_occupancies_related_overlap") => {
+                Self::RelatedOfficeOverlap(*contract.id())
+            }
+            Some("contract_occupancies_capacity") => Self::NotEnoughPositions(*contract.id()),
+            _ => Self::Database(err),
+        }
+    }
+}
+
+/// Integer columns are 32 bits, larger values are rejected rather than wrapped around
+fn integer(value: usize, column: &'static str) -> Result<i32, PersistenceError> {
+    i32::try_from(value).map_err(|_| PersistenceError::OutOfRange { column, value })
+}
+
+/// Columns selected by contract queries, see [`load_contracts`]
+struct ContractRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    host_id: Uuid,
+    guest_id: Uuid,
+    office_id: Uuid,
+    is_split_office: bool,
+    positions: i32,
+    rent: i32,
+    start: NaiveDate,
+    end: NaiveDate,
+    indexation_index: Option<String>,
+    indexation_reference_year: Option<i32>,
+    indexation_reference_quarter: Option<i32>,
+    indexation_anniversary: Option<NaiveDate>,
+    state: String,
+    terminated_on: Option<NaiveDate>,
+    termination_penalty: Option<i32>,
+}
+
+impl ContractRow {
+    fn into_contract(
+        self,
+        transitions: Vec<ContractTransition>,
+        amendments: Vec<ContractAmendment>,
+    ) -> color_eyre::Result<Contract> {
+        Ok(Contract::new_unchecked(
+            ContractId::from(self.id),
+            self.created_at,
+            self.host_id.into(),
+            self.guest_id.into(),
+            if self.is_split_office {
+                OfficeId::OfficeSplit(self.office_id.into())
+            } else {
+                OfficeId::RealOffice(self.office_id.into())
+            },
+            self.positions as usize,
+            self.rent as usize,
+            self.start,
+            self.end,
+            indexation_from_columns(
+                self.indexation_index,
+                self.indexation_reference_year,
+                self.indexation_reference_quarter,
+                self.indexation_anniversary,
+            )?,
+            ContractState::from_str(&self.state)?,
+            transitions,
+            self.terminated_on
+                .zip(self.termination_penalty)
+                .map(|(on, penalty)| Termination::new_unchecked(on, penalty as usize)),
+            amendments,
+        ))
+    }
+}
+
+/// Fetches the transitions and amendments of the given contracts rows
+async fn contracts_from_rows(
+    rows: Vec<ContractRow>,
+    pool: &PgPool,
+) -> color_eyre::Result<Vec<Contract>> {
+    let ids = rows.iter().map(|row| row.id).collect::<Vec<_>>();
+
+    let mut transitions = HashMap::<Uuid, Vec<ContractTransition>>::new();
+    for transition in sqlx::query!(
+        r#"
+        select * from contract_transitions
+        where contract_id = any($1::uuid[])
+        order by contract_id, position asc"#,
+        &ids,
+    )
+    .fetch_all(pool)
+    .await?
+    {
+        transitions.entry(transition.contract_id).or_default().push(
+            ContractTransition::new_unchecked(
+                ContractState::from_str(&transition.from)?,
+                ContractState::from_str(&transition.to)?,
+                transition.at,
+                transition.reason,
+            ),
+        );
+    }
+
+    let mut amendments = HashMap::<Uuid, Vec<ContractAmendment>>::new();
+    for amendment in sqlx::query!(
+        r#"
+        select
+            contract_amendments.*,
+            previous_office.parent_office_id is not null as "previous_office_is_split!",
+            office.parent_office_id is not null as "office_is_split!"
+        from contract_amendments
+        left join offices as previous_office
+            on previous_office.id = contract_amendments.previous_office_id
+        left join offices as office on office.id = contract_amendments.office_id
+        where contract_id = any($1::uuid[])
+        order by contract_id, position asc"#,
+        &ids,
+    )
+    .fetch_all(pool)
+    .await?
+    {
+        let office_id = |id: Uuid, is_split: bool| {
+            if is_split {
+                OfficeId::OfficeSplit(id.into())
+            } else {
+                OfficeId::RealOffice(id.into())
+            }
+        };
+
+        let change = match amendment.kind.as_str() {
+            "extension" => amendment
+                .previous_end
+                .zip(amendment.end)
+                .map(|(previous_end, end)| AmendmentChange::Extension { previous_end, end }),
+            "rent" => amendment
+                .previous_rent
+                .zip(amendment.rent)
+                .map(|(previous_rent, rent)| AmendmentChange::Rent {
+                    previous_rent: previous_rent as usize,
+                    rent: rent as usize,
+                }),
+            "office" => amendment.previous_office_id.zip(amendment.office_id).map(
+                |(previous_office, office)| AmendmentChange::Office {
+                    previous_office: office_id(previous_office, amendment.previous_office_is_split),
+                    office: office_id(office, amendment.office_is_split),
+                },
+            ),
+            _ => None,
+        }
+        .ok_or_else(|| ContractError::UnknownAmendmentKind(amendment.kind.clone()))?;
+
+        amendments.entry(amendment.contract_id).or_default().push(
+            ContractAmendment::new_unchecked(
+                ContractAmendmentId::from(amendment.id),
+                amendment.created_at,
+                amendment.effective_on,
+                change,
+                amendment.reason,
+            ),
+        );
+    }
+
+    rows.into_iter()
+        .map(|row| {
+            let contract_transitions = transitions.remove(&row.id).unwrap_or_default();
+            let contract_amendments = amendments.remove(&row.id).unwrap_or_default();
+
+            row.into_contract(contract_transitions, contract_amendments)
+        })
+        .collect()
+}
+
+pub async fn load_contract(id: &ContractId, pool: &PgPool) -> color_eyre::Result<Option<Contract>> {
+    let rows = sqlx::query_as!(
+        ContractRow,
+        r#"
+        select
+            contracts.id,
+            contracts.created_at,
+            host_id,
+            guest_id,
+            office_id,
+            offices.parent_office_id is not null as "is_split_office!",
+            positions,
+            rent,
+            start,
+            "end",
+            indexation_index,
+            indexation_reference_year,
+            indexation_reference_quarter,
+            indexation_anniversary,
+            state,
+            terminated_on,
+            termination_penalty
+        from contracts
+        inner join offices on offices.id = contracts.office_id
+        where contracts.id = $1::uuid"#,
+        id.uuid(),
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(contracts_from_rows(rows, pool).await?.pop())
+}
+
+/// Contracts running at some point between the given dates
+pub async fn load_contracts(
+    start: NaiveDate,
+    end: NaiveDate,
+    pool: &PgPool,
+) -> color_eyre::Result<Vec<Contract>> {
+    let rows = sqlx::query_as!(
+        ContractRow,
+        r#"
+        select
+            contracts.id,
+            contracts.created_at,
+            host_id,
+            guest_id,
+            office_id,
+            offices.parent_office_id is not null as "is_split_office!",
+            positions,
+            rent,
+            start,
+            "end",
+            indexation_index,
+            indexation_reference_year,
+            indexation_reference_quarter,
+            indexation_anniversary,
+            state,
+            terminated_on,
+            termination_penalty
+        from contracts
+        inner join offices on offices.id = contracts.office_id
+        where start < $2::date AND "end" > $1::date
+        order by start asc"#,
+        start,
+        end,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    contracts_from_rows(rows, pool).await
+}
+
+/// Indexation clauses are stored over 4 nullable columns, all set or none
+fn indexation_from_columns(
+    index: Option<String>,
+    reference_year: Option<i32>,
+    reference_quarter: Option<i32>,
+    anniversary: Option<NaiveDate>,
+) -> Result<Option<IndexationClause>, IndexationError> {
+    let (Some(index), Some(reference_year), Some(reference_quarter), Some(anniversary)) =
+        (index, reference_year, reference_quarter, anniversary)
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(IndexationClause::new(
+        RentIndex::from_str(&index)?,
+        Quarter::new(reference_year, reference_quarter as u32)?,
+        anniversary,
+    )))
+}
+
+/// Columns selected by office queries, see [`load_offices`]
+struct OfficeRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    name: String,
+    address_number: Option<String>,
+    address_street: String,
+    address_postal_code: String,
+    address_city: String,
+    address_country: String,
+    latitude: f64,
+    longitude: f64,
+    owner_id: Uuid,
+    available_positions: i32,
+    surface: i32,
+    position_price: i32,
+    parent_office_id: Option<Uuid>,
+}
+
+impl OfficeRow {
+    fn into_office(self) -> Office {
+        // office is split if it has a parent
+        let id = self
+            .parent_office_id
+            .map(|_| OfficeId::OfficeSplit(OfficeSplitId::from(self.id)))
+            .unwrap_or_else(|| OfficeId::RealOffice(RealOfficeId::from(self.id)));
+
+        Office::new_unchecked(
+            id,
+            self.created_at,
+            self.name,
+            Address::new_unchecked(
+                self.address_number,
+                self.address_street,
+                self.address_postal_code,
+                self.address_city,
+                self.address_country,
+            ),
+            self.latitude as f32,
+            self.longitude as f32,
+            UserId::from(self.owner_id),
+            self.available_positions as usize,
+            self.surface as usize,
+            self.position_price as usize,
+            self.parent_office_id.map(RealOfficeId::from),
+        )
+    }
+}
+
+pub async fn load_office(id: &OfficeId, pool: &PgPool) -> Result<Option<Office>, sqlx::Error> {
+    let office = sqlx::query_as!(
+        OfficeRow,
+        r#"
+        select
+            id,
+            created_at,
+            name,
+            address_number,
+            address_street,
+            address_postal_code,
+            address_city,
+            address_country,
+            latitude,
+            longitude,
+            owner_id,
+            available_positions,
+            surface,
+            position_price,
+            parent_office_id
+        from offices
+        where id = $1::uuid"#,
+        id.uuid()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(office.map(OfficeRow::into_office))
+}
+
+/// Real offices, including subdivided ones, and their splits
+pub async fn load_offices(pool: &PgPool) -> Result<Vec<Office>, sqlx::Error> {
+    let offices = sqlx::query_as!(
+        OfficeRow,
+        r#"
+        select
+            id,
+            created_at,
+            name,
+            address_number,
+            address_street,
+            address_postal_code,
+            address_city,
+            address_country,
+            latitude,
+            longitude,
+            owner_id,
+            available_positions,
+            surface,
+            position_price,
+            parent_office_id
+        from offices
+        order by created_at asc"#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(offices.into_iter().map(OfficeRow::into_office).collect())
+}
+
+pub async fn persist_office<'a, Connection>(
+    office: &Office,
+    conn: Connection,
+) -> Result<PgQueryResult, PersistenceError>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+
+    Ok(sqlx::query!(
         r#"
             insert into offices (
                 id,
                 created_at,
                 name,
-                address,
+                address_number,
+                address_street,
+                address_postal_code,
+                address_city,
+                address_country,
                 longitude,
                 latitude,
                 owner_id,
@@ -63,27 +1203,1484 @@ pub async fn persist_office(office: &Office, pool: &PgPool) -> Result<PgQueryRes
                 $2::timestamptz,
                 $3::varchar,
                 $4::varchar,
-                $5::float,
-                $6::float,
-                $7::uuid,
-                $8::integer,
-                $9::integer,
-                $10::integer,
-                $11::uuid
+                $5::varchar,
+                $6::varchar,
+                $7::varchar,
+                $8::varchar,
+                $9::float,
+                $10::float,
+                $11::uuid,
+                $12::integer,
+                $13::integer,
+                $14::integer,
+                $15::uuid
             );
         "#,
         office.uuid(),
         office.created_at(),
         office.name(),
-        office.address(),
+        office.address().number().as_deref(),
+        office.address().street(),
+        office.address().postal_code(),
+        office.address().city(),
+        office.address().country(),
         *office.longitude() as f64,
         *office.latitude() as f64,
         office.owner().uuid(),
-        *office.available_positions() as i32,
-        *office.surface() as i32,
-        *office.position_price() as i32,
+        integer(*office.available_positions(), "available_positions")?,
+        integer(*office.surface(), "surface")?,
+        integer(*office.position_price(), "position_price")?,
         office.parent_office().map(|id| *id.uuid()),
     )
+    .execute(&mut *conn)
+    .await?)
+}
+
+/// Inserts offices by batches, in a single transaction; Real offices must come before their splits
+pub async fn persist_offices<'a, Connection>(
+    offices: &[Office],
+    conn: Connection,
+) -> Result<(), PersistenceError>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut tx = conn.begin().await?;
+
+    for batch in offices.chunks(BATCH_SIZE) {
+        let column = |value: fn(&Office) -> String| batch.iter().map(value).collect::<Vec<_>>();
+        let integers = |value: fn(&Office) -> usize, column| {
+            batch
+                .iter()
+                .map(|office| integer(value(office), column))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        sqlx::query!(
+            r#"
+            insert into offices (
+                id,
+                created_at,
+                name,
+                address_number,
+                address_street,
+                address_postal_code,
+                address_city,
+                address_country,
+                longitude,
+                latitude,
+                owner_id,
+                available_positions,
+                surface,
+                position_price,
+                parent_office_id
+            )
+            select * from unnest(
+                $1::uuid[],
+                $2::timestamptz[],
+                $3::varchar[],
+                $4::varchar[],
+                $5::varchar[],
+                $6::varchar[],
+                $7::varchar[],
+                $8::varchar[],
+                $9::float[],
+                $10::float[],
+                $11::uuid[],
+                $12::integer[],
+                $13::integer[],
+                $14::integer[],
+                $15::uuid[]
+            )"#,
+            &batch
+                .iter()
+                .map(|office| *office.uuid())
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|office| *office.created_at())
+                .collect::<Vec<_>>(),
+            &column(|office| office.name().clone()),
+            &batch
+                .iter()
+                .map(|office| office.address().number().clone())
+                .collect::<Vec<_>>() as &[Option<String>],
+            &column(|office| office.address().street().clone()),
+            &column(|office| office.address().postal_code().clone()),
+            &column(|office| office.address().city().clone()),
+            &column(|office| office.address().country().clone()),
+            &batch
+                .iter()
+                .map(|office| *office.longitude() as f64)
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|office| *office.latitude() as f64)
+                .collect::<Vec<_>>(),
+            &batch
+                .iter()
+                .map(|office| *office.owner().uuid())
+                .collect::<Vec<_>>(),
+            &integers(
+                |office| *office.available_positions(),
+                "available_positions"
+            )?,
+            &integers(|office| *office.surface(), "surface")?,
+            &integers(|office| *office.position_price(), "position_price")?,
+            &batch
+                .iter()
+                .map(|office| office.parent_office().map(|id| *id.uuid()))
+                .collect::<Vec<_>>() as &[Option<Uuid>],
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    Ok(tx.commit().await?)
+}
+
+pub async fn persist_office_window(
+    window: &OfficeWindow,
+    pool: &PgPool,
+) -> Result<PgQueryResult, sqlx::Error> {
+    sqlx::query!(
+        r#"
+            insert into office_windows (
+                id, created_at, office_id, kind, start, "end", reason
+            ) values (
+                $1::uuid,
+                $2::timestamptz,
+                $3::uuid,
+                $4::varchar,
+                $5::date,
+                $6::date,
+                $7::varchar
+            );
+        "#,
+        window.uuid(),
+        window.created_at(),
+        window.office().uuid(),
+        window.kind().to_string(),
+        window.period().start(),
+        window.period().end(),
+        window.reason().as_deref(),
+    )
     .execute(pool)
     .await
 }
+
+/// Windows of every office, or of the given office only
+pub async fn load_office_windows(
+    office: Option<&OfficeId>,
+    pool: &PgPool,
+) -> color_eyre::Result<Vec<OfficeWindow>> {
+    sqlx::query!(
+        r#"
+        select
+            office_windows.id,
+            office_windows.created_at,
+            office_id,
+            offices.parent_office_id is not null as "is_split_office!",
+            kind,
+            start,
+            "end",
+            reason
+        from office_windows
+        inner join offices on offices.id = office_windows.office_id
+        where $1::uuid is null or office_id = $1::uuid
+        order by start asc"#,
+        office.map(OfficeId::uuid),
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(OfficeWindow::new_unchecked(
+            OfficeWindowId::from(row.id),
+            row.created_at,
+            if row.is_split_office {
+                OfficeId::OfficeSplit(row.office_id.into())
+            } else {
+                OfficeId::RealOffice(row.office_id.into())
+            },
+            WindowKind::from_str(&row.kind)?,
+            DateRange::new(row.start, row.end),
+            row.reason,
+        ))
+    })
+    .collect()
+}
+
+/// Whether the window existed
+pub async fn delete_office_window(id: &OfficeWindowId, pool: &PgPool) -> Result<bool, sqlx::Error> {
+    Ok(
+        sqlx::query!("delete from office_windows where id = $1::uuid", id.uuid())
+            .execute(pool)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
+
+/// Stores the run along with the contracts it created or lengthened, in a single transaction
+pub async fn persist_simulation_run(
+    run: &SimulationRun,
+    changes: &RunChanges,
+    contracts: &[Contract],
+    pool: &PgPool,
+) -> Result<(), PersistenceError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+            insert into simulation_runs (
+                id,
+                created_at,
+                name,
+                start,
+                months,
+                guest_positions,
+                indexation_index,
+                strategy,
+                seed,
+                objective,
+                runs,
+                score,
+                feasible,
+                created_contracts,
+                extended_contracts
+            ) values (
+                $1::uuid,
+                $2::timestamptz,
+                $3::varchar,
+                $4::date,
+                $5::integer,
+                $6::integer,
+                $7::varchar,
+                $8::varchar,
+                $9::bigint,
+                $10::varchar,
+                $11::integer,
+                $12::double precision,
+                $13::boolean,
+                $14::integer,
+                $15::integer
+            )"#,
+        run.id.uuid(),
+        run.created_at,
+        run.name,
+        run.start,
+        run.months as i32,
+        run.guest_positions as i32,
+        run.indexation.map(|index| index.to_string()),
+        value_name(&run.strategy),
+        run.seed.map(|seed| seed as i64),
+        value_name(&run.objective),
+        run.runs.get() as i32,
+        run.score,
+        run.feasible,
+        changes.created.len() as i32,
+        changes.extensions.len() as i32,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for contract in contracts
+        .iter()
+        .filter(|contract| changes.touches(contract))
+    {
+        persist_contract(contract, &mut *tx).await?;
+    }
+
+    sqlx::query!(
+        "update contracts set simulation_run_id = $1::uuid where id = any($2::uuid[])",
+        run.id.uuid(),
+        &changes
+            .created
+            .iter()
+            .map(|id| *id.uuid())
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "update contract_amendments set simulation_run_id = $1::uuid where id = any($2::uuid[])",
+        run.id.uuid(),
+        &changes
+            .amendments
+            .iter()
+            .map(|id| *id.uuid())
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+            insert into simulation_run_extensions (
+                simulation_run_id, contract_id, previous_end, "end"
+            ) select $1::uuid, * from unnest($2::uuid[], $3::date[], $4::date[])"#,
+        run.id.uuid(),
+        &changes
+            .extensions
+            .iter()
+            .map(|extension| *extension.contract.uuid())
+            .collect::<Vec<_>>(),
+        &changes
+            .extensions
+            .iter()
+            .map(|extension| extension.previous_end)
+            .collect::<Vec<_>>(),
+        &changes
+            .extensions
+            .iter()
+            .map(|extension| extension.end)
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(tx.commit().await?)
+}
+
+/// Columns selected by simulation run queries, see [`load_simulation_runs`]
+struct SimulationRunRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    name: String,
+    start: NaiveDate,
+    months: i32,
+    guest_positions: i32,
+    indexation_index: Option<String>,
+    strategy: String,
+    seed: Option<i64>,
+    objective: String,
+    runs: i32,
+    score: f64,
+    feasible: bool,
+    created_contracts: i32,
+    extended_contracts: i32,
+}
+
+impl SimulationRunRow {
+    fn into_run(self) -> color_eyre::Result<SimulationRun> {
+        Ok(SimulationRun {
+            id: SimulationRunId::from(self.id),
+            created_at: self.created_at,
+            name: self.name,
+            start: self.start,
+            months: self.months as u32,
+            guest_positions: self.guest_positions as usize,
+            indexation: self
+                .indexation_index
+                .as_deref()
+                .map(RentIndex::from_str)
+                .transpose()?,
+            strategy: Strategy::from_str(&self.strategy, false).map_err(|err| eyre!(err))?,
+            seed: self.seed.map(|seed| seed as u64),
+            objective: Objective::from_str(&self.objective, false).map_err(|err| eyre!(err))?,
+            runs: NonZeroUsize::try_from(self.runs as usize)?,
+            score: self.score,
+            feasible: self.feasible,
+            created_contracts: self.created_contracts as usize,
+            extended_contracts: self.extended_contracts as usize,
+        })
+    }
+}
+
+pub async fn load_simulation_runs(pool: &PgPool) -> color_eyre::Result<Vec<SimulationRun>> {
+    sqlx::query_as!(
+        SimulationRunRow,
+        "select * from simulation_runs order by created_at asc"
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(SimulationRunRow::into_run)
+    .collect()
+}
+
+pub async fn load_simulation_run(
+    id: &SimulationRunId,
+    pool: &PgPool,
+) -> color_eyre::Result<Option<SimulationRun>> {
+    sqlx::query_as!(
+        SimulationRunRow,
+        "select * from simulation_runs where id = $1::uuid",
+        id.uuid()
+    )
+    .fetch_optional(pool)
+    .await?
+    .map(SimulationRunRow::into_run)
+    .transpose()
+}
+
+/// Contracts created by the run
+pub async fn load_run_contracts(
+    id: &SimulationRunId,
+    pool: &PgPool,
+) -> color_eyre::Result<Vec<Contract>> {
+    let rows = sqlx::query_as!(
+        ContractRow,
+        r#"
+        select
+            contracts.id,
+            contracts.created_at,
+            host_id,
+            guest_id,
+            office_id,
+            offices.parent_office_id is not null as "is_split_office!",
+            positions,
+            rent,
+            start,
+            "end",
+            indexation_index,
+            indexation_reference_year,
+            indexation_reference_quarter,
+            indexation_anniversary,
+            state,
+            terminated_on,
+            termination_penalty
+        from contracts
+        inner join offices on offices.id = contracts.office_id
+        where simulation_run_id = $1::uuid
+        order by start asc"#,
+        id.uuid(),
+    )
+    .fetch_all(pool)
+    .await?;
+
+    contracts_from_rows(rows, pool).await
+}
+
+/// Existing contracts the run lengthened
+pub async fn load_run_extensions(
+    id: &SimulationRunId,
+    pool: &PgPool,
+) -> Result<Vec<RunExtension>, sqlx::Error> {
+    Ok(sqlx::query!(
+        r#"
+        select contract_id, previous_end, "end"
+        from simulation_run_extensions
+        where simulation_run_id = $1::uuid
+        order by previous_end asc"#,
+        id.uuid()
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| RunExtension {
+        contract: ContractId::from(row.contract_id),
+        previous_end: row.previous_end,
+        end: row.end,
+    })
+    .collect())
+}
+
+/// Contracts deleted and restored by discarding a run
+#[derive(Debug, PartialEq)]
+pub struct DiscardedRun {
+    pub created: u64,
+    pub restored: u64,
+}
+
+/// Deletes the run with the contracts and amendments it created, and restores the end of the
+/// contracts it lengthened, in a single transaction; `None` if there's no such run
+pub async fn discard_simulation_run(
+    id: &SimulationRunId,
+    pool: &PgPool,
+) -> Result<Option<DiscardedRun>, DiscardRunError> {
+    let mut tx = pool.begin().await?;
+
+    let run = sqlx::query!(
+        "select id from simulation_runs where id = $1::uuid for update",
+        id.uuid()
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    if run.is_none() {
+        return Ok(None);
+    }
+
+    // signed contracts are no longer the run's to delete
+    if let Some(row) = sqlx::query!(
+        "select id from contracts where simulation_run_id = $1::uuid and state <> 'draft' limit 1",
+        id.uuid()
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    {
+        return Err(DiscardRunError::NotDraft(ContractId::from(row.id)));
+    }
+
+    if let Some(row) = sqlx::query!(
+        r#"
+        select contracts.id
+        from simulation_run_extensions
+        inner join contracts on contracts.id = simulation_run_extensions.contract_id
+        where simulation_run_extensions.simulation_run_id = $1::uuid
+            and contracts."end" <> simulation_run_extensions."end"
+        limit 1"#,
+        id.uuid()
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    {
+        return Err(DiscardRunError::ChangedSinceExtended(ContractId::from(
+            row.id,
+        )));
+    }
+
+    if let Some(row) = sqlx::query!(
+        r#"
+        select contracts.id, simulation_run_extensions.simulation_run_id
+        from simulation_run_extensions
+        inner join contracts on contracts.id = simulation_run_extensions.contract_id
+        where contracts.simulation_run_id = $1::uuid
+        limit 1"#,
+        id.uuid()
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    {
+        return Err(DiscardRunError::ExtendedByRun {
+            contract: ContractId::from(row.id),
+            run: SimulationRunId::from(row.simulation_run_id),
+        });
+    }
+
+    sqlx::query!(
+        "delete from contract_amendments where simulation_run_id = $1::uuid",
+        id.uuid()
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let restored = sqlx::query!(
+        r#"
+        update contracts set "end" = simulation_run_extensions.previous_end
+        from simulation_run_extensions
+        where simulation_run_extensions.contract_id = contracts.id
+            and simulation_run_extensions.simulation_run_id = $1::uuid"#,
+        id.uuid()
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query!(
+        r#"
+        delete from contract_occupancies
+        using simulation_run_extensions
+        where simulation_run_extensions.contract_id = contract_occupancies.contract_id
+            and simulation_run_extensions.simulation_run_id = $1::uuid
+            and lower(contract_occupancies.during) >= simulation_run_extensions.previous_end"#,
+        id.uuid()
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        update contract_occupancies
+        set during = daterange(lower(during), simulation_run_extensions.previous_end)
+        from simulation_run_extensions
+        where simulation_run_extensions.contract_id = contract_occupancies.contract_id
+            and simulation_run_extensions.simulation_run_id = $1::uuid
+            and upper(contract_occupancies.during) > simulation_run_extensions.previous_end"#,
+        id.uuid()
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // transitions, amendments and occupancies are deleted along with their contract
+    let created = sqlx::query!(
+        "delete from contracts where simulation_run_id = $1::uuid",
+        id.uuid()
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query!("delete from simulation_runs where id = $1::uuid", id.uuid())
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(DiscardedRun { created, restored }))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DiscardRunError {
+    #[error("Contract {0} of the run is no longer a draft")]
+    NotDraft(ContractId),
+    #[error("Contract {0} changed since the run extended it")]
+    ChangedSinceExtended(ContractId),
+    #[error("Contract {contract} of the run was extended by run {run}, discard it first")]
+    ExtendedByRun {
+        contract: ContractId,
+        run: SimulationRunId,
+    },
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Whether the database was marked as a development one, see the `dev_database_marker` table
+pub async fn is_dev_database<'a, Connection>(conn: Connection) -> Result<bool, sqlx::Error>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+
+    Ok(
+        sqlx::query!(r#"select exists(select 1 from dev_database_marker) as "marked!""#)
+            .fetch_one(&mut *conn)
+            .await?
+            .marked,
+    )
+}
+
+pub async fn persist_tax_rule<'a, Connection>(
+    tax_rule: &TaxRule,
+    conn: Connection,
+) -> Result<PgQueryResult, sqlx::Error>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+
+    sqlx::query!(
+        r#"
+            insert into tax_rules (
+                id,
+                created_at,
+                rate,
+                start,
+                "end"
+            ) values (
+                $1::uuid,
+                $2::timestamptz,
+                $3::integer,
+                $4::date,
+                $5::date
+            );
+        "#,
+        tax_rule.uuid(),
+        tax_rule.created_at(),
+        *tax_rule.rate() as i32,
+        tax_rule.start(),
+        *tax_rule.end(),
+    )
+    .execute(&mut *conn)
+    .await
+}
+
+pub async fn persist_tax_opt_in<'a, Connection>(
+    host: &UserId,
+    conn: Connection,
+) -> Result<PgQueryResult, sqlx::Error>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+
+    sqlx::query!(
+        "insert into tax_opt_ins (host_id) values ($1::uuid) on conflict do nothing",
+        host.uuid(),
+    )
+    .execute(&mut *conn)
+    .await
+}
+
+pub async fn delete_tax_rule(id: &TaxRuleId, pool: &PgPool) -> Result<bool, sqlx::Error> {
+    Ok(
+        sqlx::query!("delete from tax_rules where id = $1::uuid", id.uuid())
+            .execute(pool)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
+
+pub async fn delete_tax_opt_in(host: &UserId, pool: &PgPool) -> Result<bool, sqlx::Error> {
+    Ok(sqlx::query!(
+        "delete from tax_opt_ins where host_id = $1::uuid",
+        host.uuid()
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+pub async fn load_tax_rule_set<'a, Connection>(conn: Connection) -> color_eyre::Result<TaxRuleSet>
+where
+    Connection: sqlx::Acquire<'a, Database = Postgres>,
+{
+    let mut conn = conn.acquire().await?;
+
+    let rules = sqlx::query!("select * from tax_rules order by start asc")
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|rule| {
+            TaxRule::new_unchecked(
+                TaxRuleId::from(rule.id),
+                rule.created_at,
+                rule.rate as usize,
+                rule.start,
+                rule.end,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let opted_in_hosts = sqlx::query!("select host_id from tax_opt_ins")
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|opt_in| UserId::from(opt_in.host_id))
+        .collect::<HashSet<_>>();
+
+    Ok(TaxRuleSet::new(rules, opted_in_hosts)?)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+    use rand::{rngs::StdRng, SeedableRng};
+    use sora_model::{
+        contract::{RentNegotiation, TerminationPolicy},
+        indexation::{IndexTable, IndexationClause, RentIndex},
+        office::OfficeSplit,
+        test_support::{self, date},
+    };
+
+    use crate::generate::generate_dataset;
+
+    use super::*;
+
+    /// Persists a host, a guest and an office split in two, returns the host, the guest, the
+    /// office and its splits
+    async fn setup(pool: &PgPool) -> (User, User, Office, Office, Office) {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        persist_user(&host, pool).await.unwrap();
+        persist_user(&guest, pool).await.unwrap();
+
+        let office = test_support::office(&host);
+        let mut splits = office
+            .split(vec![
+                OfficeSplit::new(50, 100).unwrap(),
+                OfficeSplit::new(50, 100).unwrap(),
+            ])
+            .unwrap();
+
+        persist_office(&office, pool).await.unwrap();
+        persist_office(&splits[0], pool).await.unwrap();
+        persist_office(&splits[1], pool).await.unwrap();
+        let second_split = splits.pop().unwrap();
+
+        (host, guest, office, splits.pop().unwrap(), second_split)
+    }
+
+    async fn round_trip(contract: Contract, pool: &PgPool) {
+        persist_contract(&contract, pool).await.unwrap();
+
+        let loaded = load_contracts(*contract.start(), *contract.end(), pool)
+            .await
+            .unwrap();
+
+        assert_eq!(vec![contract], loaded);
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_contract_round_trip(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let contract = Contract::for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+        )
+        .unwrap();
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_rent_out_of_range(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let contract = Contract::new(
+            &host,
+            &guest,
+            &office,
+            10,
+            i32::MAX as usize + 1,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            persist_contract(&contract, &pool).await,
+            Err(PersistenceError::OutOfRange { column: "rent", .. })
+        ));
+        assert!(matches!(
+            persist_contracts(&[contract], &pool).await,
+            Err(PersistenceError::OutOfRange { column: "rent", .. })
+        ));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_round_trip_on_split_office(pool: PgPool) {
+        let (host, guest, _, split, _) = setup(&pool).await;
+        let contract = Contract::for_office(
+            &split,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+        )
+        .unwrap();
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_round_trip_keeps_negotiated_rent(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let contract = Contract::negotiated_for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+            RentNegotiation::Override(123456),
+        )
+        .unwrap();
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_round_trip_keeps_indexation(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let contract = Contract::negotiated_for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2027, 1, 1),
+            RentNegotiation::Discount(500),
+        )
+        .unwrap()
+        .with_indexation(IndexationClause::yearly_from(
+            RentIndex::Ilat,
+            date(2025, 1, 1),
+        ));
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_round_trip_keeps_state_and_transitions(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let mut contract = Contract::for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+        )
+        .unwrap();
+        persist_contract(&contract, &pool).await.unwrap();
+
+        contract
+            .transition(
+                ContractState::Signed,
+                Utc.with_ymd_and_hms(2024, 12, 1, 10, 0, 0).unwrap(),
+                Some("Agreed by phone".to_string()),
+            )
+            .unwrap();
+        contract
+            .transition(
+                ContractState::Active,
+                Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap(),
+                None,
+            )
+            .unwrap();
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_round_trip_keeps_termination(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let mut contract = Contract::for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap();
+        contract
+            .transition(
+                ContractState::Signed,
+                Utc.with_ymd_and_hms(2024, 12, 1, 10, 0, 0).unwrap(),
+                None,
+            )
+            .unwrap();
+        contract
+            .transition(
+                ContractState::Active,
+                Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap(),
+                None,
+            )
+            .unwrap();
+        persist_contract(&contract, &pool).await.unwrap();
+
+        contract
+            .terminate(
+                Utc.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap(),
+                Some("Moving out".to_string()),
+                &TerminationPolicy::default(),
+                &IndexTable::new(),
+            )
+            .unwrap();
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_round_trip_keeps_amendments(pool: PgPool) {
+        let (host, guest, _, split, other_split) = setup(&pool).await;
+        let mut contract = Contract::for_office(
+            &split,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap();
+        persist_contract(&contract, &pool).await.unwrap();
+
+        contract
+            .change_rent(2000000, date(2025, 4, 1), Some("Renegotiated".to_string()))
+            .unwrap();
+        contract
+            .change_office(&split, &other_split, date(2025, 7, 1), None)
+            .unwrap();
+        contract.extend(date(2026, 7, 1), None).unwrap();
+
+        round_trip(contract, &pool).await;
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_overlapping_guest_contracts_are_rejected(pool: PgPool) {
+        let (host, guest, office, split, _) = setup(&pool).await;
+        let other_guest = User::new("Jules".to_string(), "Guest".to_string(), Role::Guest);
+        persist_user(&other_guest, &pool).await.unwrap();
+
+        let contract = Contract::for_office(
+            &split,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+        )
+        .unwrap();
+        persist_contract(&contract, &pool).await.unwrap();
+
+        // guests share offices
+        let same_office = Contract::for_office(
+            &split,
+            &host,
+            &other_guest,
+            10,
+            date(2025, 5, 1),
+            date(2025, 9, 1),
+        )
+        .unwrap();
+        persist_contract(&same_office, &pool).await.unwrap();
+
+        let same_guest = Contract::for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 3, 1),
+            date(2025, 7, 1),
+        )
+        .unwrap();
+        assert!(matches!(
+            persist_contract(&same_guest, &pool).await,
+            Err(PersistenceError::GuestOverlap(id)) if id == *same_guest.id()
+        ));
+
+        let back_to_back = Contract::for_office(
+            &split,
+            &host,
+            &guest,
+            10,
+            date(2025, 6, 1),
+            date(2025, 10, 1),
+        )
+        .unwrap();
+        persist_contract(&back_to_back, &pool).await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_related_office_contracts_are_rejected(pool: PgPool) {
+        let (host, guest, office, split, other_split) = setup(&pool).await;
+        let other_guest = User::new("Jules".to_string(), "Guest".to_string(), Role::Guest);
+        persist_user(&other_guest, &pool).await.unwrap();
+
+        // in the real office until it's split
+        let mut contract = Contract::for_office(
+            &office,
+            &host,
+            &guest,
+            10,
+            date(2025, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap();
+        contract
+            .change_office(&office, &split, date(2025, 7, 1), None)
+            .unwrap();
+        persist_contract(&contract, &pool).await.unwrap();
+
+        let contract = |office, start, end| {
+            Contract::for_office(office, &host, &other_guest, 10, start, end).unwrap()
+        };
+
+        let before_move = contract(&other_split, date(2025, 3, 1), date(2025, 8, 1));
+        assert!(matches!(
+            persist_contract(&before_move, &pool).await,
+            Err(PersistenceError::RelatedOfficeOverlap(id)) if id == *before_move.id()
+        ));
+
+        let real_office = contract(&office, date(2025, 8, 1), date(2025, 12, 1));
+        assert!(matches!(
+            persist_contract(&real_office, &pool).await,
+            Err(PersistenceError::RelatedOfficeOverlap(id)) if id == *real_office.id()
+        ));
+
+        let after_move = contract(&other_split, date(2025, 7, 1), date(2025, 11, 1));
+        persist_contract(&after_move, &pool).await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_overbooking_contracts_are_rejected(pool: PgPool) {
+        let (host, guest, office, _, _) = setup(&pool).await;
+        let guests = [
+            User::new("Jules".to_string(), "Guest".to_string(), Role::Guest),
+            User::new("Julie".to_string(), "Guest".to_string(), Role::Guest),
+        ];
+        for guest in guests.iter() {
+            persist_user(guest, &pool).await.unwrap();
+        }
+
+        // the office has 100 positions
+        let contract = |guest, positions, start, end| {
+            Contract::for_office(&office, &host, guest, positions, start, end).unwrap()
+        };
+        persist_contract(
+            &contract(&guest, 60, date(2025, 3, 1), date(2025, 9, 1)),
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        // from before the first contract into it
+        let overbooking = contract(&guests[0], 50, date(2025, 1, 1), date(2025, 5, 1));
+        assert!(matches!(
+            persist_contract(&overbooking, &pool).await,
+            Err(PersistenceError::NotEnoughPositions(id)) if id == *overbooking.id()
+        ));
+
+        persist_contract(
+            &contract(&guests[0], 40, date(2025, 1, 1), date(2025, 5, 1)),
+            &pool,
+        )
+        .await
+        .unwrap();
+        persist_contract(
+            &contract(&guests[1], 50, date(2025, 9, 1), date(2026, 1, 1)),
+            &pool,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_team_round_trip(pool: PgPool) {
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        persist_user(&guest, &pool).await.unwrap();
+        let team = Team::new(
+            "Équipe".to_string(),
+            &guest,
+            vec![
+                Headcount::new(date(2024, 1, 1), 45),
+                Headcount::new(date(2025, 1, 1), 90),
+            ],
+        )
+        .unwrap();
+
+        persist_team(&team, &pool).await.unwrap();
+        let loaded = load_teams(&pool).await.unwrap();
+
+        assert_eq!(1, loaded.len());
+        assert_eq!(team.id(), loaded[0].id());
+        assert_eq!(team.guest(), loaded[0].guest());
+        assert_eq!(team.headcounts(), loaded[0].headcounts());
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_guest_preferences_round_trip(pool: PgPool) {
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        persist_user(&guest, &pool).await.unwrap();
+        let preferences = GuestPreferences::new(
+            &guest,
+            Some(Preference::new(
+                Location::new(Coordinates::new(48.8566, 2.3522).unwrap(), 5.0),
+                Strictness::Required,
+            )),
+            None,
+            Some(Preference::new(
+                OfficeSize::new(40, 80),
+                Strictness::Preferred,
+            )),
+        )
+        .unwrap();
+
+        persist_guest_preferences(&preferences, &pool)
+            .await
+            .unwrap();
+        let loaded = load_guest_preferences(&pool).await.unwrap();
+
+        assert_eq!(1, loaded.len());
+        assert_eq!(preferences.guest(), loaded[0].guest());
+        assert_eq!(preferences.location(), loaded[0].location());
+        assert_eq!(preferences.budget(), loaded[0].budget());
+        assert_eq!(preferences.office_size(), loaded[0].office_size());
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_tax_round_trip(pool: PgPool) {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        persist_user(&host, &pool).await.unwrap();
+        let rule = TaxRule::new(2000, date(2014, 1, 1), None).unwrap();
+
+        persist_tax_rule(&rule, &pool).await.unwrap();
+        persist_tax_opt_in(host.id(), &pool).await.unwrap();
+        let loaded = load_tax_rule_set(&pool).await.unwrap();
+
+        assert_eq!(1, loaded.rules().len());
+        assert_eq!(rule.id(), loaded.rules()[0].id());
+        assert_eq!(rule.rate(), loaded.rules()[0].rate());
+        assert_eq!(rule.start(), loaded.rules()[0].start());
+        assert_eq!(rule.end(), loaded.rules()[0].end());
+        assert!(loaded.is_opted_in(host.id()));
+
+        assert!(delete_tax_rule(rule.id(), &pool).await.unwrap());
+        assert!(!delete_tax_rule(rule.id(), &pool).await.unwrap());
+        assert!(delete_tax_opt_in(host.id(), &pool).await.unwrap());
+        assert!(!delete_tax_opt_in(host.id(), &pool).await.unwrap());
+        let loaded = load_tax_rule_set(&pool).await.unwrap();
+        assert!(loaded.rules().is_empty());
+        assert!(!loaded.is_opted_in(host.id()));
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_office_window_round_trip(pool: PgPool) {
+        let host = User::new("Jean".to_string(), "Host".to_string(), Role::Host);
+        persist_user(&host, &pool).await.unwrap();
+        let office = test_support::office(&host);
+        let splits = office
+            .split(vec![
+                OfficeSplit::new(50, 100).unwrap(),
+                OfficeSplit::new(50, 100).unwrap(),
+            ])
+            .unwrap();
+        persist_office(&office, &pool).await.unwrap();
+        for split in splits.iter() {
+            persist_office(split, &pool).await.unwrap();
+        }
+        let period = DateRange::new(date(2025, 3, 1), date(2025, 5, 1));
+        let windows = [
+            OfficeWindow::new(&office, WindowKind::Listing, period, None).unwrap(),
+            OfficeWindow::new(
+                &splits[1],
+                WindowKind::Blackout,
+                period,
+                Some("Renovation".to_string()),
+            )
+            .unwrap(),
+        ];
+        for window in windows.iter() {
+            persist_office_window(window, &pool).await.unwrap();
+        }
+
+        assert_eq!(2, load_office_windows(None, &pool).await.unwrap().len());
+        let loaded = load_office_windows(Some(splits[1].id()), &pool)
+            .await
+            .unwrap();
+        assert_eq!(1, loaded.len());
+        assert_eq!(windows[1].id(), loaded[0].id());
+        assert_eq!(splits[1].id(), loaded[0].office());
+        assert_eq!(windows[1].kind(), loaded[0].kind());
+        assert_eq!(windows[1].period(), loaded[0].period());
+        assert_eq!(windows[1].reason(), loaded[0].reason());
+
+        assert!(delete_office_window(windows[1].id(), &pool).await.unwrap());
+        assert!(!delete_office_window(windows[1].id(), &pool).await.unwrap());
+        assert_eq!(1, load_office_windows(None, &pool).await.unwrap().len());
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_address_round_trip(pool: PgPool) {
+        let host = User::new("Jean".to_string(), "Host".to_string(), Role::Host);
+        persist_user(&host, &pool).await.unwrap();
+        let addresses = [
+            Address::french(
+                Some("10 bis".to_string()),
+                "rue de Rivoli".to_string(),
+                "75001".to_string(),
+                "Paris".to_string(),
+            )
+            .unwrap(),
+            Address::new(
+                None,
+                "Rue de la Loi".to_string(),
+                "1000".to_string(),
+                "Bruxelles".to_string(),
+                "BE".to_string(),
+            )
+            .unwrap(),
+        ];
+        for address in addresses.iter() {
+            let office = Office::new_real(
+                "Bureau".to_string(),
+                address.clone(),
+                48.85,
+                2.35,
+                *host.id(),
+                100,
+                200,
+                50000,
+            )
+            .unwrap();
+            persist_office(&office, &pool).await.unwrap();
+        }
+
+        let loaded = load_offices(&pool).await.unwrap();
+
+        assert_eq!(
+            addresses.iter().collect::<Vec<_>>(),
+            loaded
+                .iter()
+                .map(|office| office.address())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_batched_inserts(pool: PgPool) {
+        let today = date(2025, 1, 1);
+        let mut dataset =
+            generate_dataset(&mut StdRng::seed_from_u64(42), 60, 20, 0.8, today).unwrap();
+        let surface = *dataset.offices[0].surface();
+        let positions = *dataset.offices[0].available_positions();
+        let splits = dataset.offices[0]
+            .split(vec![
+                OfficeSplit::new(positions / 2, surface / 2).unwrap(),
+                OfficeSplit::new(positions - positions / 2, surface - surface / 2).unwrap(),
+            ])
+            .unwrap();
+        dataset.offices.extend(splits);
+
+        persist_users(&dataset.users, &pool).await.unwrap();
+        persist_offices(&dataset.offices, &pool).await.unwrap();
+        persist_contracts(&dataset.contracts, &pool).await.unwrap();
+
+        let users = load_users(&pool).await.unwrap();
+        assert_eq!(
+            dataset.users.iter().map(User::id).collect::<HashSet<_>>(),
+            users.iter().map(User::id).collect::<HashSet<_>>()
+        );
+
+        let offices = load_offices(&pool).await.unwrap();
+        assert_eq!(22, offices.len());
+        for office in dataset.offices.iter() {
+            let loaded = offices
+                .iter()
+                .find(|loaded| loaded.id() == office.id())
+                .unwrap();
+            assert_eq!(office.address(), loaded.address());
+            assert_eq!(office.parent_office(), loaded.parent_office());
+            assert_eq!(office.owner(), loaded.owner());
+        }
+
+        let contracts = load_contracts(today, date(2030, 1, 1), &pool)
+            .await
+            .unwrap();
+        assert!(!contracts.is_empty());
+        assert_eq!(dataset.contracts.len(), contracts.len());
+        for contract in dataset.contracts.iter() {
+            let loaded = contracts
+                .iter()
+                .find(|loaded| loaded.id() == contract.id())
+                .unwrap();
+            assert_eq!(contract.period(), loaded.period());
+            assert_eq!(contract.rent(), loaded.rent());
+            assert_eq!(ContractState::Signed, *loaded.state());
+            assert_eq!(1, loaded.transitions().len());
+        }
+    }
+
+    /// Persists a signed contract, then a run extending it and creating a draft; Returns the
+    /// signed contract as it was, the run and the draft
+    async fn setup_run(pool: &PgPool) -> (Contract, SimulationRun, Contract) {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        persist_user(&host, pool).await.unwrap();
+        persist_user(&guest, pool).await.unwrap();
+        let office = test_support::office(&host);
+        persist_office(&office, pool).await.unwrap();
+
+        let mut signed = Contract::new(
+            &host,
+            &guest,
+            &office,
+            10,
+            100000,
+            date(2025, 1, 1),
+            date(2025, 6, 1),
+        )
+        .unwrap();
+        signed
+            .transition(ContractState::Signed, Utc::now(), None)
+            .unwrap();
+        persist_contract(&signed, pool).await.unwrap();
+
+        let mut extended = signed.clone();
+        extended.extend(date(2025, 8, 1), None).unwrap();
+        let draft = Contract::new(
+            &host,
+            &guest,
+            &office,
+            10,
+            100000,
+            date(2026, 1, 1),
+            date(2026, 6, 1),
+        )
+        .unwrap();
+        let simulated = vec![extended, draft.clone()];
+        let changes = RunChanges::new(&[signed.clone()], &simulated);
+
+        let run = SimulationRun {
+            id: SimulationRunId::new(),
+            created_at: Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap(),
+            name: "Spread".to_string(),
+            start: date(2025, 1, 1),
+            months: 24,
+            guest_positions: 10,
+            indexation: Some(RentIndex::Ilc),
+            strategy: Strategy::Spread,
+            seed: Some(u64::MAX),
+            objective: Objective::SatisfiedGuests,
+            runs: NonZeroUsize::new(20).unwrap(),
+            score: 1.0,
+            feasible: true,
+            created_contracts: 1,
+            extended_contracts: 1,
+        };
+        persist_simulation_run(&run, &changes, &simulated, pool)
+            .await
+            .unwrap();
+
+        (signed, run, draft)
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_simulation_run_round_trip(pool: PgPool) {
+        let (signed, run, draft) = setup_run(&pool).await;
+
+        assert_eq!(
+            vec![run.clone()],
+            load_simulation_runs(&pool).await.unwrap()
+        );
+        assert_eq!(
+            Some(run.clone()),
+            load_simulation_run(&run.id, &pool).await.unwrap()
+        );
+        assert_eq!(
+            vec![draft],
+            load_run_contracts(&run.id, &pool).await.unwrap()
+        );
+        assert_eq!(
+            vec![RunExtension {
+                contract: *signed.id(),
+                previous_end: date(2025, 6, 1),
+                end: date(2025, 8, 1),
+            }],
+            load_run_extensions(&run.id, &pool).await.unwrap()
+        );
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_discard_run(pool: PgPool) {
+        let (signed, run, draft) = setup_run(&pool).await;
+
+        assert_eq!(
+            Some(DiscardedRun {
+                created: 1,
+                restored: 1
+            }),
+            discard_simulation_run(&run.id, &pool).await.unwrap()
+        );
+
+        let restored = load_contract(signed.id(), &pool).await.unwrap().unwrap();
+        assert_eq!(signed.end(), restored.end());
+        assert!(restored.amendments().is_empty());
+        assert_eq!(None, load_contract(draft.id(), &pool).await.unwrap());
+        assert!(load_simulation_runs(&pool).await.unwrap().is_empty());
+        assert_eq!(None, discard_simulation_run(&run.id, &pool).await.unwrap());
+    }
+
+    #[sqlx::test(migrations = "../../migrations")]
+    async fn test_discard_signed_contract(pool: PgPool) {
+        let (signed, run, mut draft) = setup_run(&pool).await;
+        draft
+            .transition(ContractState::Signed, Utc::now(), None)
+            .unwrap();
+        persist_contract(&draft, &pool).await.unwrap();
+
+        assert!(matches!(
+            discard_simulation_run(&run.id, &pool).await,
+            Err(DiscardRunError::NotDraft(id)) if id == *draft.id()
+        ));
+        // nothing was discarded
+        assert_eq!(1, load_simulation_runs(&pool).await.unwrap().len());
+        assert_eq!(
+            date(2025, 8, 1),
+            *load_contract(signed.id(), &pool)
+                .await
+                .unwrap()
+                .unwrap()
+                .end()
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::{collections::HashMap, num::NonZeroUsize};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use sora_model::{
+    contract::{Contract, ContractAmendmentId, ContractId},
+    indexation::RentIndex,
+};
+use sqlx::PgPool;
+
+use crate::{
+    exploration::Objective,
+    persistence::{
+        discard_simulation_run, load_run_contracts, load_run_extensions, load_simulation_run,
+        load_simulation_runs,
+    },
+    simulation::{Simulation, SimulationError, Strategy},
+};
+
+sora_model::model_id!(SimulationRunId, "sim");
+
+/// A simulation persisted with `simulate --persist`, with what it takes to replay it and its
+/// outcome
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationRun {
+    pub id: SimulationRunId,
+    pub created_at: DateTime<Utc>,
+    pub name: String,
+    pub start: NaiveDate,
+    pub months: u32,
+    pub guest_positions: usize,
+    pub indexation: Option<RentIndex>,
+    pub strategy: Strategy,
+    pub seed: Option<u64>,
+    pub objective: Objective,
+    /// Simulations explored, the best one being persisted
+    pub runs: NonZeroUsize,
+    pub score: f64,
+    /// Whether every guest got their target days in office
+    pub feasible: bool,
+    pub created_contracts: usize,
+    pub extended_contracts: usize,
+}
+
+impl SimulationRun {
+    /// The simulation replaying the run on the data it ran on
+    pub fn simulation(&self) -> Result<Simulation, SimulationError> {
+        let simulation = Simulation::new(
+            self.start,
+            self.months,
+            self.guest_positions,
+            self.indexation,
+        )?
+        .with_strategy(self.strategy);
+
+        Ok(match self.seed {
+            Some(seed) => simulation.with_seed(seed),
+            None => simulation,
+        })
+    }
+}
+
+/// An existing contract lengthened by a run, either as a longer draft or an extension
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunExtension {
+    pub contract: ContractId,
+    pub previous_end: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// What a run changed to the contracts it started from, for it to be discarded later on
+#[derive(Debug, Default, PartialEq)]
+pub struct RunChanges {
+    pub created: Vec<ContractId>,
+    pub extensions: Vec<RunExtension>,
+    /// Amendments added to existing contracts
+    pub amendments: Vec<ContractAmendmentId>,
+}
+
+impl RunChanges {
+    /// Changes between the `existing` contracts and the `simulated` ones, which contain them
+    pub fn new(existing: &[Contract], simulated: &[Contract]) -> Self {
+        let existing = existing
+            .iter()
+            .map(|contract| (contract.id(), contract))
+            .collect::<HashMap<_, _>>();
+        let mut changes = Self::default();
+
+        for contract in simulated {
+            let Some(previous) = existing.get(contract.id()) else {
+                changes.created.push(*contract.id());
+                continue;
+            };
+
+            if contract.end() > previous.end() {
+                changes.extensions.push(RunExtension {
+                    contract: *contract.id(),
+                    previous_end: *previous.end(),
+                    end: *contract.end(),
+                });
+            }
+            changes.amendments.extend(
+                contract.amendments()[previous.amendments().len()..]
+                    .iter()
+                    .map(|amendment| *amendment.id()),
+            );
+        }
+
+        changes
+    }
+
+    /// Whether the contract was created or changed by the run
+    pub fn touches(&self, contract: &Contract) -> bool {
+        self.created.contains(contract.id())
+            || self
+                .extensions
+                .iter()
+                .any(|extension| extension.contract == *contract.id())
+    }
+}
+
+/// Name a value is given on the command line and stored under
+pub(crate) fn value_name(value: &impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .expect("Skipped values are never stored")
+        .get_name()
+        .to_string()
+}
+
+pub async fn list_runs(pool: &PgPool) -> color_eyre::Result<()> {
+    let runs = load_simulation_runs(pool).await?;
+    if runs.is_empty() {
+        log::info!("No simulation run persisted, see `simulate --persist`");
+    }
+
+    for run in runs {
+        println!(
+            "{} \"{}\" on {}: {} contracts created and {} extended, scoring {:.0} on {}{}",
+            run.id,
+            run.name,
+            run.created_at.format("%Y-%m-%d %H:%M"),
+            run.created_contracts,
+            run.extended_contracts,
+            run.score,
+            run.objective,
+            if run.feasible {
+                ""
+            } else {
+                " (some guests miss their target days)"
+            }
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn show_run(pool: &PgPool, id: SimulationRunId) -> color_eyre::Result<()> {
+    let run = load_simulation_run(&id, pool)
+        .await?
+        .ok_or(RunCommandError::NoSuchRun(id))?;
+
+    println!(
+        "Run {} \"{}\", persisted on {}",
+        run.id, run.name, run.created_at
+    );
+    println!(
+        "> Simulated {} months from {}, {} positions per guest{}",
+        run.months,
+        run.start,
+        run.guest_positions,
+        run.indexation
+            .map(|index| format!(", revised against {index}"))
+            .unwrap_or_default()
+    );
+    println!(
+        "> Best of {} runs on {} with a score of {:.0}, {}",
+        run.runs,
+        run.objective,
+        run.score,
+        if run.feasible {
+            "every guest getting their target days"
+        } else {
+            "some guests missing their target days"
+        }
+    );
+    println!(
+        "> Replay it with `--strategy {}{} --guest-positions {}`",
+        run.strategy,
+        run.seed
+            .map(|seed| format!(" --seed {seed}"))
+            .unwrap_or_default(),
+        run.guest_positions
+    );
+
+    for contract in load_run_contracts(&id, pool).await? {
+        println!(
+            "> Created contract {} ({}) for {} in office {}, from {} to {}",
+            contract.id(),
+            contract.state(),
+            contract.guest(),
+            contract.office(),
+            contract.start(),
+            contract.end()
+        );
+    }
+    for extension in load_run_extensions(&id, pool).await? {
+        println!(
+            "> Extended contract {} from {} to {}",
+            extension.contract, extension.previous_end, extension.end
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn discard_run(pool: &PgPool, id: SimulationRunId) -> color_eyre::Result<()> {
+    let discarded = discard_simulation_run(&id, pool)
+        .await?
+        .ok_or(RunCommandError::NoSuchRun(id))?;
+
+    log::info!(
+        "Discarded run {id}, deleting {} contracts and restoring the end of {} others",
+        discarded.created,
+        discarded.restored
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+enum RunCommandError {
+    #[error("No simulation run found with id {0}")]
+    NoSuchRun(SimulationRunId),
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use sora_model::{
+        contract::ContractState,
+        test_support::{self, date},
+        user::{Role, User},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_changes() {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+        let contract =
+            |start, end| Contract::new(&host, &guest, &office, 10, 100000, start, end).unwrap();
+
+        let mut signed = contract(date(2025, 1, 1), date(2025, 6, 1));
+        signed
+            .transition(ContractState::Signed, Utc::now(), None)
+            .unwrap();
+        let draft = contract(date(2025, 11, 1), date(2026, 3, 1));
+        let untouched = contract(date(2026, 9, 1), date(2027, 1, 1));
+        let existing = vec![signed.clone(), draft.clone(), untouched.clone()];
+
+        let mut extended = signed.clone();
+        extended.extend(date(2025, 8, 1), None).unwrap();
+        let mut lengthened = draft.clone();
+        lengthened
+            .merge_following(&contract(date(2026, 3, 1), date(2026, 7, 1)), None)
+            .unwrap();
+        let created = contract(date(2027, 1, 1), date(2027, 6, 1));
+
+        let changes = RunChanges::new(
+            &existing,
+            &[
+                extended.clone(),
+                lengthened,
+                untouched.clone(),
+                created.clone(),
+            ],
+        );
+
+        assert_eq!(vec![*created.id()], changes.created);
+        assert_eq!(
+            vec![
+                RunExtension {
+                    contract: *signed.id(),
+                    previous_end: date(2025, 6, 1),
+                    end: date(2025, 8, 1),
+                },
+                RunExtension {
+                    contract: *draft.id(),
+                    previous_end: date(2026, 3, 1),
+                    end: date(2026, 7, 1),
+                },
+            ],
+            changes.extensions
+        );
+        assert_eq!(vec![*extended.amendments()[0].id()], changes.amendments);
+        assert!(changes.touches(&created));
+        assert!(changes.touches(&extended));
+        assert!(!changes.touches(&untouched));
+    }
+}
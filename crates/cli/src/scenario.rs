@@ -0,0 +1,634 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
+use serde::Deserialize;
+use sora_model::{
+    address::{Address, FRANCE},
+    calendar::OfficeCalendar,
+    contract::{Contract, ContractState, RentNegotiation},
+    office::{Office, OfficeSplit},
+    user::{Role, User},
+};
+
+/// Exact users, offices, splits and contracts to create instead of random ones, users and offices
+/// being referred to by their `key`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    users: Vec<ScenarioUser>,
+    #[serde(default)]
+    offices: Vec<ScenarioOffice>,
+    #[serde(default)]
+    splits: Vec<ScenarioSplit>,
+    #[serde(default)]
+    contracts: Vec<ScenarioContract>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioUser {
+    key: String,
+    first_name: String,
+    last_name: String,
+    /// "host", "guest" or "host_and_guest"
+    role: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioOffice {
+    key: String,
+    /// Key of the owning user
+    owner: String,
+    name: String,
+    number: Option<String>,
+    street: String,
+    postal_code: String,
+    city: String,
+    #[serde(default = "default_country")]
+    country: String,
+    latitude: f32,
+    longitude: f32,
+    positions: usize,
+    surface: usize,
+    /// Monthly price of a position excluding VAT, in cents
+    position_price: usize,
+}
+
+fn default_country() -> String {
+    FRANCE.to_string()
+}
+
+/// Subdivides a real office into as many offices as `parts`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioSplit {
+    office: String,
+    parts: Vec<ScenarioSplitPart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioSplitPart {
+    key: String,
+    positions: usize,
+    surface: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioContract {
+    host: String,
+    guest: String,
+    office: String,
+    positions: usize,
+    /// Relative to the day the scenario is created on
+    start: ScenarioDate,
+    /// Relative to the contract's start; Defaults to the shortest contract possible
+    end: Option<ScenarioDate>,
+    /// Discount on the list price, in basis points
+    discount: Option<usize>,
+    /// "draft", "signed" or "active"; Defaults to "signed"
+    state: Option<String>,
+}
+
+/// Either a date ("2025-01-01", quoted or not), or an offset from a reference date such as
+/// "today", "+3 months", "-1 year" or "+10 days"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "toml::Value")]
+pub enum ScenarioDate {
+    Absolute(NaiveDate),
+    Relative { months: i32, days: i64 },
+}
+
+impl ScenarioDate {
+    pub fn resolve(&self, reference: NaiveDate) -> Option<NaiveDate> {
+        match *self {
+            Self::Absolute(date) => Some(date),
+            Self::Relative { months, days } => {
+                let date = if months < 0 {
+                    reference.checked_sub_months(Months::new(months.unsigned_abs()))
+                } else {
+                    reference.checked_add_months(Months::new(months as u32))
+                }?;
+
+                if days < 0 {
+                    date.checked_sub_days(Days::new(days.unsigned_abs()))
+                } else {
+                    date.checked_add_days(Days::new(days as u64))
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for ScenarioDate {
+    type Err = ScenarioError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || ScenarioError::InvalidDate(s.to_string());
+
+        if s == "today" {
+            return Ok(Self::Relative { months: 0, days: 0 });
+        }
+
+        if let Ok(date) = NaiveDate::from_str(s) {
+            return Ok(Self::Absolute(date));
+        }
+
+        let (sign, offset) = match s.split_at_checked(1) {
+            Some(("+", offset)) => (1, offset),
+            Some(("-", offset)) => (-1, offset),
+            _ => return Err(invalid()),
+        };
+        let (amount, unit) = offset.trim().split_once(' ').ok_or_else(invalid)?;
+        let amount = sign * amount.parse::<i32>().map_err(|_| invalid())?;
+
+        Ok(match unit.trim().trim_end_matches('s') {
+            "day" => Self::Relative {
+                months: 0,
+                days: amount as i64,
+            },
+            "week" => Self::Relative {
+                months: 0,
+                days: amount as i64 * 7,
+            },
+            "month" => Self::Relative {
+                months: amount,
+                days: 0,
+            },
+            "year" => Self::Relative {
+                months: amount.checked_mul(12).ok_or_else(invalid)?,
+                days: 0,
+            },
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+impl TryFrom<toml::Value> for ScenarioDate {
+    type Error = ScenarioError;
+
+    fn try_from(value: toml::Value) -> Result<Self, Self::Error> {
+        match value {
+            toml::Value::String(date) => date.parse(),
+            toml::Value::Datetime(toml::value::Datetime {
+                date: Some(date),
+                time: None,
+                offset: None,
+            }) => NaiveDate::from_ymd_opt(date.year as i32, date.month as u32, date.day as u32)
+                .map(Self::Absolute)
+                .ok_or_else(|| ScenarioError::InvalidDate(date.to_string())),
+            value => Err(ScenarioError::InvalidDate(value.to_string())),
+        }
+    }
+}
+
+/// Everything a scenario describes, ready to be persisted
+#[derive(Debug)]
+pub struct ScenarioFixtures {
+    pub users: Vec<User>,
+    /// Real offices first, then their splits
+    pub offices: Vec<Office>,
+    pub contracts: Vec<Contract>,
+}
+
+impl Scenario {
+    pub fn from_toml(toml: &str) -> Result<Self, ScenarioError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Builds the model objects, relative dates being resolved against `today` and contracts
+    /// being moved to their state at `now`
+    pub fn build(
+        &self,
+        today: NaiveDate,
+        now: DateTime<Utc>,
+    ) -> Result<ScenarioFixtures, ScenarioError> {
+        use ScenarioError::*;
+
+        let mut users = HashMap::<&str, User>::new();
+        let mut user_keys = Vec::new();
+        for user in &self.users {
+            let role = Role::from_str(&user.role).map_err(|err| invalid("user", &user.key, err))?;
+            if users
+                .insert(
+                    &user.key,
+                    User::new(user.first_name.clone(), user.last_name.clone(), role),
+                )
+                .is_some()
+            {
+                return Err(DuplicateKey(user.key.clone()));
+            }
+            user_keys.push(user.key.as_str());
+        }
+
+        let mut offices = HashMap::<&str, Office>::new();
+        let mut office_keys = Vec::new();
+        for office in &self.offices {
+            let owner = users
+                .get(office.owner.as_str())
+                .ok_or_else(|| UnknownUser(office.owner.clone()))?;
+            let address = Address::new(
+                office.number.clone(),
+                office.street.clone(),
+                office.postal_code.clone(),
+                office.city.clone(),
+                office.country.clone(),
+            )
+            .map_err(|err| invalid("office", &office.key, err))?;
+
+            let real_office = Office::new_real(
+                office.name.clone(),
+                address,
+                office.latitude,
+                office.longitude,
+                *owner.id(),
+                office.positions,
+                office.surface,
+                office.position_price,
+            )
+            .map_err(|err| invalid("office", &office.key, err))?;
+
+            if offices.insert(&office.key, real_office).is_some() {
+                return Err(DuplicateKey(office.key.clone()));
+            }
+            office_keys.push(office.key.as_str());
+        }
+
+        for split in &self.splits {
+            let office = offices
+                .get(split.office.as_str())
+                .ok_or_else(|| UnknownOffice(split.office.clone()))?;
+
+            let parts = split
+                .parts
+                .iter()
+                .map(|part| OfficeSplit::new(part.positions, part.surface))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| invalid("split of office", &split.office, err))?;
+            let subdivisions = office
+                .split(parts)
+                .map_err(|err| invalid("split of office", &split.office, err))?;
+
+            for (part, subdivision) in split.parts.iter().zip(subdivisions) {
+                if offices.insert(&part.key, subdivision).is_some() {
+                    return Err(DuplicateKey(part.key.clone()));
+                }
+                office_keys.push(part.key.as_str());
+            }
+        }
+
+        // contracts booking unavailable offices, or guests already in an office, are rejected
+        let mut calendar = OfficeCalendar::new(offices.values());
+        let mut contracts = Vec::new();
+        for (index, contract) in self.contracts.iter().enumerate() {
+            let key = format!("#{}", index + 1);
+            let user = |key: &String| {
+                users
+                    .get(key.as_str())
+                    .ok_or_else(|| UnknownUser(key.clone()))
+            };
+
+            let start = contract
+                .start
+                .resolve(today)
+                .ok_or_else(|| InvalidDate(format!("{:?}", contract.start)))?;
+            let end = match contract.end {
+                Some(end) => end
+                    .resolve(start)
+                    .ok_or_else(|| InvalidDate(format!("{end:?}")))?,
+                None => Contract::minimum_end(start),
+            };
+
+            let mut built = Contract::negotiated_for_office(
+                offices
+                    .get(contract.office.as_str())
+                    .ok_or_else(|| UnknownOffice(contract.office.clone()))?,
+                user(&contract.host)?,
+                user(&contract.guest)?,
+                contract.positions,
+                start,
+                end,
+                contract
+                    .discount
+                    .map_or(RentNegotiation::ListPrice, RentNegotiation::Discount),
+            )
+            .map_err(|err| invalid("contract", &key, err))?;
+
+            let state = match &contract.state {
+                Some(state) => {
+                    ContractState::from_str(state).map_err(|err| invalid("contract", &key, err))?
+                }
+                None => ContractState::Signed,
+            };
+            let transitions = match state {
+                ContractState::Draft => vec![],
+                ContractState::Signed => vec![ContractState::Signed],
+                ContractState::Active => vec![ContractState::Signed, ContractState::Active],
+                state => {
+                    return Err(UnsupportedState {
+                        contract: key,
+                        state,
+                    })
+                }
+            };
+            for state in transitions {
+                built
+                    .transition(state, now, Some("Scenario".to_string()))
+                    .map_err(|err| invalid("contract", &key, err))?;
+            }
+
+            calendar
+                .book(&built)
+                .map_err(|err| invalid("contract", &key, err))?;
+            contracts.push(built);
+        }
+
+        Ok(ScenarioFixtures {
+            users: user_keys
+                .into_iter()
+                .map(|key| users.remove(key).unwrap())
+                .collect(),
+            offices: office_keys
+                .into_iter()
+                .map(|key| offices.remove(key).unwrap())
+                .collect(),
+            contracts,
+        })
+    }
+}
+
+fn invalid(
+    entity: &'static str,
+    key: &str,
+    source: impl std::error::Error + Send + Sync + 'static,
+) -> ScenarioError {
+    ScenarioError::Invalid {
+        entity,
+        key: key.to_string(),
+        source: Box::new(source),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScenarioError {
+    #[error(transparent)]
+    Parse(#[from] toml::de::Error),
+    #[error(r#"Invalid date "{0}", expected e.g. "2025-01-01", "today" or "+3 months""#)]
+    InvalidDate(String),
+    #[error(r#"Key "{0}" is used more than once"#)]
+    DuplicateKey(String),
+    #[error(r#"No user with key "{0}""#)]
+    UnknownUser(String),
+    #[error(r#"No office with key "{0}""#)]
+    UnknownOffice(String),
+    #[error("Contract {contract} cannot be created {state}, only draft, signed or active")]
+    UnsupportedState {
+        contract: String,
+        state: ContractState,
+    },
+    #[error("Invalid {entity} {key}: {source}")]
+    Invalid {
+        entity: &'static str,
+        key: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    use rstest::rstest;
+
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()
+    }
+
+    #[rstest]
+    #[case("today", Some((2025, 1, 31)))]
+    #[case("2024-06-01", Some((2024, 6, 1)))]
+    #[case("+3 months", Some((2025, 4, 30)))]
+    #[case("-1 year", Some((2024, 1, 31)))]
+    #[case("+10 days", Some((2025, 2, 10)))]
+    #[case("+2 weeks", Some((2025, 2, 14)))]
+    #[case("-1 day", Some((2025, 1, 30)))]
+    #[case("3 months", None)]
+    #[case("+3 fortnights", None)]
+    #[case("+three months", None)]
+    fn test_parse_date(#[case] input: &str, #[case] expected: Option<(i32, u32, u32)>) {
+        assert_eq!(
+            expected.map(|(year, month, day)| NaiveDate::from_ymd_opt(year, month, day).unwrap()),
+            ScenarioDate::from_str(input)
+                .ok()
+                .and_then(|date| date.resolve(today()))
+        );
+    }
+
+    #[test]
+    fn test_build() {
+        let scenario = Scenario::from_toml(
+            r#"
+            [[users]]
+            key = "host"
+            first_name = "Jean"
+            last_name = "Dupont"
+            role = "host"
+
+            [[users]]
+            key = "guest"
+            first_name = "Marie"
+            last_name = "Curie"
+            role = "guest"
+
+            [[users]]
+            key = "other-guest"
+            first_name = "Pierre"
+            last_name = "Curie"
+            role = "guest"
+
+            [[offices]]
+            key = "rivoli"
+            owner = "host"
+            name = "Bureau Rivoli"
+            number = "10"
+            street = "rue de Rivoli"
+            postal_code = "75001"
+            city = "Paris"
+            latitude = 48.8556
+            longitude = 2.3601
+            positions = 100
+            surface = 200
+            position_price = 50000
+
+            [[splits]]
+            office = "rivoli"
+            parts = [
+                { key = "rivoli-a", positions = 40, surface = 80 },
+                { key = "rivoli-b", positions = 60, surface = 120 },
+            ]
+
+            [[contracts]]
+            host = "host"
+            guest = "guest"
+            office = "rivoli-a"
+            positions = 40
+            start = 2024-10-01
+            end = "+1 year"
+            state = "active"
+
+            [[contracts]]
+            host = "host"
+            guest = "other-guest"
+            office = "rivoli-b"
+            positions = 10
+            start = "+1 month"
+            discount = 1000
+            "#,
+        )
+        .unwrap();
+
+        let fixtures = scenario.build(today(), Utc::now()).unwrap();
+
+        assert_eq!(3, fixtures.users.len());
+        assert_eq!(3, fixtures.offices.len());
+        assert_eq!(
+            fixtures.offices[0].real_office(),
+            fixtures.offices[2].real_office()
+        );
+
+        let [active, signed] = &fixtures.contracts[..] else {
+            panic!("expected 2 contracts");
+        };
+        assert_eq!(ContractState::Active, *active.state());
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap(), *active.end());
+        assert_eq!(fixtures.offices[1].id(), active.office());
+        assert_eq!(ContractState::Signed, *signed.state());
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2025, 2, 28).unwrap(),
+            *signed.start()
+        );
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 6, 28).unwrap(), *signed.end());
+        assert_eq!(450000, *signed.rent());
+    }
+
+    #[rstest]
+    #[case(
+        r#"[[users]]
+        key = "guest"
+        first_name = "Marie"
+        last_name = "Curie"
+        role = "tenant""#
+    )]
+    #[case(
+        r#"[[offices]]
+        key = "rivoli"
+        owner = "nobody"
+        name = "Bureau"
+        street = "rue de Rivoli"
+        postal_code = "75001"
+        city = "Paris"
+        latitude = 48.8556
+        longitude = 2.3601
+        positions = 100
+        surface = 200
+        position_price = 50000"#
+    )]
+    #[case(
+        r#"[[splits]]
+        office = "nowhere"
+        parts = []"#
+    )]
+    #[case(
+        r#"[[users]]
+        key = "guest"
+        first_name = "Marie"
+        last_name = "Curie"
+        role = "guest"
+        age = 58"#
+    )]
+    fn test_invalid_scenario(#[case] toml: &str) {
+        assert!(Scenario::from_toml(toml)
+            .and_then(|scenario| scenario.build(today(), Utc::now()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_unavailable_office() {
+        let scenario = Scenario::from_toml(
+            r#"
+            [[users]]
+            key = "host"
+            first_name = "Jean"
+            last_name = "Dupont"
+            role = "host"
+
+            [[users]]
+            key = "guest"
+            first_name = "Marie"
+            last_name = "Curie"
+            role = "guest"
+
+            [[users]]
+            key = "other-guest"
+            first_name = "Pierre"
+            last_name = "Curie"
+            role = "guest"
+
+            [[offices]]
+            key = "rivoli"
+            owner = "host"
+            name = "Bureau Rivoli"
+            street = "rue de Rivoli"
+            postal_code = "75001"
+            city = "Paris"
+            latitude = 48.8556
+            longitude = 2.3601
+            positions = 100
+            surface = 200
+            position_price = 50000
+
+            [[contracts]]
+            host = "host"
+            guest = "guest"
+            office = "rivoli"
+            positions = 60
+            start = "today"
+
+            [[contracts]]
+            host = "host"
+            guest = "other-guest"
+            office = "rivoli"
+            positions = 60
+            start = "+1 month"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            scenario.build(today(), Utc::now()),
+            Err(ScenarioError::Invalid { entity: "contract", key, .. }) if key == "#2"
+        ));
+    }
+
+    #[test]
+    fn test_bundled_scenarios() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+
+        for entry in fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            let scenario = Scenario::from_toml(&fs::read_to_string(&path).unwrap()).unwrap();
+
+            assert!(
+                scenario.build(Utc::now().date_naive(), Utc::now()).is_ok(),
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
@@ -3,22 +3,57 @@ use std::{fmt::Display, str::FromStr};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-pub async fn show(pool: &PgPool, filter: Option<String>) -> color_eyre::Result<()> {
+pub async fn show(
+    pool: &PgPool,
+    filter: Option<String>,
+    city: Option<String>,
+    postal_code: Option<String>,
+) -> color_eyre::Result<()> {
     let filter = filter
         .as_deref()
         .map(|str| str.trim().trim_matches(['\'', '"']))
         .map(|str| Filter::from_str(str).map_err(|_| FilterError::NoSuchFilter(str.to_string())))
         .unwrap_or(Ok(Filter::None))?;
 
+    // addresses are only searched among offices
+    let by_address = city.is_some() || postal_code.is_some();
+    let filter = match filter {
+        Filter::None if by_address => Filter::Aggregate(Aggregate::Offices),
+        Filter::Aggregate(Aggregate::Offices | Aggregate::OfficeSplit) | Filter::None => filter,
+        _ if by_address => return Err(FilterError::NoAddress(filter).into()),
+        _ => filter,
+    };
+
     log::info!("Using filter {:?}", filter);
 
     match filter {
         Filter::Aggregate(aggregate) => {
-            let condition = aggregate
+            let mut conditions = aggregate
                 .condition()
-                .map(|condition| format!("where {condition}"))
-                .unwrap_or_default();
-            let results = sqlx::query(&format!("select * from {aggregate} {condition}"))
+                .map(str::to_string)
+                .into_iter()
+                .collect::<Vec<_>>();
+            let mut values = Vec::<String>::new();
+
+            if let Some(city) = city {
+                values.push(city.trim().to_string());
+                conditions.push(format!("lower(address_city) = lower(${})", values.len()));
+            }
+
+            if let Some(postal_code) = postal_code {
+                values.push(like_prefix(postal_code.trim()));
+                conditions.push(format!("address_postal_code like ${}", values.len()));
+            }
+
+            let condition = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("where {}", conditions.join(" and "))
+            };
+            let query = format!("select * from {aggregate} {condition}");
+            let results = values
+                .into_iter()
+                .fold(sqlx::query(&query), |query, value| query.bind(value))
                 .fetch_all(pool)
                 .await?;
 
@@ -32,7 +67,13 @@ pub async fn show(pool: &PgPool, filter: Option<String>) -> color_eyre::Result<(
             dbg!(result);
         }
         Filter::None => {
-            let tables = [Aggregate::Users, Aggregate::Contracts, Aggregate::Offices];
+            let tables = [
+                Aggregate::Users,
+                Aggregate::Contracts,
+                Aggregate::Offices,
+                Aggregate::TaxRules,
+                Aggregate::Teams,
+            ];
             let queries = tables
                 .iter()
                 .map(|aggregate| format!("select * from {aggregate}"))
@@ -74,6 +115,8 @@ enum Filter {
 enum FilterError {
     #[error(r#"No filter matched for "{0}""#)]
     NoSuchFilter(String),
+    #[error("Only offices can be filtered by city or postal code, not {0:?}")]
+    NoAddress(Filter),
 }
 
 impl FromStr for Filter {
@@ -100,6 +143,8 @@ enum Aggregate {
     Contracts,
     Offices,
     OfficeSplit,
+    TaxRules,
+    Teams,
     Users,
 }
 
@@ -121,6 +166,8 @@ impl FromStr for Aggregate {
             "ofc" | "office" | "offices" => Self::Offices,
             "spl" => Self::OfficeSplit,
             "agr" | "contract" | "contracts" => Self::Contracts,
+            "tax" | "tax_rule" | "tax_rules" => Self::TaxRules,
+            "tem" | "team" | "teams" => Self::Teams,
             _ => return Err(()),
         })
     }
@@ -134,8 +181,36 @@ impl Display for Aggregate {
             match self {
                 Aggregate::Contracts => "contracts",
                 Aggregate::Offices | Aggregate::OfficeSplit => "offices",
+                Aggregate::TaxRules => "tax_rules",
+                Aggregate::Teams => "teams",
                 Aggregate::Users => "users",
             }
         )
     }
 }
+
+/// `like` pattern of the values starting with `prefix`, its wildcards matched literally
+fn like_prefix(prefix: &str) -> String {
+    let prefix = prefix
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("{prefix}%")
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("75", "75%")]
+    #[case("7_", "7\\_%")]
+    #[case("%", "\\%%")]
+    #[case("\\", "\\\\%")]
+    fn test_like_prefix(#[case] prefix: &str, #[case] expected: &str) {
+        assert_eq!(expected, like_prefix(prefix));
+    }
+}
@@ -1,121 +1,161 @@
-use std::ops::Deref;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    time::Instant,
+};
 
 use chrono::{Days, Months, NaiveDate, Utc};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use sora_model::{
-    contract::{Contract, ContractError, ContractId, CONTRACT_DURATION_MINIMUM_DAYS},
-    office::{Office, OfficeId, OfficeSplitId, RealOfficeId},
+    calendar::OfficeCalendar,
+    contract::Contract,
+    id::Identifier,
+    indexation::{IndexTable, IndexationClause, Quarter, RentIndex},
+    interval::{DateRange, IntervalSet},
+    office::{Office, OfficeWindow},
+    preference::GuestPreferences,
+    team::Team,
     user::{User, UserId},
 };
 use sqlx::PgPool;
 
 use crate::{
-    persistence,
-    range::{invert_ranges_in_boundary, DateRange},
+    exploration::{explore, Runs, SimulationInput},
+    persistence::{
+        load_contracts, load_guest_preferences, load_office_windows, load_offices,
+        load_tax_rule_set, load_teams, load_users, persist_simulation_run,
+    },
+    runs::{RunChanges, SimulationRun, SimulationRunId},
 };
 
+#[allow(clippy::too_many_arguments)]
 pub async fn simulate(
     duration_in_months: usize,
     should_persist: bool,
+    name: Option<String>,
+    indexation: Option<RentIndex>,
+    index_table: IndexTable,
+    guest_positions: usize,
+    runs: Runs,
     pool: &PgPool,
 ) -> color_eyre::Result<()> {
-    let simulation = Simulation {
-        start: Utc::now().date_naive(),
-        end: Utc::now()
-            .checked_add_months(Months::new(duration_in_months as u32))
-            .unwrap()
-            .date_naive(),
-        target_days_in_office: 12 * 30, // ~1 year
+    let simulation = Simulation::new(
+        Utc::now().date_naive(),
+        duration_in_months as u32,
+        guest_positions,
+        indexation,
+    )?;
+
+    let users = load_users(pool).await?;
+
+    let offices = load_offices(pool).await?;
+
+    let contracts = load_contracts(simulation.start, simulation.end, pool).await?;
+
+    let tax_rule_set = load_tax_rule_set(pool).await?;
+
+    let teams = load_teams(pool).await?;
+
+    let preferences = load_guest_preferences(pool).await?;
+
+    let windows = load_office_windows(None, pool).await?;
+
+    let input = SimulationInput {
+        users: users.iter().collect(),
+        offices: offices.iter().collect(),
+        windows: windows.iter().collect(),
+        teams: teams.iter().collect(),
+        preferences: preferences.iter().collect(),
+        contracts,
     };
 
-    let users = sqlx::query!("select * from users")
-        .fetch_all(pool)
-        .await?
-        .into_iter()
-        .map(|user| {
-            User::new_unchecked(
-                UserId::from(user.id),
-                user.created_at,
-                user.first_name,
-                user.last_name,
-            )
-        })
-        .collect::<Vec<_>>();
-
-    let offices = sqlx::query!("select * from offices where not exists(select sub.id from offices as sub where sub.parent_office_id = offices.id)")
-        .fetch_all(pool)
-        .await?
-        .into_iter()
-        .map(|office| {
-            // office is split if it has a parent
-            let id = office
-                .parent_office_id
-                .map(|_| OfficeId::OfficeSplit(OfficeSplitId::from(office.id)))
-                .unwrap_or_else(|| OfficeId::RealOffice(RealOfficeId::from(office.id)));
-
-            Office::new_unchecked(
-                id,
-                office.created_at,
-                office.name,
-                office.address,
-                office.latitude as f32,
-                office.longitude as f32,
-                UserId::from(office.owner_id),
-                office.available_positions as usize,
-                office.surface as usize,
-                office.position_price as usize,
-                office.parent_office_id.map(RealOfficeId::from),
-            )
-        })
-        .collect::<Vec<_>>();
-
-    let contracts = sqlx::query!(
-        r#"
-        select *,
-        exists(
-            select id from offices where id = contracts.office_id
-        ) as is_split_office 
-        from contracts
-        where start >= $1::date AND "end" <= $2::date
-        order by start asc"#,
-        simulation.start,
-        simulation.end
-    )
-    .fetch_all(pool)
-    .await?
-    .into_iter()
-    .map(|contract| {
-        Contract::new_unchecked(
-            ContractId::from(contract.id),
-            contract.created_at,
-            contract.host_id.into(),
-            contract.guest_id.into(),
-            contract
-                .is_split_office
+    // runs after the first one are only worth it with guests taken in different orders
+    let runs = match runs.seed {
+        None if runs.count.get() > 1 => Runs {
+            seed: Some(rand::random()),
+            ..runs
+        },
+        _ => runs,
+    };
+
+    let started_at = Instant::now();
+    let exploration = explore(&simulation, &input, &runs, &index_table)?;
+    let best = *exploration.best();
+
+    if runs.count.get() > 1 {
+        println!(
+            "Ran {} simulations in {:.2?}, scoring {}:",
+            runs.count,
+            started_at.elapsed(),
+            runs.objective
+        );
+        println!("> Scores: {}", exploration.distribution());
+        for (strategy, distribution) in exploration.by_strategy() {
+            println!("> Strategy {strategy}: {distribution}");
+        }
+        println!(
+            "> {:.1}% of runs get every guest their target days in office",
+            exploration.feasibility_rate() * 100.0
+        );
+        println!(
+            "> Best run is #{} with a score of {:.0}, reproduce it with `--strategy {}{}`",
+            best.run,
+            best.score,
+            best.strategy,
+            best.seed
+                .map(|seed| format!(" --seed {seed}"))
                 .unwrap_or_default()
-                .then(|| OfficeId::RealOffice(contract.office_id.into()))
-                .unwrap_or_else(|| OfficeId::OfficeSplit(contract.office_id.into())),
-            contract.rent as usize,
-            contract.start,
-            contract.end,
-        )
-    })
-    .collect::<Vec<_>>();
+        );
+        println!();
+    }
 
-    let contracts =
-        simulation.simulate(users.iter().collect(), offices.iter().collect(), contracts)?;
+    // replayed, since runs don't keep their contracts
+    let contracts = input.simulate(&best.simulation(&simulation))?;
+    warn_unpublished_quarters(&contracts, &index_table);
 
     if should_persist {
-        log::info!("Persisting {} contracts to database.", contracts.len());
-        persist(offices.iter().collect(), contracts.iter().collect(), pool).await?;
+        let changes = RunChanges::new(&input.contracts, &contracts);
+        let created_at = Utc::now();
+        let run = SimulationRun {
+            id: SimulationRunId::new(),
+            created_at,
+            name: name.unwrap_or_else(|| {
+                format!("Simulation of {}", created_at.format("%Y-%m-%d %H:%M"))
+            }),
+            start: simulation.start,
+            months: duration_in_months as u32,
+            guest_positions,
+            indexation,
+            strategy: best.strategy,
+            seed: best.seed,
+            objective: runs.objective,
+            runs: runs.count,
+            score: best.score,
+            feasible: best.feasible,
+            created_contracts: changes.created.len(),
+            extended_contracts: changes.extensions.len(),
+        };
+
+        log::info!(
+            "Persisting run {}, creating {} contracts and extending {} others",
+            run.id,
+            run.created_contracts,
+            run.extended_contracts
+        );
+        persist_simulation_run(&run, &changes, &contracts, pool).await?;
+        log::info!(
+            "Generated contracts are drafts, confirm them with the `contract sign <id>` command or drop them with `simulate discard-run {}`",
+            run.id
+        );
     } else {
         log::info!("Not persisting since the --persist flag wasn't passed");
     }
 
     println!("Simulation done, printing best solution found:");
     println!("Displaying informations for offices");
-    println!("");
+    println!();
 
-    for office in offices {
+    for office in offices.iter() {
         let office_contracts = contracts
             .iter()
             .filter(|contract| contract.office() == office.id())
@@ -124,20 +164,30 @@ pub async fn simulate(
         println!("Office {}:", office.id());
         for contract in office_contracts {
             println!(
-                "> From {} to {} ({} days), office will be occupied by {} with contract {}",
+                "> From {} to {} ({} days), {} positions will be occupied by {} with contract {} ({})",
                 contract.start(),
                 contract.end(),
                 (*contract.end() - *contract.start()).num_days(),
+                contract.positions(),
                 contract.guest(),
-                contract.id()
+                contract.id(),
+                contract.state(),
             );
+
+            if let Some(termination) = contract.termination() {
+                println!(
+                    "> Terminated, office is freed on {} with {}€ owed in penalties",
+                    termination.on(),
+                    *termination.penalty() as f32 / 100.0
+                );
+            }
         }
-        println!("");
+        println!();
     }
 
     println!("============================");
     println!("Displaying user informations:");
-    println!("");
+    println!();
 
     for user in users {
         let mut user_contracts = contracts
@@ -153,6 +203,10 @@ pub async fn simulate(
             .map(|time| time.num_days())
             .sum::<i64>();
 
+        let user_preferences = preferences
+            .iter()
+            .find(|preferences| preferences.guest() == user.id());
+
         println!("Displaying informations for {}", user.id());
 
         println!(
@@ -170,171 +224,974 @@ pub async fn simulate(
                 (*contract.end() - *contract.start()).num_days()
             );
 
+            if let (Some(preferences), Some(office)) = (
+                user_preferences,
+                offices
+                    .iter()
+                    .find(|office| office.id() == contract.office()),
+            ) {
+                for outcome in preferences.evaluate(office, *contract.rent()).outcomes() {
+                    println!("> Preference on {outcome}");
+                }
+            }
+
+            for (period, breakdown) in contract
+                .rent_schedule(&index_table)
+                .iter()
+                .flat_map(|period| tax_rule_set.breakdowns(contract.host(), period))
+            {
+                println!(
+                    "> From {} to {}, the rent is of {}€/month excluding VAT ({}€ VAT, {}€ including VAT)",
+                    period.start(),
+                    period.end(),
+                    *breakdown.net() as f32 / 100.0,
+                    *breakdown.tax() as f32 / 100.0,
+                    *breakdown.gross() as f32 / 100.0,
+                );
+            }
+
             println!(
-                "> The rent for said office is of {}€/month, user will be paying a total of {}€ for the contract duration", 
-                *contract.rent() as f32 / 100.0,
-                (*contract.rent() as f32 * ((*contract.end() - *contract.start()).num_weeks() as f32 / 7.0)) / 100.0
+                "> User will be paying a total of {}€ excluding VAT for the contract duration",
+                contract.total_rent(&index_table) as f32 / 100.0
             );
         }
 
-        println!("");
+        println!();
     }
 
     Ok(())
 }
 
+/// How a guest's next contract is chosen among the offices they can rent
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Earliest contract, then the one meeting most preferences, then the smallest office, packing
+    /// guests together
+    #[default]
+    Earliest,
+    /// Contract meeting most preferences, then the earliest one, then the smallest office
+    Preferred,
+    /// Earliest contract, then the one meeting most preferences, then the largest office,
+    /// spreading guests out
+    Spread,
+}
+
+impl Strategy {
+    /// Whether a contract starting on `start` and scoring `score` beats the best one so far
+    fn prefers(
+        &self,
+        (start, score): (NaiveDate, f64),
+        (best_start, best_score): (NaiveDate, f64),
+    ) -> bool {
+        match self {
+            Self::Earliest | Self::Spread => {
+                start < best_start || (start == best_start && score > best_score)
+            }
+            Self::Preferred => score > best_score || (score == best_score && start < best_start),
+        }
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Earliest => write!(f, "earliest"),
+            Self::Preferred => write!(f, "preferred"),
+            Self::Spread => write!(f, "spread"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Simulation {
     start: NaiveDate,
     end: NaiveDate,
-    target_days_in_office: usize,
+    /// Calendar months each guest should rent an office for
+    target_months_in_office: u32,
+    /// Positions reserved by guests without a team
+    guest_positions: usize,
+    /// Index generated contracts are revised against, if any
+    indexation: Option<RentIndex>,
+    strategy: Strategy,
+    /// Seed guests are shuffled with before looking for offices; They are taken in the given
+    /// order without one
+    seed: Option<u64>,
+    /// Progress is logged at the debug level instead of info, for runs that are not the outcome
+    quiet: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimulationError {
+    #[error("A period of {months} months from {start} ends beyond the last supported date")]
+    InvalidPeriod { start: NaiveDate, months: u32 },
 }
 
 impl Simulation {
-    fn simulate(
+    /// Guests are looking for a year of office over the `duration_in_months` following `start`
+    pub fn new(
+        start: NaiveDate,
+        duration_in_months: u32,
+        guest_positions: usize,
+        indexation: Option<RentIndex>,
+    ) -> Result<Self, SimulationError> {
+        let end = start
+            .checked_add_months(Months::new(duration_in_months))
+            .ok_or(SimulationError::InvalidPeriod {
+                start,
+                months: duration_in_months,
+            })?;
+
+        Ok(Self {
+            start,
+            end,
+            target_months_in_office: 12,
+            guest_positions,
+            indexation,
+            strategy: Strategy::default(),
+            seed: None,
+            quiet: false,
+        })
+    }
+
+    pub fn with_strategy(self, strategy: Strategy) -> Self {
+        Self { strategy, ..self }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    pub fn with_quiet(self, quiet: bool) -> Self {
+        Self { quiet, ..self }
+    }
+
+    fn progress_level(&self) -> log::Level {
+        if self.quiet {
+            log::Level::Debug
+        } else {
+            log::Level::Info
+        }
+    }
+
+    /// Contracts getting guests as close as possible to their target days in office, along with
+    /// the existing `contracts`
+    pub fn simulate(
         &self,
         users: Vec<&User>,
         offices: Vec<&Office>,
+        windows: Vec<&OfficeWindow>,
+        teams: Vec<&Team>,
+        preferences: Vec<&GuestPreferences>,
         contracts: Vec<Contract>,
-    ) -> Result<Vec<Contract>, ContractError> {
+    ) -> color_eyre::Result<Vec<Contract>> {
         let mut contracts = contracts;
+        let target_days_in_office = self.target_days_in_office();
 
-        'users: for user in users {
-            let user_unavailable_ranges = contracts
-                .iter()
-                .filter(|contract| contract.guest() == user.id())
-                .map(DateRange::from)
-                .collect::<Vec<_>>();
+        let mut calendar = seed_calendar(offices.iter().copied(), contracts.iter(), windows);
 
-            let user_ranges_to_fill =
-                invert_ranges_in_boundary(user_unavailable_ranges.iter(), self.start, self.end);
+        // smaller offices first, so that guests are packed together, unless spreading them out
+        let mut offices = rentable_offices(&offices);
+        offices.sort_by_key(|office| *office.available_positions());
+        if self.strategy == Strategy::Spread {
+            offices.reverse();
+        }
+
+        let hosts = users
+            .iter()
+            .filter(|user| user.role().can_host())
+            .map(|user| (*user.id(), *user))
+            .collect::<HashMap<UserId, &User>>();
+
+        let teams = teams
+            .iter()
+            .map(|team| (*team.guest(), *team))
+            .collect::<HashMap<UserId, &Team>>();
+
+        let preferences = preferences
+            .iter()
+            .map(|preferences| (*preferences.guest(), *preferences))
+            .collect::<HashMap<UserId, &GuestPreferences>>();
+
+        let mut office_days = HashMap::<UserId, IntervalSet>::new();
+        for contract in contracts.iter() {
+            office_days
+                .entry(*contract.guest())
+                .or_default()
+                .insert(contract.period());
+        }
+
+        let mut guests = users
+            .iter()
+            .filter(|user| user.role().can_rent())
+            .collect::<Vec<_>>();
+        if let Some(seed) = self.seed {
+            guests.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+
+        'users: for user in guests {
+            let needs = match teams.get(user.id()) {
+                Some(team) => GuestNeeds::Team(team),
+                None => GuestNeeds::Fixed(self.guest_positions),
+            };
+
+            let user_total_office_days = office_days
+                .get(user.id())
+                .map(IntervalSet::total_days)
+                .unwrap_or_default();
+
+            let mut user_missing_office_days =
+                target_days_in_office.saturating_sub(user_total_office_days);
+
+            if user_missing_office_days == 0 {
+                log::log!(
+                    self.progress_level(),
+                    "User {} has locked all necessary days, switching to next user",
+                    user.id()
+                );
+
+                continue 'users;
+            }
+
+            log::log!(
+                    self.progress_level(),"User {} has {user_total_office_days}/{} days of locked office, which means we still need to lock at least {user_missing_office_days} days!", user.id(), target_days_in_office);
 
             let office_candidates = offices
                 .iter()
                 .filter(|office| office.owner() != user.id())
+                .filter_map(|office| Some((*office, *hosts.get(office.owner())?)))
                 .collect::<Vec<_>>();
 
-            let user_total_office_days = user_unavailable_ranges
-                .iter()
-                .map(|range| (range.end - range.start).num_days())
-                .sum::<i64>() as usize;
+            while user_missing_office_days > 0 {
+                let Some(contract) = self.next_contract(
+                    user,
+                    &needs,
+                    preferences.get(user.id()).copied(),
+                    user_missing_office_days,
+                    &office_candidates,
+                    &calendar,
+                )?
+                else {
+                    log::log!(
+                        self.progress_level(),
+                        "User {} still needs to lock {} more days",
+                        user.id(),
+                        user_missing_office_days
+                    );
 
-            let user_missing_office_days = (self.target_days_in_office - user_total_office_days)
-                .max(CONTRACT_DURATION_MINIMUM_DAYS);
+                    continue 'users;
+                };
 
-            if user_total_office_days >= self.target_days_in_office {
-                log::info!(
-                    "User {} has locked all necessary days, switching to next user",
+                log::log!(
+                    self.progress_level(),
+                    "> Locking {} positions of office {} from {} to {} for user {}",
+                    contract.positions(),
+                    contract.office(),
+                    contract.start(),
+                    contract.end(),
                     user.id()
                 );
 
-                continue 'users;
+                if let Err(err) = calendar.book(&contract) {
+                    log::error!(
+                        "Tried to book an office but failed ({err}). Skipping to next user"
+                    );
+
+                    continue 'users;
+                }
+
+                user_missing_office_days =
+                    user_missing_office_days.saturating_sub(contract.period().days());
+                contracts.push(contract);
             }
 
-            log::info!("User {} has {user_total_office_days}/{} days of locked office, which means we still need to lock at least {user_missing_office_days} days!", user.id(), self.target_days_in_office);
-
-            for office_candidate in office_candidates {
-                'user_availability: for user_availability in user_ranges_to_fill.iter() {
-                    let contracts_for_office = contracts
-                        .iter()
-                        .filter(|contract| contract.office() == office_candidate.id())
-                        .collect::<Vec<_>>();
-
-                    let office_unavailabilities = contracts_for_office
-                        .iter()
-                        .map(Deref::deref)
-                        .map(DateRange::from)
-                        .collect::<Vec<_>>();
-
-                    let office_availabilities = invert_ranges_in_boundary(
-                        office_unavailabilities.iter(),
-                        self.start,
-                        self.end,
-                    );
+            log::log!(
+                self.progress_level(),
+                "User {} has locked all necessary days, switching to next user",
+                user.id()
+            );
+        }
+
+        Ok(merge_adjacent_contracts(contracts, self.progress_level()))
+    }
+
+    pub(crate) fn period(&self) -> DateRange {
+        DateRange::new(self.start, self.end)
+    }
+
+    /// The contract the guest can get in one of the offices meeting their required preferences
+    /// during their first availability possible, chosen following the simulation's strategy
+    fn next_contract(
+        &self,
+        guest: &User,
+        needs: &GuestNeeds,
+        preferences: Option<&GuestPreferences>,
+        missing_days: usize,
+        office_candidates: &[(&Office, &User)],
+        calendar: &OfficeCalendar,
+    ) -> color_eyre::Result<Option<Contract>> {
+        for availability in calendar
+            .guest_availabilities(guest.id(), self.period())
+            .ranges()
+        {
+            let mut best: Option<(Contract, f64)> = None;
+
+            for (office, host) in office_candidates.iter().copied() {
+                let Some((period, positions)) =
+                    self.fit(office, needs, missing_days, *availability, calendar)?
+                else {
+                    continue;
+                };
+
+                let contract = match Contract::for_office(
+                    office,
+                    host,
+                    guest,
+                    positions,
+                    *period.start(),
+                    *period.end(),
+                ) {
+                    Ok(contract) => contract,
+                    Err(err) => {
+                        log::warn!(
+                            "Skipping office {} for guest {}, no contract possible ({err})",
+                            office.id(),
+                            guest.id()
+                        );
+                        continue;
+                    }
+                };
 
-                    // The 3 vars above should be recalculated every time a new contract is added
-                    // but for simplicity we'll just recalculate them on each loop
-
-                    for office_availability in office_availabilities.iter() {
-                        let overlap_start = user_availability.start.max(office_availability.start);
-                        let overlap_end = user_availability.end.min(office_availability.end);
-
-                        if overlap_start < overlap_end {
-                            let contract_end = overlap_start
-                                .checked_add_days(Days::new(user_missing_office_days as u64))
-                                .unwrap()
-                                .min(overlap_end);
-
-                            log::info!(
-                                "> Trying to lock office {} from {} to {} for user {}",
-                                office_candidate.id(),
-                                overlap_start,
-                                contract_end,
-                                user.id()
-                            );
-
-                            let contract = match Contract::for_office(
-                                office_candidate,
-                                *user.id(),
-                                overlap_start,
-                                contract_end,
-                            ) {
-                                Ok(contract) => contract,
-                                Err(err) => {
-                                    log::error!("Tried to create a contract but failed ({err}). Skipping to next scenario");
-
-                                    continue;
-                                }
-                            };
-
-                            let days_locked = (contract_end - overlap_start).num_days() as usize;
-                            let user_missing_office_days =
-                                user_missing_office_days.saturating_sub(days_locked);
-
-                            contracts.push(contract);
-
-                            if user_missing_office_days == 0 {
-                                log::info!(
-                                    "User {} has locked all necessary days, switching to next user",
-                                    user.id()
-                                );
-
-                                continue 'users;
-                            }
-
-                            continue 'user_availability;
+                let score = match preferences {
+                    Some(preferences) => {
+                        let evaluation = preferences.evaluate(office, *contract.rent());
+                        if !evaluation.is_acceptable() {
+                            continue;
                         }
+
+                        evaluation.score()
                     }
+                    None => 1.0,
+                };
 
-                    if user_missing_office_days > 0 {
-                        log::info!(
-                            "User {} still needs to lock {} more days",
-                            user.id(),
-                            user_missing_office_days
-                        );
+                if best.as_ref().is_none_or(|(best, best_score)| {
+                    self.strategy
+                        .prefers((*contract.start(), score), (*best.start(), *best_score))
+                }) {
+                    best = Some((contract, score));
+                }
+
+                // no other office can start earlier or meet more preferences
+                if best.as_ref().is_some_and(|(best, best_score)| {
+                    best.start() == availability.start() && *best_score >= 1.0
+                }) {
+                    break;
+                }
+            }
+
+            if let Some((contract, _)) = best {
+                let start = *contract.start();
+
+                return Ok(Some(match self.indexation {
+                    Some(index) => {
+                        contract.with_indexation(IndexationClause::yearly_from(index, start))
                     }
+                    None => contract,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The first period of `availability` the guest's needs fit in the office for, as long as
+    /// possible up to the missing days, and the positions it needs during that period. The period
+    /// ends when the guest outgrows the office
+    fn fit(
+        &self,
+        office: &Office,
+        needs: &GuestNeeds,
+        missing_days: usize,
+        availability: DateRange,
+        calendar: &OfficeCalendar,
+    ) -> color_eyre::Result<Option<(DateRange, usize)>> {
+        let capacity = *office.available_positions();
+        let office_availabilities = calendar.office_availabilities(
+            office.id(),
+            needs.lowest(&availability),
+            availability,
+        )?;
+
+        for office_availability in office_availabilities.ranges() {
+            let start = *office_availability.start();
+            let minimum_end = Contract::minimum_end(start);
+
+            if needs.on(start) > capacity {
+                continue;
+            }
+
+            let mut end = start
+                .checked_add_days(Days::new(missing_days as u64))
+                .unwrap()
+                .max(minimum_end)
+                .min(*availability.end());
+            if let Some(outgrown_on) = needs.outgrows_on(capacity, start) {
+                end = end.min(outgrown_on);
+            }
+
+            // shortened until the office has the positions needed during the whole period
+            while end >= minimum_end {
+                let period = DateRange::new(start, end);
+                let positions = needs.peak(&period);
+
+                match calendar
+                    .occupancy(office.id(), period)?
+                    .into_iter()
+                    .find(|(_, remaining)| *remaining < positions)
+                {
+                    Some((full, _)) => end = *full.start(),
+                    None => return Ok(Some((period, positions))),
                 }
             }
         }
 
-        Ok(contracts)
+        Ok(None)
+    }
+
+    /// Days in the target months following the simulation start
+    pub(crate) fn target_days_in_office(&self) -> usize {
+        let target_end = self
+            .start
+            .checked_add_months(Months::new(self.target_months_in_office))
+            .unwrap();
+
+        (target_end - self.start).num_days() as usize
     }
 }
 
-async fn persist(
-    offices: Vec<&Office>,
-    contracts: Vec<&Contract>,
-    pool: &PgPool,
-) -> Result<(), sqlx::Error> {
-    let mut tx = pool.begin().await?;
+/// Positions a guest needs over time, following their team's headcount if they have one
+enum GuestNeeds<'a> {
+    Team(&'a Team),
+    Fixed(usize),
+}
 
-    for office in offices {
-        for contract in contracts
-            .iter()
-            .filter(|contract| contract.office() == office.id())
-        {
-            persistence::persist_contract(contract, office, &mut *tx).await?;
+impl GuestNeeds<'_> {
+    fn on(&self, date: NaiveDate) -> usize {
+        match self {
+            Self::Team(team) => team.headcount_on(date),
+            Self::Fixed(positions) => *positions,
+        }
+    }
+
+    fn peak(&self, period: &DateRange) -> usize {
+        match self {
+            Self::Team(team) => team.peak_headcount(period),
+            Self::Fixed(positions) => *positions,
+        }
+    }
+
+    fn lowest(&self, period: &DateRange) -> usize {
+        match self {
+            Self::Team(team) => team.lowest_headcount(period),
+            Self::Fixed(positions) => *positions,
+        }
+    }
+
+    fn outgrows_on(&self, positions: usize, after: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Team(team) => team.outgrows_on(positions, after),
+            Self::Fixed(_) => None,
+        }
+    }
+}
+
+/// Revisions against quarters missing from the index table keep the latest earlier value, which
+/// understates the rent
+fn warn_unpublished_quarters(contracts: &[Contract], index_table: &IndexTable) {
+    let mut unpublished = BTreeMap::<RentIndex, BTreeSet<Quarter>>::new();
+    for contract in contracts {
+        if let Some(indexation) = contract.indexation() {
+            unpublished
+                .entry(*indexation.index())
+                .or_default()
+                .extend(indexation.unpublished_quarters(*contract.end(), index_table));
         }
     }
 
-    tx.commit().await
+    for (index, quarters) in unpublished {
+        if !quarters.is_empty() {
+            log::warn!(
+                "No {index} value for {}, rents revised against them use the latest earlier value",
+                quarters
+                    .iter()
+                    .map(Quarter::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+}
+
+/// A calendar of the offices with the existing contracts and windows booked, contracts conflicting
+/// with another one being booked anyway so that their dates are taken nonetheless
+pub(crate) fn seed_calendar<'a>(
+    offices: impl IntoIterator<Item = &'a Office>,
+    contracts: impl IntoIterator<Item = &'a Contract>,
+    windows: impl IntoIterator<Item = &'a OfficeWindow>,
+) -> OfficeCalendar {
+    let mut calendar = OfficeCalendar::new(offices);
+    for contract in contracts {
+        if let Err(err) = calendar.book(contract) {
+            log::warn!(
+                "Existing contract {} conflicts with another one ({err}), booking it anyway",
+                contract.id()
+            );
+            if let Err(err) = calendar.force_book(contract) {
+                log::warn!("Existing contract {} was left out ({err})", contract.id());
+            }
+        }
+    }
+
+    // after the existing contracts, which are kept even when they overlap a blackout
+    for window in windows {
+        if let Err(err) = calendar.add_window(window) {
+            log::warn!("Window {} was left out ({err})", window.id());
+        }
+    }
+
+    calendar
+}
+
+/// The offices guests can rent, subdivided offices only being rented through their splits
+pub(crate) fn rentable_offices<'a>(offices: &[&'a Office]) -> Vec<&'a Office> {
+    let subdivided = offices
+        .iter()
+        .filter_map(|office| *office.parent_office())
+        .collect::<HashSet<_>>();
+
+    offices
+        .iter()
+        .copied()
+        .filter(|office| {
+            office.parent_office().is_some() || !subdivided.contains(&office.real_office())
+        })
+        .collect()
+}
+
+/// Generated drafts following a contract for the same guest and office are merged into it, as a
+/// longer draft or an extension
+fn merge_adjacent_contracts(contracts: Vec<Contract>, level: log::Level) -> Vec<Contract> {
+    let mut contracts = contracts;
+    contracts.sort_by_key(|contract| {
+        (
+            *contract.guest().uuid(),
+            *contract.office().uuid(),
+            *contract.start(),
+        )
+    });
+
+    let mut merged: Vec<Contract> = Vec::with_capacity(contracts.len());
+
+    for contract in contracts {
+        if let Some(previous) = merged.last_mut() {
+            let reason = Some(format!("Merged with simulated contract {}", contract.id()));
+
+            if previous.merge_following(&contract, reason).is_ok() {
+                log::log!(
+                    level,
+                    "Merged contract {} into contract {}, now ending on {}",
+                    contract.id(),
+                    previous.id(),
+                    previous.end()
+                );
+
+                continue;
+            }
+        }
+
+        merged.push(contract);
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+    use sora_model::{
+        contract::ContractState,
+        geo::Coordinates,
+        office::{OfficeSplit, WindowKind},
+        preference::{Location, Preference, Strictness},
+        team::{Headcount, Team},
+        test_support::{self, date, user},
+        user::Role,
+    };
+
+    use crate::verify::assert_verified;
+
+    use super::*;
+
+    fn create_contract(
+        host: &User,
+        guest: &User,
+        office: &Office,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Contract {
+        Contract::new(host, guest, office, 10, 100000, start, end).unwrap()
+    }
+
+    #[rstest]
+    #[case(10, true)]
+    #[case(50, true)]
+    #[case(60, false)]
+    fn test_guests_are_packed_in_offices(#[case] guest_positions: usize, #[case] shared: bool) {
+        let host = user(Role::Host);
+        let guests = [user(Role::Guest), user(Role::Guest)];
+        let office = test_support::office(&host);
+        let simulation = Simulation::new(date(2025, 1, 1), 24, guest_positions, None).unwrap();
+
+        let contracts = simulation
+            .simulate(
+                vec![&host, &guests[0], &guests[1]],
+                vec![&office],
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap();
+        assert_verified(
+            &simulation,
+            &[&host, &guests[0], &guests[1]],
+            &[&office],
+            &contracts,
+            0,
+        );
+
+        assert_eq!(2, contracts.len());
+        assert!(contracts
+            .iter()
+            .all(|contract| *contract.positions() == guest_positions));
+        assert_eq!(
+            shared,
+            contracts[0].period().overlaps(&contracts[1].period())
+        );
+    }
+
+    #[test]
+    fn test_invalid_contracts_are_skipped() {
+        let (host, guest) = (user(Role::Host), user(Role::Guest));
+        let office = test_support::office(&host);
+        let simulation = Simulation::new(date(2025, 1, 1), 24, 0, None).unwrap();
+
+        assert_eq!(
+            Vec::<Contract>::new(),
+            simulation
+                .simulate(
+                    vec![&host, &guest],
+                    vec![&office],
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_teams_move_when_outgrowing_a_split() {
+        let (host, guest) = (user(Role::Host), user(Role::Guest));
+        let team = Team::new(
+            "Équipe".to_string(),
+            &guest,
+            vec![
+                Headcount::new(date(2025, 1, 1), 30),
+                Headcount::new(date(2025, 7, 1), 80),
+            ],
+        )
+        .unwrap();
+        let (subdivided, other) = (test_support::office(&host), test_support::office(&host));
+        let splits = subdivided
+            .split(vec![
+                OfficeSplit::new(50, 100).unwrap(),
+                OfficeSplit::new(50, 100).unwrap(),
+            ])
+            .unwrap();
+        let simulation = Simulation::new(date(2025, 1, 1), 24, 10, None).unwrap();
+
+        let mut contracts = simulation
+            .simulate(
+                vec![&host, &guest],
+                vec![&subdivided, &splits[0], &splits[1], &other],
+                Vec::new(),
+                vec![&team],
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap();
+        assert_verified(
+            &simulation,
+            &[&host, &guest],
+            &[&subdivided, &splits[0], &splits[1], &other],
+            &contracts,
+            0,
+        );
+        contracts.sort_by_key(|contract| *contract.start());
+
+        assert_eq!(2, contracts.len());
+        assert_eq!(splits[0].id(), contracts[0].office());
+        assert_eq!(30, *contracts[0].positions());
+        assert_eq!(
+            DateRange::new(date(2025, 1, 1), date(2025, 7, 1)),
+            contracts[0].period()
+        );
+        assert_eq!(other.id(), contracts[1].office());
+        assert_eq!(80, *contracts[1].positions());
+        assert_eq!(
+            DateRange::new(date(2025, 7, 1), date(2026, 1, 1)),
+            contracts[1].period()
+        );
+    }
+
+    #[test]
+    fn test_offices_are_not_rented_while_closed() {
+        let (host, guest) = (user(Role::Host), user(Role::Guest));
+        let office = test_support::office(&host);
+        let blackout = OfficeWindow::new(
+            &office,
+            WindowKind::Blackout,
+            DateRange::new(date(2025, 1, 1), date(2025, 3, 1)),
+            Some("Renovation".to_string()),
+        )
+        .unwrap();
+        let simulation = Simulation::new(date(2025, 1, 1), 24, 10, None).unwrap();
+
+        let contracts = simulation
+            .simulate(
+                vec![&host, &guest],
+                vec![&office],
+                vec![&blackout],
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap();
+        assert_verified(&simulation, &[&host, &guest], &[&office], &contracts, 0);
+
+        assert_eq!(1, contracts.len());
+        assert_eq!(
+            DateRange::new(date(2025, 3, 1), date(2026, 3, 1)),
+            contracts[0].period()
+        );
+    }
+
+    /// Lyon is about 390 km away from the office in Paris
+    fn near_lyon(strictness: Strictness) -> Option<Preference<Location>> {
+        Some(Preference::new(
+            Location::new(Coordinates::new(45.76, 4.84).unwrap(), 10.0),
+            strictness,
+        ))
+    }
+
+    #[rstest]
+    #[case(near_lyon(Strictness::Required), None, Some(true))]
+    #[case(near_lyon(Strictness::Preferred), None, Some(true))]
+    // 10 positions cost 5000€ in Paris and 4000€ in Lyon
+    #[case(
+        None,
+        Some(Preference::new(500000, Strictness::Preferred)),
+        Some(false)
+    )]
+    #[case(None, Some(Preference::new(499999, Strictness::Preferred)), Some(true))]
+    #[case(
+        None,
+        Some(Preference::new(399999, Strictness::Preferred)),
+        Some(false)
+    )]
+    #[case(None, Some(Preference::new(399999, Strictness::Required)), None)]
+    fn test_guest_preferences(
+        #[case] location: Option<Preference<Location>>,
+        #[case] budget: Option<Preference<usize>>,
+        #[case] in_lyon: Option<bool>,
+    ) {
+        let (host, guest) = (user(Role::Host), user(Role::Guest));
+        let paris = test_support::office(&host);
+        let lyon = test_support::lyon_office(&host);
+        let preferences = GuestPreferences::new(&guest, location, budget, None).unwrap();
+        let simulation = Simulation::new(date(2025, 1, 1), 24, 10, None).unwrap();
+
+        let contracts = simulation
+            .simulate(
+                vec![&host, &guest],
+                vec![&paris, &lyon],
+                Vec::new(),
+                Vec::new(),
+                vec![&preferences],
+                Vec::new(),
+            )
+            .unwrap();
+        assert_verified(
+            &simulation,
+            &[&host, &guest],
+            &[&paris, &lyon],
+            &contracts,
+            in_lyon.is_none() as usize,
+        );
+
+        assert_eq!(
+            in_lyon,
+            contracts
+                .first()
+                .map(|contract| contract.office() == lyon.id())
+        );
+        assert!(contracts.iter().all(|contract| *contract.rent()
+            == if contract.office() == lyon.id() {
+                400000
+            } else {
+                500000
+            }));
+    }
+
+    #[rstest]
+    #[case(Strategy::Earliest, false)]
+    #[case(Strategy::Spread, false)]
+    #[case(Strategy::Preferred, true)]
+    fn test_strategies(#[case] strategy: Strategy, #[case] in_lyon: bool) {
+        let (host, guest) = (user(Role::Host), user(Role::Guest));
+        let paris = test_support::office(&host);
+        let lyon = test_support::lyon_office(&host);
+        let renovation = OfficeWindow::new(
+            &lyon,
+            WindowKind::Blackout,
+            DateRange::new(date(2025, 1, 1), date(2025, 7, 1)),
+            None,
+        )
+        .unwrap();
+        let preferences =
+            GuestPreferences::new(&guest, near_lyon(Strictness::Preferred), None, None).unwrap();
+        let simulation = Simulation::new(date(2025, 1, 1), 24, 10, None)
+            .unwrap()
+            .with_strategy(strategy);
+
+        let contracts = simulation
+            .simulate(
+                vec![&host, &guest],
+                vec![&paris, &lyon],
+                vec![&renovation],
+                Vec::new(),
+                vec![&preferences],
+                Vec::new(),
+            )
+            .unwrap();
+        assert_verified(
+            &simulation,
+            &[&host, &guest],
+            &[&paris, &lyon],
+            &contracts,
+            0,
+        );
+
+        assert_eq!(in_lyon, contracts[0].office() == lyon.id());
+        assert_eq!(
+            if in_lyon {
+                date(2025, 7, 1)
+            } else {
+                date(2025, 1, 1)
+            },
+            *contracts[0].start()
+        );
+    }
+
+    #[test]
+    fn test_seeds_shuffle_guests() {
+        let host = user(Role::Host);
+        let guests = [user(Role::Guest), user(Role::Guest)];
+        let office = test_support::office(&host);
+
+        // both guests cannot share the office, the first one getting it first
+        let first_guest = |seed| {
+            let simulation = Simulation::new(date(2025, 1, 1), 24, 60, None)
+                .unwrap()
+                .with_seed(seed);
+            let contracts = simulation
+                .simulate(
+                    vec![&host, &guests[0], &guests[1]],
+                    vec![&office],
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                )
+                .unwrap();
+            assert_verified(
+                &simulation,
+                &[&host, &guests[0], &guests[1]],
+                &[&office],
+                &contracts,
+                0,
+            );
+
+            contracts
+                .into_iter()
+                .find(|contract| *contract.start() == date(2025, 1, 1))
+                .map(|contract| *contract.guest())
+                .unwrap()
+        };
+
+        let firsts = (0..20).map(first_guest).collect::<Vec<_>>();
+        assert_eq!(firsts, (0..20).map(first_guest).collect::<Vec<_>>());
+        assert!(guests.iter().all(|guest| firsts.contains(guest.id())));
+    }
+
+    #[test]
+    fn test_merge_adjacent_contracts() {
+        let (host, guest) = (user(Role::Host), user(Role::Guest));
+        let office = test_support::office(&host);
+        let mut signed =
+            create_contract(&host, &guest, &office, date(2025, 1, 1), date(2025, 6, 1));
+        signed
+            .transition(ContractState::Signed, Utc::now(), None)
+            .unwrap();
+
+        let merged = merge_adjacent_contracts(
+            vec![
+                create_contract(&host, &guest, &office, date(2025, 10, 1), date(2026, 3, 1)),
+                signed,
+                create_contract(&host, &guest, &office, date(2025, 6, 1), date(2025, 10, 1)),
+                create_contract(
+                    &host,
+                    &user(Role::Guest),
+                    &office,
+                    date(2026, 3, 1),
+                    date(2026, 8, 1),
+                ),
+            ],
+            log::Level::Info,
+        );
+
+        assert_eq!(2, merged.len());
+        let merged = merged
+            .iter()
+            .find(|contract| contract.guest() == guest.id())
+            .unwrap();
+        assert_eq!(date(2026, 3, 1), *merged.end());
+        assert_eq!(2, merged.amendments().len());
+    }
+
+    #[rstest]
+    #[case(RentIndex::Ilat)]
+    #[case(RentIndex::Ilc)]
+    #[case(RentIndex::Icc)]
+    fn test_default_index_values(#[case] index: RentIndex) {
+        let table = IndexTable::from_csv(crate::DEFAULT_INDEX_VALUES).unwrap();
+
+        assert!(table
+            .value_at(index, Quarter::new(2025, 4).unwrap())
+            .is_some());
+    }
 }
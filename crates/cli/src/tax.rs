@@ -0,0 +1,113 @@
+use chrono::NaiveDate;
+use sora_model::{
+    tax::{TaxRule, TaxRuleId},
+    user::UserId,
+};
+use sqlx::PgPool;
+
+use crate::{
+    persistence::{
+        delete_tax_opt_in, delete_tax_rule, load_tax_rule_set, load_users, persist_tax_opt_in,
+        persist_tax_rule,
+    },
+    TaxCommand,
+};
+
+pub async fn tax(pool: &PgPool, command: TaxCommand) -> color_eyre::Result<()> {
+    match command {
+        TaxCommand::AddRule { rate, start, end } => add_rule(pool, rate, start, end).await,
+        TaxCommand::Rules => rules(pool).await,
+        TaxCommand::RemoveRule { id } => remove_rule(pool, id).await,
+        TaxCommand::OptIn { host } => opt_in(pool, host).await,
+        TaxCommand::OptOut { host } => opt_out(pool, host).await,
+    }
+}
+
+async fn add_rule(
+    pool: &PgPool,
+    rate: usize,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+) -> color_eyre::Result<()> {
+    let mut rule_set = load_tax_rule_set(pool).await?;
+    let rule = rule_set.add(TaxRule::new(rate, start, end)?)?;
+    persist_tax_rule(rule, pool).await?;
+
+    log::info!("Added tax rule {} ({})", rule.id(), describe(rule));
+
+    Ok(())
+}
+
+async fn rules(pool: &PgPool) -> color_eyre::Result<()> {
+    let rule_set = load_tax_rule_set(pool).await?;
+
+    for rule in rule_set.rules() {
+        println!("{} {}", rule.id(), describe(rule));
+    }
+    for host in rule_set.opted_in_hosts() {
+        println!("Host {host} opted in to VAT");
+    }
+
+    Ok(())
+}
+
+async fn remove_rule(pool: &PgPool, id: TaxRuleId) -> color_eyre::Result<()> {
+    if !delete_tax_rule(&id, pool).await? {
+        return Err(TaxCommandError::NoSuchRule(id).into());
+    }
+
+    log::info!("Removed tax rule {id}");
+
+    Ok(())
+}
+
+async fn opt_in(pool: &PgPool, host: UserId) -> color_eyre::Result<()> {
+    let user = load_users(pool)
+        .await?
+        .into_iter()
+        .find(|user| *user.id() == host)
+        .ok_or(TaxCommandError::NoSuchUser(host))?;
+
+    if !user.role().can_host() {
+        return Err(TaxCommandError::NotAHost(host).into());
+    }
+
+    persist_tax_opt_in(&host, pool).await?;
+
+    log::info!("Host {host} opted in to VAT");
+
+    Ok(())
+}
+
+async fn opt_out(pool: &PgPool, host: UserId) -> color_eyre::Result<()> {
+    if !delete_tax_opt_in(&host, pool).await? {
+        return Err(TaxCommandError::NotOptedIn(host).into());
+    }
+
+    log::info!("Host {host} opted out of VAT");
+
+    Ok(())
+}
+
+fn describe(rule: &TaxRule) -> String {
+    format!(
+        "{}% from {}{}",
+        *rule.rate() as f32 / 100.0,
+        rule.start(),
+        rule.end()
+            .map(|end| format!(" to {end}"))
+            .unwrap_or_default()
+    )
+}
+
+#[derive(Debug, thiserror::Error)]
+enum TaxCommandError {
+    #[error("No tax rule found with id {0}")]
+    NoSuchRule(TaxRuleId),
+    #[error("No user found with id {0}")]
+    NoSuchUser(UserId),
+    #[error("User {0} is not a host")]
+    NotAHost(UserId),
+    #[error("Host {0} has not opted in to VAT")]
+    NotOptedIn(UserId),
+}
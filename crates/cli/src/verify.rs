@@ -0,0 +1,709 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, Utc};
+use sora_model::{
+    contract::{Contract, ContractId, ContractState},
+    interval::{DateRange, IntervalSet},
+    office::{Office, OfficeId},
+    user::{User, UserId},
+};
+use sqlx::PgPool;
+
+use crate::{
+    persistence::{load_contracts, load_offices, load_simulation_run, load_users},
+    runs::SimulationRunId,
+    simulation::Simulation,
+};
+
+/// An invariant a simulation's solution breaks
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+pub enum Violation {
+    #[error("Contract {contract} refers to unknown office {office}")]
+    UnknownOffice {
+        contract: ContractId,
+        office: OfficeId,
+    },
+    #[error("Contract {contract} refers to unknown user {user}")]
+    UnknownUser { contract: ContractId, user: UserId },
+    #[error("Contract {contract} brings office {office} to {positions} positions booked out of {capacity} on {on}")]
+    OfficeOverbooked {
+        contract: ContractId,
+        office: OfficeId,
+        on: NaiveDate,
+        positions: usize,
+        capacity: usize,
+    },
+    #[error("Contract {contract} books split {office} while contract {other} books its real office {real_office}")]
+    RelatedOfficesBooked {
+        contract: ContractId,
+        office: OfficeId,
+        other: ContractId,
+        real_office: OfficeId,
+    },
+    #[error("Guest {guest} occupies two offices at once with contracts {contract} and {other}")]
+    GuestOverlap {
+        guest: UserId,
+        contract: ContractId,
+        other: ContractId,
+    },
+    #[error(
+        "Contract {contract} ends on {end}, before the minimum duration is over on {minimum_end}"
+    )]
+    TooShort {
+        contract: ContractId,
+        end: NaiveDate,
+        minimum_end: NaiveDate,
+    },
+    #[error(
+        "Contract {contract} is hosted by {host}, who does not own office {office} or cannot host"
+    )]
+    NotHostedByOwner {
+        contract: ContractId,
+        host: UserId,
+        office: OfficeId,
+    },
+    #[error("Contract {contract} is rented by {guest}, who cannot rent or owns the office")]
+    InvalidGuest { contract: ContractId, guest: UserId },
+    #[error("Contract {contract} runs outside of the simulated period {period}")]
+    OutOfPeriod {
+        contract: ContractId,
+        period: DateRange,
+    },
+    #[error("Guest {guest} gets {days} days in office out of the {target} targeted")]
+    TargetMissed {
+        guest: UserId,
+        days: usize,
+        target: usize,
+    },
+}
+
+impl Violation {
+    /// Whether the solution is valid but doesn't get a guest all of their target days, e.g. when
+    /// there are not enough offices
+    pub fn is_missed_target(&self) -> bool {
+        matches!(self, Self::TargetMissed { .. })
+    }
+}
+
+/// Checks a simulation's contracts against the invariants every solution keeps, independently of
+/// the calendar the simulation books offices with: offices are not booked beyond their positions,
+/// a real office and its splits are not booked at once, guests don't occupy two offices at once,
+/// contracts last at least the minimum duration, are hosted by the office's owner and rented by a
+/// guest who doesn't own it, contracts overlap the simulated period and drafts, generated by the
+/// simulation, lie within it, and every guest gets their target days
+pub fn verify(
+    simulation: &Simulation,
+    users: &[&User],
+    offices: &[&Office],
+    contracts: &[Contract],
+) -> Vec<Violation> {
+    let users = users
+        .iter()
+        .map(|user| (*user.id(), *user))
+        .collect::<HashMap<_, _>>();
+    let offices = offices
+        .iter()
+        .map(|office| (*office.id(), *office))
+        .collect::<HashMap<_, _>>();
+    let period = simulation.period();
+
+    let mut violations = Vec::new();
+
+    for contract in contracts {
+        violations.extend(check_contract(contract, &users, &offices, period));
+    }
+
+    // (contract, guest, office, positions, period) of every office occupied
+    let occupancies = contracts
+        .iter()
+        .flat_map(|contract| {
+            contract
+                .occupancies()
+                .into_iter()
+                .filter(|(office, _)| offices.contains_key(office))
+                .map(|(office, period)| Occupancy {
+                    contract: *contract.id(),
+                    guest: *contract.guest(),
+                    office,
+                    positions: *contract.positions(),
+                    period,
+                })
+        })
+        .collect::<Vec<_>>();
+
+    violations.extend(check_capacities(&occupancies, &offices));
+    violations.extend(check_related_offices(&occupancies, &offices));
+    violations.extend(check_guests(&occupancies));
+    violations.extend(check_targets(simulation, &users, contracts));
+
+    violations
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Occupancy {
+    contract: ContractId,
+    guest: UserId,
+    office: OfficeId,
+    positions: usize,
+    period: DateRange,
+}
+
+fn check_contract(
+    contract: &Contract,
+    users: &HashMap<UserId, &User>,
+    offices: &HashMap<OfficeId, &Office>,
+    period: DateRange,
+) -> Vec<Violation> {
+    let id = *contract.id();
+    let mut violations = Vec::new();
+
+    let minimum_end = Contract::minimum_end(*contract.start());
+    if *contract.end() < minimum_end {
+        violations.push(Violation::TooShort {
+            contract: id,
+            end: *contract.end(),
+            minimum_end,
+        });
+    }
+
+    let contract_period = DateRange::new(*contract.start(), *contract.end());
+    if !contract_period.overlaps(&period)
+        || (*contract.state() == ContractState::Draft && !contract_period.is_contained_in(&period))
+    {
+        violations.push(Violation::OutOfPeriod {
+            contract: id,
+            period,
+        });
+    }
+
+    for (office, _) in contract.occupancies() {
+        if !offices.contains_key(&office) {
+            violations.push(Violation::UnknownOffice {
+                contract: id,
+                office,
+            });
+        }
+    }
+
+    for user in [contract.host(), contract.guest()] {
+        if !users.contains_key(user) {
+            violations.push(Violation::UnknownUser {
+                contract: id,
+                user: *user,
+            });
+        }
+    }
+
+    if let (Some(office), Some(host)) = (offices.get(contract.office()), users.get(contract.host()))
+    {
+        if office.owner() != host.id() || !host.role().can_host() {
+            violations.push(Violation::NotHostedByOwner {
+                contract: id,
+                host: *host.id(),
+                office: *office.id(),
+            });
+        }
+    }
+
+    if let (Some(office), Some(guest)) =
+        (offices.get(contract.office()), users.get(contract.guest()))
+    {
+        if office.owner() == guest.id() || !guest.role().can_rent() {
+            violations.push(Violation::InvalidGuest {
+                contract: id,
+                guest: *guest.id(),
+            });
+        }
+    }
+
+    violations
+}
+
+/// Positions booked in each office over time, going through contract starts and ends in order
+fn check_capacities(
+    occupancies: &[Occupancy],
+    offices: &HashMap<OfficeId, &Office>,
+) -> Vec<Violation> {
+    let mut by_office = HashMap::<OfficeId, Vec<&Occupancy>>::new();
+    for occupancy in occupancies {
+        by_office
+            .entry(occupancy.office)
+            .or_default()
+            .push(occupancy);
+    }
+
+    let mut violations = Vec::new();
+
+    for (office, occupancies) in by_office {
+        let capacity = *offices[&office].available_positions();
+
+        // ends before starts on a same date, the office being freed on the end date
+        let mut events = occupancies
+            .iter()
+            .flat_map(|occupancy| {
+                [
+                    (*occupancy.period.end(), false, *occupancy),
+                    (*occupancy.period.start(), true, *occupancy),
+                ]
+            })
+            .collect::<Vec<_>>();
+        events.sort_by_key(|(date, is_start, _)| (*date, *is_start));
+
+        let mut positions = 0;
+        for (date, is_start, occupancy) in events {
+            if !is_start {
+                positions -= occupancy.positions;
+                continue;
+            }
+
+            positions += occupancy.positions;
+            if positions > capacity {
+                violations.push(Violation::OfficeOverbooked {
+                    contract: occupancy.contract,
+                    office,
+                    on: date,
+                    positions,
+                    capacity,
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Splits booked while their real office is
+fn check_related_offices(
+    occupancies: &[Occupancy],
+    offices: &HashMap<OfficeId, &Office>,
+) -> Vec<Violation> {
+    let mut real_offices = HashMap::<OfficeId, Vec<&Occupancy>>::new();
+    for occupancy in occupancies {
+        if matches!(occupancy.office, OfficeId::RealOffice(_)) {
+            real_offices
+                .entry(occupancy.office)
+                .or_default()
+                .push(occupancy);
+        }
+    }
+
+    occupancies
+        .iter()
+        .filter_map(|occupancy| {
+            let real_office = OfficeId::RealOffice((*offices[&occupancy.office].parent_office())?);
+            let other = real_offices
+                .get(&real_office)?
+                .iter()
+                .find(|other| other.period.overlaps(&occupancy.period))?;
+
+            Some(Violation::RelatedOfficesBooked {
+                contract: occupancy.contract,
+                office: occupancy.office,
+                other: other.contract,
+                real_office,
+            })
+        })
+        .collect()
+}
+
+/// Guests occupying an office while they already occupy another one
+fn check_guests(occupancies: &[Occupancy]) -> Vec<Violation> {
+    let mut by_guest = HashMap::<UserId, Vec<&Occupancy>>::new();
+    for occupancy in occupancies {
+        by_guest.entry(occupancy.guest).or_default().push(occupancy);
+    }
+
+    let mut violations = Vec::new();
+
+    for (guest, mut occupancies) in by_guest {
+        occupancies.sort_by_key(|occupancy| *occupancy.period.start());
+
+        // the occupancy ending last so far
+        let mut latest: Option<&Occupancy> = None;
+        for occupancy in occupancies {
+            match latest {
+                Some(previous)
+                    if previous.contract != occupancy.contract
+                        && previous.period.end() > occupancy.period.start() =>
+                {
+                    violations.push(Violation::GuestOverlap {
+                        guest,
+                        contract: occupancy.contract,
+                        other: previous.contract,
+                    });
+                }
+                _ => {}
+            }
+
+            if latest.is_none_or(|previous| occupancy.period.end() > previous.period.end()) {
+                latest = Some(occupancy);
+            }
+        }
+    }
+
+    violations
+}
+
+fn check_targets(
+    simulation: &Simulation,
+    users: &HashMap<UserId, &User>,
+    contracts: &[Contract],
+) -> Vec<Violation> {
+    let target = simulation.target_days_in_office();
+
+    let mut occupied = HashMap::<UserId, IntervalSet>::new();
+    for contract in contracts {
+        occupied
+            .entry(*contract.guest())
+            .or_default()
+            .insert(contract.period());
+    }
+
+    let mut violations = users
+        .values()
+        .filter(|user| user.role().can_rent())
+        .filter_map(|user| {
+            let days = occupied
+                .get(user.id())
+                .map(IntervalSet::total_days)
+                .unwrap_or_default();
+
+            (days < target).then_some(Violation::TargetMissed {
+                guest: *user.id(),
+                days,
+                target,
+            })
+        })
+        .collect::<Vec<_>>();
+    violations.sort_by_key(|violation| violation.to_string());
+
+    violations
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    #[error("Found {0} violations")]
+    Violations(usize),
+    #[error("No simulation run found with id {0}")]
+    NoSuchRun(SimulationRunId),
+}
+
+/// Verifies the contracts stored for the period a simulation started on `start` covers, or the
+/// period of the given run
+pub async fn verify_stored(
+    start: Option<NaiveDate>,
+    duration_in_months: u32,
+    run: Option<SimulationRunId>,
+    allow_missed_targets: bool,
+    pool: &PgPool,
+) -> color_eyre::Result<()> {
+    let simulation = match run {
+        Some(id) => load_simulation_run(&id, pool)
+            .await?
+            .ok_or(VerifyError::NoSuchRun(id))?
+            .simulation()?,
+        None => Simulation::new(
+            start.unwrap_or_else(|| Utc::now().date_naive()),
+            duration_in_months,
+            0,
+            None,
+        )?,
+    };
+    let period = simulation.period();
+
+    let users = load_users(pool).await?;
+    let offices = load_offices(pool).await?;
+    let contracts = load_contracts(*period.start(), *period.end(), pool).await?;
+
+    let violations = verify(
+        &simulation,
+        &users.iter().collect::<Vec<_>>(),
+        &offices.iter().collect::<Vec<_>>(),
+        &contracts,
+    );
+
+    println!(
+        "Verified {} contracts running from {} to {}",
+        contracts.len(),
+        period.start(),
+        period.end()
+    );
+    for violation in violations.iter() {
+        println!("> {violation}");
+    }
+
+    let violations = violations
+        .iter()
+        .filter(|violation| !(allow_missed_targets && violation.is_missed_target()))
+        .count();
+    if violations > 0 {
+        return Err(VerifyError::Violations(violations).into());
+    }
+
+    println!("No violation found");
+
+    Ok(())
+}
+
+/// Checks the solution against every invariant, `missed_targets` guests not getting their
+/// target days in office
+#[cfg(test)]
+pub(crate) fn assert_verified(
+    simulation: &Simulation,
+    users: &[&User],
+    offices: &[&Office],
+    contracts: &[Contract],
+    missed_targets: usize,
+) {
+    let violations = verify(simulation, users, offices, contracts);
+
+    assert_eq!(
+        missed_targets,
+        violations
+            .iter()
+            .filter(|violation| violation.is_missed_target())
+            .count(),
+        "{violations:#?}"
+    );
+    assert!(
+        violations.iter().all(Violation::is_missed_target),
+        "{violations:#?}"
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use sora_model::{
+        id::Identifier,
+        office::OfficeSplit,
+        test_support::{self, date, user},
+        user::Role,
+    };
+
+    use super::*;
+
+    fn contract(
+        office: &Office,
+        host: &User,
+        guest: &User,
+        positions: usize,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Contract {
+        Contract::for_office(office, host, guest, positions, start, end).unwrap()
+    }
+
+    /// A year in office over the two years following 2025-01-01
+    fn simulation() -> Simulation {
+        Simulation::new(date(2025, 1, 1), 24, 10, None).unwrap()
+    }
+
+    #[test]
+    fn test_valid_solution() {
+        let host = user(Role::Host);
+        let guests = [user(Role::Guest), user(Role::Guest)];
+        let office = test_support::office(&host);
+        let contracts = [
+            contract(
+                &office,
+                &host,
+                &guests[0],
+                50,
+                date(2025, 1, 1),
+                date(2026, 1, 1),
+            ),
+            contract(
+                &office,
+                &host,
+                &guests[1],
+                50,
+                date(2025, 6, 1),
+                date(2026, 6, 1),
+            ),
+        ];
+
+        assert_eq!(
+            Vec::<Violation>::new(),
+            verify(
+                &simulation(),
+                &[&host, &guests[0], &guests[1]],
+                &[&office],
+                &contracts
+            )
+        );
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let host = user(Role::Host);
+        let (guest, other_guest) = (user(Role::Guest), user(Role::Guest));
+        let (office, other_office) = (test_support::office(&host), test_support::office(&host));
+        let splits = office
+            .split(vec![
+                OfficeSplit::new(50, 100).unwrap(),
+                OfficeSplit::new(50, 100).unwrap(),
+            ])
+            .unwrap();
+        let contracts = [
+            contract(
+                &splits[0],
+                &host,
+                &guest,
+                30,
+                date(2025, 1, 1),
+                date(2026, 1, 1),
+            ),
+            contract(
+                &splits[0],
+                &host,
+                &other_guest,
+                30,
+                date(2025, 3, 1),
+                date(2026, 1, 1),
+            ),
+            contract(
+                &office,
+                &host,
+                &other_guest,
+                10,
+                date(2026, 1, 1),
+                date(2026, 6, 1),
+            ),
+            contract(
+                &splits[1],
+                &host,
+                &guest,
+                10,
+                date(2026, 3, 1),
+                date(2026, 9, 1),
+            ),
+            contract(
+                &other_office,
+                &host,
+                &guest,
+                10,
+                date(2026, 8, 1),
+                date(2026, 12, 1),
+            ),
+        ];
+
+        let mut violations = verify(
+            &simulation(),
+            &[&host, &guest, &other_guest],
+            &[&office, &splits[0], &splits[1], &other_office],
+            &contracts,
+        );
+        violations.retain(|violation| !violation.is_missed_target());
+        violations.sort_by_key(|violation| violation.to_string());
+
+        let mut expected = vec![
+            Violation::OfficeOverbooked {
+                contract: *contracts[1].id(),
+                office: *splits[0].id(),
+                on: date(2025, 3, 1),
+                positions: 60,
+                capacity: 50,
+            },
+            Violation::RelatedOfficesBooked {
+                contract: *contracts[3].id(),
+                office: *splits[1].id(),
+                other: *contracts[2].id(),
+                real_office: *office.id(),
+            },
+            Violation::GuestOverlap {
+                guest: *guest.id(),
+                contract: *contracts[4].id(),
+                other: *contracts[3].id(),
+            },
+        ];
+        expected.sort_by_key(|violation| violation.to_string());
+        assert_eq!(expected, violations);
+    }
+
+    #[test]
+    fn test_contract_checks() {
+        let (host, other_host) = (user(Role::Host), user(Role::Host));
+        let (guest, idle_guest) = (user(Role::Guest), user(Role::Guest));
+        let office = test_support::office(&host);
+        let unchecked = |host: &User, start, end, state| {
+            Contract::new_unchecked(
+                ContractId::new(),
+                Utc::now(),
+                *host.id(),
+                *guest.id(),
+                *office.id(),
+                10,
+                500000,
+                start,
+                end,
+                None,
+                state,
+                Vec::new(),
+                None,
+                Vec::new(),
+            )
+        };
+        let contracts = [
+            unchecked(
+                &other_host,
+                date(2025, 1, 1),
+                date(2025, 5, 1),
+                ContractState::Draft,
+            ),
+            unchecked(
+                &host,
+                date(2025, 5, 1),
+                date(2025, 7, 1),
+                ContractState::Draft,
+            ),
+            unchecked(
+                &host,
+                date(2026, 7, 1),
+                date(2027, 7, 1),
+                ContractState::Draft,
+            ),
+            unchecked(
+                &host,
+                date(2024, 1, 1),
+                date(2024, 12, 1),
+                ContractState::Signed,
+            ),
+        ];
+
+        let violations = verify(
+            &simulation(),
+            &[&host, &other_host, &guest, &idle_guest],
+            &[&office],
+            &contracts,
+        );
+
+        assert_eq!(
+            vec![
+                Violation::NotHostedByOwner {
+                    contract: *contracts[0].id(),
+                    host: *other_host.id(),
+                    office: *office.id(),
+                },
+                Violation::TooShort {
+                    contract: *contracts[1].id(),
+                    end: date(2025, 7, 1),
+                    minimum_end: date(2025, 9, 1),
+                },
+                Violation::OutOfPeriod {
+                    contract: *contracts[2].id(),
+                    period: simulation().period(),
+                },
+                Violation::OutOfPeriod {
+                    contract: *contracts[3].id(),
+                    period: simulation().period(),
+                },
+                Violation::TargetMissed {
+                    guest: *idle_guest.id(),
+                    days: 0,
+                    target: 365,
+                },
+            ],
+            violations
+        );
+    }
+}
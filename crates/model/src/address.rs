@@ -0,0 +1,270 @@
+use std::fmt::Display;
+
+/// ISO 3166-1 alpha-2 code of France, the only country postal codes are validated for
+pub const FRANCE: &str = "FR";
+
+/// A postal address, formatted the French way ("10 rue de Rivoli, 75001 Paris")
+#[derive(Debug, Clone, PartialEq, Eq, derive_getters::Getters)]
+pub struct Address {
+    /// Street number, including its suffix if any ("10 bis")
+    number: Option<String>,
+    street: String,
+    postal_code: String,
+    city: String,
+    /// ISO 3166-1 alpha-2 code
+    country: String,
+}
+
+impl Address {
+    pub fn new(
+        number: Option<String>,
+        street: String,
+        postal_code: String,
+        city: String,
+        country: String,
+    ) -> Result<Self, AddressError> {
+        use AddressError::*;
+
+        let number = number
+            .map(|number| number.trim().to_string())
+            .filter(|number| !number.is_empty());
+        let (street, postal_code, city, country) = (
+            street.trim().to_string(),
+            postal_code.trim().to_string(),
+            city.trim().to_string(),
+            country.trim().to_uppercase(),
+        );
+
+        if street.is_empty() {
+            return Err(EmptyStreet);
+        }
+
+        if city.is_empty() {
+            return Err(EmptyCity);
+        }
+
+        if country.len() != 2 || !country.chars().all(|char| char.is_ascii_uppercase()) {
+            return Err(InvalidCountry(country));
+        }
+
+        if postal_code.is_empty()
+            || (country == FRANCE && !is_valid_french_postal_code(&postal_code))
+        {
+            return Err(InvalidPostalCode {
+                postal_code,
+                country,
+            });
+        }
+
+        Ok(Self::new_unchecked(
+            number,
+            street,
+            postal_code,
+            city,
+            country,
+        ))
+    }
+
+    /// An address in France
+    pub fn french(
+        number: Option<String>,
+        street: String,
+        postal_code: String,
+        city: String,
+    ) -> Result<Self, AddressError> {
+        Self::new(number, street, postal_code, city, FRANCE.to_string())
+    }
+
+    /// Whether the address is in the given city, whatever its case, and its postal code starts
+    /// with the given prefix ("75" for Paris)
+    pub fn matches(&self, city: Option<&str>, postal_code: Option<&str>) -> bool {
+        city.is_none_or(|city| self.city.to_lowercase() == city.trim().to_lowercase())
+            && postal_code
+                .is_none_or(|postal_code| self.postal_code.starts_with(postal_code.trim()))
+    }
+
+    pub fn new_unchecked(
+        number: Option<String>,
+        street: String,
+        postal_code: String,
+        city: String,
+        country: String,
+    ) -> Self {
+        Self {
+            number,
+            street,
+            postal_code,
+            city,
+            country,
+        }
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(number) = &self.number {
+            write!(f, "{number} ")?;
+        }
+
+        write!(f, "{}, {} {}", self.street, self.postal_code, self.city)?;
+
+        if self.country != FRANCE {
+            write!(f, ", {}", self.country)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Five digits, starting with the code of a metropolitan department (01 to 95, Corsica being 20),
+/// of an overseas department or collectivity (971 to 978) or of Monaco and the Pacific territories
+/// (980, 986 to 988)
+pub fn is_valid_french_postal_code(postal_code: &str) -> bool {
+    if postal_code.len() != 5 || !postal_code.chars().all(|char| char.is_ascii_digit()) {
+        return false;
+    }
+
+    let department = postal_code[..2].parse::<u32>().unwrap_or_default();
+    let territory = postal_code[..3].parse::<u32>().unwrap_or_default();
+
+    matches!(department, 1..=95) || matches!(territory, 971..=978 | 980 | 986..=988)
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum AddressError {
+    #[error("An address needs a street")]
+    EmptyStreet,
+    #[error("An address needs a city")]
+    EmptyCity,
+    #[error("Invalid country code {0}, expected an ISO 3166-1 alpha-2 code such as \"FR\"")]
+    InvalidCountry(String),
+    #[error("Invalid postal code \"{postal_code}\" for country {country}")]
+    InvalidPostalCode {
+        postal_code: String,
+        country: String,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("75001", true)]
+    #[case("01000", true)]
+    #[case("20000", true)]
+    #[case("95880", true)]
+    #[case("97400", true)]
+    #[case("98000", true)]
+    #[case("00100", false)]
+    #[case("96000", false)]
+    #[case("97900", false)]
+    #[case("98200", false)]
+    #[case("7500", false)]
+    #[case("750012", false)]
+    #[case("75 01", false)]
+    #[case("", false)]
+    fn test_french_postal_code(#[case] postal_code: &str, #[case] is_valid: bool) {
+        assert_eq!(
+            is_valid,
+            Address::french(
+                Some("10".to_string()),
+                "rue de Rivoli".to_string(),
+                postal_code.to_string(),
+                "Paris".to_string(),
+            )
+            .is_ok()
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Some("10"),
+        "rue de Rivoli",
+        "75001",
+        "Paris",
+        "FR",
+        Ok("10 rue de Rivoli, 75001 Paris")
+    )]
+    #[case(
+        None,
+        " place Bellecour ",
+        "69002",
+        "Lyon",
+        "fr",
+        Ok("place Bellecour, 69002 Lyon")
+    )]
+    #[case(
+        Some(" "),
+        "Rue de la Loi",
+        "1000",
+        "Bruxelles",
+        "BE",
+        Ok("Rue de la Loi, 1000 Bruxelles, BE")
+    )]
+    #[case(Some("10"), "", "75001", "Paris", "FR", Err(AddressError::EmptyStreet))]
+    #[case(
+        Some("10"),
+        "rue de Rivoli",
+        "75001",
+        "",
+        "FR",
+        Err(AddressError::EmptyCity)
+    )]
+    #[case(
+        Some("10"),
+        "rue de Rivoli",
+        "75001",
+        "Paris",
+        "France",
+        Err(AddressError::InvalidCountry("FRANCE".to_string()))
+    )]
+    #[case(
+        None,
+        "Rue de la Loi",
+        "",
+        "Bruxelles",
+        "BE",
+        Err(AddressError::InvalidPostalCode { postal_code: String::new(), country: "BE".to_string() })
+    )]
+    fn test_new(
+        #[case] number: Option<&str>,
+        #[case] street: &str,
+        #[case] postal_code: &str,
+        #[case] city: &str,
+        #[case] country: &str,
+        #[case] expected: Result<&str, AddressError>,
+    ) {
+        assert_eq!(
+            expected.map(str::to_string),
+            Address::new(
+                number.map(str::to_string),
+                street.to_string(),
+                postal_code.to_string(),
+                city.to_string(),
+                country.to_string(),
+            )
+            .map(|address| address.to_string())
+        );
+    }
+
+    #[rstest]
+    #[case(None, None, true)]
+    #[case(Some("paris"), None, true)]
+    #[case(Some("Lyon"), None, false)]
+    #[case(None, Some("75"), true)]
+    #[case(None, Some("75001"), true)]
+    #[case(None, Some("750010"), false)]
+    #[case(Some("Paris"), Some("69"), false)]
+    fn test_matches(
+        #[case] city: Option<&str>,
+        #[case] postal_code: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let address = crate::test_support::address();
+
+        assert_eq!(expected, address.matches(city, postal_code));
+    }
+}
@@ -0,0 +1,850 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+};
+
+use chrono::NaiveDate;
+
+use crate::{
+    contract::{Contract, ContractId},
+    interval::{DateRange, IntervalSet},
+    office::{Office, OfficeId, OfficeWindow, RealOfficeId, WindowKind},
+    user::UserId,
+};
+
+/// `positions` of an office occupied by a guest during `period`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct Booking {
+    contract: ContractId,
+    office: OfficeId,
+    guest: UserId,
+    positions: usize,
+    period: DateRange,
+}
+
+/// Bookings of a set of offices. Guests share an office as long as it has enough positions left,
+/// but a real office cannot be booked while one of its splits is, and the other way around, while
+/// a guest cannot occupy two offices at once. Offices cannot be booked while their hosts take them
+/// off the market
+///
+/// Bookings are indexed by real office and by guest, and each office keeps the positions used over
+/// time up to date as bookings are added, so that queries only look at the office or guest asked
+/// about
+#[derive(Debug, Clone, Default)]
+pub struct OfficeCalendar {
+    offices: HashMap<OfficeId, CalendarOffice>,
+    /// Real offices and their splits
+    families: HashMap<RealOfficeId, Vec<OfficeId>>,
+    /// Bookings of each real office and of its splits
+    office_bookings: HashMap<RealOfficeId, Vec<Booking>>,
+    guest_bookings: HashMap<UserId, Vec<Booking>>,
+    /// Dates each guest occupies an office on
+    guest_occupancies: HashMap<UserId, IntervalSet>,
+}
+
+#[derive(Debug, Clone)]
+struct CalendarOffice {
+    real_office: RealOfficeId,
+    positions: usize,
+    /// Positions used over time, all of them while a related office is booked
+    used: Timeline,
+    listings: IntervalSet,
+    blackouts: IntervalSet,
+}
+
+/// A step function of dates: each key holds the value from its date until the next key, and 0
+/// comes before the first one
+#[derive(Debug, Clone, Default)]
+struct Timeline {
+    steps: BTreeMap<NaiveDate, usize>,
+}
+
+impl Timeline {
+    fn value_on(&self, date: NaiveDate) -> usize {
+        self.steps
+            .range(..=date)
+            .next_back()
+            .map(|(_, value)| *value)
+            .unwrap_or_default()
+    }
+
+    /// Dates strictly within `period` the value may change on
+    fn changes_within(&self, period: DateRange) -> impl Iterator<Item = NaiveDate> + '_ {
+        (!period.is_empty())
+            .then(|| {
+                self.steps.range((
+                    Bound::Excluded(*period.start()),
+                    Bound::Excluded(*period.end()),
+                ))
+            })
+            .into_iter()
+            .flatten()
+            .map(|(date, _)| *date)
+    }
+
+    fn add(&mut self, period: DateRange, amount: usize) {
+        if period.is_empty() {
+            return;
+        }
+
+        for date in [*period.start(), *period.end()] {
+            let value = self.value_on(date);
+            self.steps.entry(date).or_insert(value);
+        }
+
+        for (_, value) in self.steps.range_mut(*period.start()..*period.end()) {
+            *value += amount;
+        }
+    }
+}
+
+impl OfficeCalendar {
+    pub fn new<'a>(offices: impl IntoIterator<Item = &'a Office>) -> Self {
+        let mut calendar = Self::default();
+
+        for office in offices {
+            calendar.offices.insert(
+                *office.id(),
+                CalendarOffice {
+                    real_office: office.real_office(),
+                    positions: *office.available_positions(),
+                    used: Timeline::default(),
+                    listings: IntervalSet::new(),
+                    blackouts: IntervalSet::new(),
+                },
+            );
+            calendar
+                .families
+                .entry(office.real_office())
+                .or_default()
+                .push(*office.id());
+        }
+
+        calendar
+    }
+
+    /// Existing bookings are kept, even when the window closes the office during them
+    pub fn add_window(&mut self, window: &OfficeWindow) -> Result<(), CalendarError> {
+        let office = *window.office();
+        let real_office = self.office(&office)?.real_office;
+        let period = *window.period();
+
+        // a real office's listings apply to its splits, while its splits' blackouts apply to it
+        for member in self.families[&real_office].clone() {
+            let calendar_office = self.offices.get_mut(&member).unwrap();
+
+            match window.kind() {
+                WindowKind::Listing
+                    if member == office || matches!(office, OfficeId::RealOffice(_)) =>
+                {
+                    calendar_office.listings.insert(period)
+                }
+                WindowKind::Blackout
+                    if member == office
+                        || matches!(office, OfficeId::RealOffice(_))
+                        || matches!(member, OfficeId::RealOffice(_)) =>
+                {
+                    calendar_office.blackouts.insert(period)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Dates of `within` during which `office` cannot be booked: outside of its own or its real
+    /// office's listing periods, if any, and during its own, its real office's or, for a real
+    /// office, its splits' blackouts
+    pub fn closures(
+        &self,
+        office: &OfficeId,
+        within: DateRange,
+    ) -> Result<IntervalSet, CalendarError> {
+        let office = self.office(office)?;
+
+        let unlisted = if office.listings.is_empty() {
+            IntervalSet::new()
+        } else {
+            office.listings.complement(within)
+        };
+
+        Ok(unlisted
+            .union(&office.blackouts)
+            .intersection(&IntervalSet::from_iter([within])))
+    }
+
+    /// Books the positions of every office the contract occupies, or none of them if one is
+    /// unavailable
+    pub fn book(&mut self, contract: &Contract) -> Result<(), CalendarError> {
+        let bookings = Self::bookings(contract);
+
+        for booking in bookings.iter() {
+            let others = |existing: &&Booking| {
+                existing.contract != booking.contract && existing.period.overlaps(&booking.period)
+            };
+
+            if let Some(conflict) = self
+                .office_bookings(&booking.office)?
+                .filter(others)
+                .find(|existing| existing.office != booking.office)
+            {
+                return Err(CalendarError::OfficeAlreadyBooked {
+                    office: booking.office,
+                    contract: booking.contract,
+                    booked_by: conflict.contract,
+                });
+            }
+
+            if let Some(conflict) = self.guest_bookings(&booking.guest).find(others) {
+                return Err(CalendarError::GuestAlreadyBooked {
+                    guest: booking.guest,
+                    contract: booking.contract,
+                    booked_by: conflict.contract,
+                });
+            }
+
+            if !self.closures(&booking.office, booking.period)?.is_empty() {
+                return Err(CalendarError::OfficeClosed {
+                    office: booking.office,
+                    contract: booking.contract,
+                });
+            }
+
+            let remaining = self
+                .occupancy(&booking.office, booking.period)?
+                .into_iter()
+                .map(|(_, remaining)| remaining)
+                .min()
+                .unwrap_or_default();
+
+            if remaining < booking.positions {
+                return Err(CalendarError::NotEnoughPositions {
+                    office: booking.office,
+                    contract: booking.contract,
+                    positions: booking.positions,
+                    remaining,
+                });
+            }
+        }
+
+        for booking in bookings {
+            self.insert(booking);
+        }
+
+        Ok(())
+    }
+
+    /// Books the positions of every office the contract occupies even when they are unavailable,
+    /// e.g. for an existing contract conflicting with another one, so that its dates are taken
+    /// nonetheless; Overbooked offices have no position left
+    pub fn force_book(&mut self, contract: &Contract) -> Result<(), CalendarError> {
+        let bookings = Self::bookings(contract);
+
+        for booking in bookings.iter() {
+            self.office(&booking.office)?;
+        }
+        for booking in bookings {
+            self.insert(booking);
+        }
+
+        Ok(())
+    }
+
+    fn bookings(contract: &Contract) -> Vec<Booking> {
+        contract
+            .occupancies()
+            .into_iter()
+            .map(|(office, period)| Booking {
+                contract: *contract.id(),
+                office,
+                guest: *contract.guest(),
+                positions: *contract.positions(),
+                period,
+            })
+            .collect()
+    }
+
+    /// Bookings preventing `office` from being booked: its own, its real office's and its
+    /// real office's splits'; Splits of a same office don't prevent each other from being booked
+    pub fn office_bookings(
+        &self,
+        office: &OfficeId,
+    ) -> Result<impl Iterator<Item = &Booking>, CalendarError> {
+        let (office, real_office) = (*office, self.office(office)?.real_office);
+
+        Ok(self
+            .office_bookings
+            .get(&real_office)
+            .into_iter()
+            .flatten()
+            .filter(move |booking| {
+                matches!(office, OfficeId::RealOffice(_))
+                    || booking.office == office
+                    || booking.office == OfficeId::RealOffice(real_office)
+            }))
+    }
+
+    pub fn guest_bookings(&self, guest: &UserId) -> impl Iterator<Item = &Booking> {
+        self.guest_bookings.get(guest).into_iter().flatten()
+    }
+
+    /// Positions of `office` left over time within `within`, as consecutive periods. None are left
+    /// while its real office or one of its splits is booked, or while it is closed
+    pub fn occupancy(
+        &self,
+        office: &OfficeId,
+        within: DateRange,
+    ) -> Result<Vec<(DateRange, usize)>, CalendarError> {
+        let closures = self.closures(office, within)?;
+        let office = self.office(office)?;
+
+        let mut boundaries = office
+            .used
+            .changes_within(within)
+            .chain(
+                closures
+                    .ranges()
+                    .iter()
+                    .flat_map(|period| [*period.start(), *period.end()]),
+            )
+            .chain([*within.start(), *within.end()])
+            .collect::<Vec<_>>();
+        boundaries.sort();
+        boundaries.dedup();
+
+        let mut occupancy: Vec<(DateRange, usize)> = Vec::new();
+        for period in boundaries
+            .windows(2)
+            .map(|dates| DateRange::new(dates[0], dates[1]))
+        {
+            let remaining = if closures.overlaps(&period) {
+                0
+            } else {
+                office
+                    .positions
+                    .saturating_sub(office.used.value_on(*period.start()))
+            };
+
+            match occupancy.last_mut() {
+                Some((previous, previous_remaining)) if *previous_remaining == remaining => {
+                    *previous = DateRange::new(*previous.start(), *period.end());
+                }
+                _ => occupancy.push((period, remaining)),
+            }
+        }
+
+        Ok(occupancy)
+    }
+
+    pub fn is_available(
+        &self,
+        office: &OfficeId,
+        positions: usize,
+        period: DateRange,
+    ) -> Result<bool, CalendarError> {
+        Ok(self
+            .occupancy(office, period)?
+            .iter()
+            .all(|(_, remaining)| *remaining >= positions))
+    }
+
+    /// Dates of `within` during which `positions` of `office` can be booked
+    pub fn office_availabilities(
+        &self,
+        office: &OfficeId,
+        positions: usize,
+        within: DateRange,
+    ) -> Result<IntervalSet, CalendarError> {
+        Ok(self
+            .occupancy(office, within)?
+            .into_iter()
+            .filter(|(_, remaining)| *remaining >= positions)
+            .map(|(period, _)| period)
+            .collect())
+    }
+
+    /// Dates of `within` during which `guest` doesn't occupy any office
+    pub fn guest_availabilities(&self, guest: &UserId, within: DateRange) -> IntervalSet {
+        match self.guest_occupancies.get(guest) {
+            Some(occupancy) => occupancy.complement(within),
+            None => IntervalSet::from_iter([within]),
+        }
+    }
+
+    /// Indexes an accepted booking, using the positions it reserves in its office and every
+    /// position of the offices it prevents from being booked
+    fn insert(&mut self, booking: Booking) {
+        let real_office = self.offices[&booking.office].real_office;
+
+        for member in self.families[&real_office].iter() {
+            let office = self.offices.get_mut(member).unwrap();
+
+            if *member == booking.office {
+                office.used.add(booking.period, booking.positions);
+            } else if matches!(member, OfficeId::RealOffice(_))
+                || matches!(booking.office, OfficeId::RealOffice(_))
+            {
+                office.used.add(booking.period, office.positions);
+            }
+        }
+
+        self.guest_occupancies
+            .entry(booking.guest)
+            .or_default()
+            .insert(booking.period);
+        self.guest_bookings
+            .entry(booking.guest)
+            .or_default()
+            .push(booking);
+        self.office_bookings
+            .entry(real_office)
+            .or_default()
+            .push(booking);
+    }
+
+    fn office(&self, office: &OfficeId) -> Result<&CalendarOffice, CalendarError> {
+        self.offices
+            .get(office)
+            .ok_or(CalendarError::UnknownOffice(*office))
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CalendarError {
+    #[error("Office {0} is not part of the calendar")]
+    UnknownOffice(OfficeId),
+    #[error(
+        "Office {office} is already booked by contract {booked_by} during contract {contract}"
+    )]
+    OfficeAlreadyBooked {
+        office: OfficeId,
+        contract: ContractId,
+        booked_by: ContractId,
+    },
+    #[error("Office {office} only has {remaining} positions left during contract {contract}, which needs {positions}")]
+    NotEnoughPositions {
+        office: OfficeId,
+        contract: ContractId,
+        positions: usize,
+        remaining: usize,
+    },
+    #[error("Office {office} is off the market during contract {contract}")]
+    OfficeClosed {
+        office: OfficeId,
+        contract: ContractId,
+    },
+    #[error("Guest {guest} already occupies an office with contract {booked_by} during contract {contract}")]
+    GuestAlreadyBooked {
+        guest: UserId,
+        contract: ContractId,
+        booked_by: ContractId,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use rstest::rstest;
+
+    use crate::{
+        office::OfficeSplit,
+        test_support::{self, date, user},
+        user::{Role, User},
+    };
+
+    use super::*;
+
+    /// Returns a real office, its two splits and another real office, all owned by `host`
+    fn create_offices(host: &User) -> Vec<Office> {
+        let create_office = || test_support::office(host);
+
+        let office = create_office();
+        let splits = office
+            .split(vec![
+                OfficeSplit::new(50, 100).unwrap(),
+                OfficeSplit::new(50, 100).unwrap(),
+            ])
+            .unwrap();
+
+        std::iter::once(office)
+            .chain(splits)
+            .chain(std::iter::once(create_office()))
+            .collect()
+    }
+
+    /// Reserves every position of the office
+    fn create_contract(office: &Office, host: &User, guest: &User, start: NaiveDate) -> Contract {
+        create_contract_for_positions(office, host, guest, *office.available_positions(), start)
+    }
+
+    fn create_contract_for_positions(
+        office: &Office,
+        host: &User,
+        guest: &User,
+        positions: usize,
+        start: NaiveDate,
+    ) -> Contract {
+        Contract::for_office(
+            office,
+            host,
+            guest,
+            positions,
+            start,
+            Contract::minimum_end(start),
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    // a real office and its splits
+    #[case(0, 1, date(2025, 3, 1), true)]
+    #[case(2, 0, date(2025, 3, 1), true)]
+    // splits of a same office
+    #[case(1, 2, date(2025, 3, 1), false)]
+    // unrelated offices
+    #[case(0, 3, date(2025, 3, 1), false)]
+    // back to back
+    #[case(2, 0, date(2025, 5, 1), false)]
+    fn test_related_office_bookings(
+        #[case] booked: usize,
+        #[case] booking: usize,
+        #[case] start: NaiveDate,
+        #[case] is_conflict: bool,
+    ) {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        let contract = create_contract_for_positions(
+            &offices[booked],
+            &host,
+            &user(Role::Guest),
+            1,
+            date(2025, 1, 1),
+        );
+        calendar.book(&contract).unwrap();
+
+        let period = DateRange::new(start, Contract::minimum_end(start));
+        assert_eq!(
+            !is_conflict,
+            calendar
+                .is_available(offices[booking].id(), 1, period)
+                .unwrap()
+        );
+
+        let result = calendar.book(&create_contract_for_positions(
+            &offices[booking],
+            &host,
+            &user(Role::Guest),
+            1,
+            start,
+        ));
+        if is_conflict {
+            assert!(matches!(
+                result,
+                Err(CalendarError::OfficeAlreadyBooked { booked_by, .. }) if booked_by == *contract.id()
+            ));
+        } else {
+            assert!(result.is_ok());
+        }
+    }
+
+    #[rstest]
+    // the office has 100 positions
+    #[case(60, 40, true)]
+    #[case(60, 41, false)]
+    #[case(100, 1, false)]
+    fn test_guests_share_an_office(
+        #[case] booked: usize,
+        #[case] positions: usize,
+        #[case] is_available: bool,
+    ) {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        calendar
+            .book(&create_contract_for_positions(
+                &offices[0],
+                &host,
+                &user(Role::Guest),
+                booked,
+                date(2025, 1, 1),
+            ))
+            .unwrap();
+
+        let result = calendar.book(&create_contract_for_positions(
+            &offices[0],
+            &host,
+            &user(Role::Guest),
+            positions,
+            date(2025, 3, 1),
+        ));
+        if is_available {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(
+                result,
+                Err(CalendarError::NotEnoughPositions { remaining, .. }) if remaining == 100 - booked
+            ));
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut timeline = Timeline::default();
+        timeline.add(DateRange::new(date(2025, 1, 1), date(2025, 3, 1)), 2);
+        timeline.add(DateRange::new(date(2025, 2, 1), date(2025, 4, 1)), 3);
+        timeline.add(DateRange::new(date(2025, 5, 1), date(2025, 5, 1)), 7);
+
+        for (expected, on) in [
+            (0, date(2024, 12, 31)),
+            (2, date(2025, 1, 1)),
+            (5, date(2025, 2, 1)),
+            (5, date(2025, 2, 28)),
+            (3, date(2025, 3, 1)),
+            (0, date(2025, 4, 1)),
+            (0, date(2025, 5, 1)),
+        ] {
+            assert_eq!(expected, timeline.value_on(on), "on {on}");
+        }
+        assert_eq!(
+            vec![date(2025, 2, 1), date(2025, 3, 1)],
+            timeline
+                .changes_within(DateRange::new(date(2025, 1, 1), date(2025, 4, 1)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            0,
+            timeline
+                .changes_within(DateRange::new(date(2025, 3, 1), date(2025, 1, 1)))
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        for (positions, start) in [(30, date(2025, 1, 1)), (50, date(2025, 3, 1))] {
+            calendar
+                .book(&create_contract_for_positions(
+                    &offices[0],
+                    &host,
+                    &user(Role::Guest),
+                    positions,
+                    start,
+                ))
+                .unwrap();
+        }
+
+        let within = DateRange::new(date(2024, 12, 1), date(2025, 12, 1));
+        assert_eq!(
+            vec![
+                (DateRange::new(date(2024, 12, 1), date(2025, 1, 1)), 100),
+                (DateRange::new(date(2025, 1, 1), date(2025, 3, 1)), 70),
+                (DateRange::new(date(2025, 3, 1), date(2025, 5, 1)), 20),
+                (DateRange::new(date(2025, 5, 1), date(2025, 7, 1)), 50),
+                (DateRange::new(date(2025, 7, 1), date(2025, 12, 1)), 100),
+            ],
+            calendar.occupancy(offices[0].id(), within).unwrap()
+        );
+        assert_eq!(
+            &[
+                DateRange::new(date(2024, 12, 1), date(2025, 3, 1)),
+                DateRange::new(date(2025, 5, 1), date(2025, 12, 1)),
+            ],
+            calendar
+                .office_availabilities(offices[0].id(), 50, within)
+                .unwrap()
+                .ranges()
+        );
+    }
+
+    fn window(office: &Office, kind: WindowKind, start: NaiveDate, end: NaiveDate) -> OfficeWindow {
+        OfficeWindow::new(office, kind, DateRange::new(start, end), None).unwrap()
+    }
+
+    #[rstest]
+    // blackouts of an office, its real office or, for a real office, its splits
+    #[case(1, WindowKind::Blackout, 1, vec![(date(2025, 3, 1), date(2025, 5, 1))])]
+    #[case(0, WindowKind::Blackout, 2, vec![(date(2025, 3, 1), date(2025, 5, 1))])]
+    #[case(1, WindowKind::Blackout, 0, vec![(date(2025, 3, 1), date(2025, 5, 1))])]
+    #[case(1, WindowKind::Blackout, 2, vec![])]
+    #[case(1, WindowKind::Blackout, 3, vec![])]
+    // an office listed by itself or its real office is closed outside of its listing periods
+    #[case(1, WindowKind::Listing, 1, vec![
+        (date(2025, 1, 1), date(2025, 3, 1)),
+        (date(2025, 5, 1), date(2026, 1, 1)),
+    ])]
+    #[case(0, WindowKind::Listing, 2, vec![
+        (date(2025, 1, 1), date(2025, 3, 1)),
+        (date(2025, 5, 1), date(2026, 1, 1)),
+    ])]
+    #[case(1, WindowKind::Listing, 0, vec![])]
+    fn test_closures(
+        #[case] windowed: usize,
+        #[case] kind: WindowKind,
+        #[case] office: usize,
+        #[case] expected: Vec<(NaiveDate, NaiveDate)>,
+    ) {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        calendar
+            .add_window(&window(
+                &offices[windowed],
+                kind,
+                date(2025, 3, 1),
+                date(2025, 5, 1),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            expected
+                .into_iter()
+                .map(|(start, end)| DateRange::new(start, end))
+                .collect::<Vec<_>>(),
+            calendar
+                .closures(
+                    offices[office].id(),
+                    DateRange::new(date(2025, 1, 1), date(2026, 1, 1))
+                )
+                .unwrap()
+                .ranges()
+        );
+    }
+
+    #[test]
+    fn test_closed_offices_cannot_be_booked() {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        calendar
+            .add_window(&window(
+                &offices[0],
+                WindowKind::Blackout,
+                date(2025, 6, 1),
+                date(2025, 7, 1),
+            ))
+            .unwrap();
+
+        assert!(matches!(
+            calendar.book(&create_contract(
+                &offices[0],
+                &host,
+                &user(Role::Guest),
+                date(2025, 3, 1)
+            )),
+            Err(CalendarError::OfficeClosed { .. })
+        ));
+        assert_eq!(
+            &[
+                DateRange::new(date(2025, 1, 1), date(2025, 6, 1)),
+                DateRange::new(date(2025, 7, 1), date(2026, 1, 1)),
+            ],
+            calendar
+                .office_availabilities(
+                    offices[0].id(),
+                    1,
+                    DateRange::new(date(2025, 1, 1), date(2026, 1, 1))
+                )
+                .unwrap()
+                .ranges()
+        );
+    }
+
+    #[test]
+    fn test_guest_cannot_occupy_two_offices() {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        let guest = user(Role::Guest);
+        calendar
+            .book(&create_contract(
+                &offices[0],
+                &host,
+                &guest,
+                date(2025, 1, 1),
+            ))
+            .unwrap();
+
+        assert!(matches!(
+            calendar.book(&create_contract(
+                &offices[3],
+                &host,
+                &guest,
+                date(2025, 3, 1)
+            )),
+            Err(CalendarError::GuestAlreadyBooked { .. })
+        ));
+        assert_eq!(1, calendar.guest_bookings(guest.id()).count());
+        assert_eq!(
+            &[DateRange::new(date(2025, 5, 1), date(2026, 1, 1))],
+            calendar
+                .guest_availabilities(
+                    guest.id(),
+                    DateRange::new(date(2025, 1, 1), date(2026, 1, 1))
+                )
+                .ranges()
+        );
+    }
+
+    #[test]
+    fn test_force_book() {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices.iter());
+        let (guest, other_guest) = (user(Role::Guest), user(Role::Guest));
+        calendar
+            .book(&create_contract(
+                &offices[1],
+                &host,
+                &guest,
+                date(2025, 1, 1),
+            ))
+            .unwrap();
+
+        let conflicting = create_contract(&offices[0], &host, &other_guest, date(2025, 3, 1));
+        assert!(calendar.book(&conflicting).is_err());
+        calendar.force_book(&conflicting).unwrap();
+
+        let period = DateRange::new(date(2025, 1, 1), date(2026, 1, 1));
+        assert_eq!(
+            &[
+                DateRange::new(date(2025, 1, 1), date(2025, 3, 1)),
+                DateRange::new(date(2025, 7, 1), date(2026, 1, 1))
+            ],
+            calendar
+                .office_availabilities(offices[2].id(), 1, period)
+                .unwrap()
+                .ranges()
+        );
+        assert_eq!(
+            &[
+                DateRange::new(date(2025, 1, 1), date(2025, 3, 1)),
+                DateRange::new(date(2025, 7, 1), date(2026, 1, 1))
+            ],
+            calendar
+                .guest_availabilities(other_guest.id(), period)
+                .ranges()
+        );
+    }
+
+    #[test]
+    fn test_unknown_office() {
+        let host = user(Role::Host);
+        let offices = create_offices(&host);
+        let mut calendar = OfficeCalendar::new(offices[1..].iter());
+
+        assert_eq!(
+            Err(CalendarError::UnknownOffice(*offices[0].id())),
+            calendar.book(&create_contract(
+                &offices[0],
+                &host,
+                &user(Role::Guest),
+                date(2025, 1, 1)
+            ))
+        );
+    }
+}
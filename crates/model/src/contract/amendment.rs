@@ -0,0 +1,583 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    id::Identifier,
+    interval::DateRange,
+    model_id,
+    office::{Office, OfficeId},
+    Object,
+};
+
+use super::{Contract, ContractError, ContractState};
+
+model_id!(ContractAmendmentId, "amd");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmendmentChange {
+    /// The contract now ends later
+    Extension {
+        previous_end: NaiveDate,
+        end: NaiveDate,
+    },
+    /// New monthly rent, in cents
+    Rent { previous_rent: usize, rent: usize },
+    /// The guest moves to another office of the same real office
+    Office {
+        previous_office: OfficeId,
+        office: OfficeId,
+    },
+}
+
+impl AmendmentChange {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AmendmentChange::Extension { .. } => "extension",
+            AmendmentChange::Rent { .. } => "rent",
+            AmendmentChange::Office { .. } => "office",
+        }
+    }
+}
+
+/// A change to a contract's terms, applying from `effective_on`
+#[derive(Debug, Clone, PartialEq, derive_getters::Getters)]
+pub struct ContractAmendment {
+    id: ContractAmendmentId,
+    #[getter(skip)]
+    created_at: DateTime<Utc>,
+    effective_on: NaiveDate,
+    change: AmendmentChange,
+    reason: Option<String>,
+}
+
+impl Object for ContractAmendment {
+    fn uuid(&self) -> &uuid::Uuid {
+        &self.id.0
+    }
+
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+impl ContractAmendment {
+    pub fn new_unchecked(
+        id: ContractAmendmentId,
+        created_at: DateTime<Utc>,
+        effective_on: NaiveDate,
+        change: AmendmentChange,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            created_at,
+            effective_on,
+            change,
+            reason,
+        }
+    }
+}
+
+impl Contract {
+    /// Pushes the end date back to `end`, effective from the current end date
+    pub fn extend(&mut self, end: NaiveDate, reason: Option<String>) -> Result<(), ContractError> {
+        if end <= self.end {
+            return Err(ContractError::ExtensionNotLater {
+                end,
+                current_end: self.end,
+            });
+        }
+
+        let previous_end = self.end;
+        self.amend(
+            previous_end,
+            AmendmentChange::Extension { previous_end, end },
+            reason,
+        )?;
+        self.end = end;
+
+        Ok(())
+    }
+
+    pub fn change_rent(
+        &mut self,
+        rent: usize,
+        on: NaiveDate,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        if rent == 0 {
+            return Err(ContractError::NoRent);
+        }
+
+        let previous_rent = self.rent;
+        self.amend(
+            on,
+            AmendmentChange::Rent {
+                previous_rent,
+                rent,
+            },
+            reason,
+        )?;
+        self.rent = rent;
+
+        Ok(())
+    }
+
+    /// Moves the guest from `current`, the contract's office, to `office` from the given date.
+    /// Both must be part of the same real office, the new one having the reserved positions
+    pub fn change_office(
+        &mut self,
+        current: &Office,
+        office: &Office,
+        on: NaiveDate,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        use ContractError::*;
+
+        if *current.id() != self.office {
+            return Err(OfficeMismatch {
+                expected: self.office,
+                given: *current.id(),
+            });
+        }
+
+        if current.real_office() != office.real_office() {
+            return Err(OfficeOutsideRealOffice {
+                office: *office.id(),
+                real_office: current.real_office(),
+            });
+        }
+
+        super::validate_positions(self.positions, office)?;
+
+        let previous_office = self.office;
+        self.amend(
+            on,
+            AmendmentChange::Office {
+                previous_office,
+                office: *office.id(),
+            },
+            reason,
+        )?;
+        self.office = *office.id();
+
+        Ok(())
+    }
+
+    /// Merges `next`, a draft for the same guest, office and positions starting when this contract
+    /// ends. A draft is simply lengthened, other contracts are extended
+    pub fn merge_following(
+        &mut self,
+        next: &Contract,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        if next.state != ContractState::Draft
+            || next.guest != self.guest
+            || next.office != self.office
+            || next.positions != self.positions
+            || next.start != self.occupied_until()
+        {
+            return Err(ContractError::NotMergeable {
+                contract: self.id,
+                next: next.id,
+            });
+        }
+
+        if self.state == ContractState::Draft {
+            self.end = next.end;
+
+            Ok(())
+        } else {
+            self.extend(next.end, reason)
+        }
+    }
+
+    /// Offices occupied by the guest over time
+    pub fn occupancies(&self) -> Vec<(OfficeId, DateRange)> {
+        let until = self.occupied_until();
+        let mut occupancies = Vec::new();
+        let (mut office, mut from) = (self.initial_office(), self.start);
+
+        for amendment in self.amendments.iter() {
+            if let AmendmentChange::Office { office: next, .. } = amendment.change {
+                let period = DateRange::new(from, amendment.effective_on.min(until));
+                if !period.is_empty() {
+                    occupancies.push((office, period));
+                }
+
+                (office, from) = (next, amendment.effective_on);
+            }
+        }
+
+        let period = DateRange::new(from, until);
+        if !period.is_empty() {
+            occupancies.push((office, period));
+        }
+
+        occupancies
+    }
+
+    /// Monthly rent agreed upon for the given date, before indexation
+    pub(super) fn base_rent_on(&self, date: NaiveDate) -> usize {
+        self.amendments
+            .iter()
+            .rev()
+            .find_map(|amendment| match amendment.change {
+                AmendmentChange::Rent { rent, .. } if amendment.effective_on <= date => Some(rent),
+                _ => None,
+            })
+            .unwrap_or_else(|| self.initial_rent())
+    }
+
+    /// Dates the rent was changed at
+    pub(super) fn rent_change_dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.amendments
+            .iter()
+            .filter_map(|amendment| match amendment.change {
+                AmendmentChange::Rent { .. } => Some(amendment.effective_on),
+                _ => None,
+            })
+    }
+
+    fn initial_rent(&self) -> usize {
+        self.amendments
+            .iter()
+            .find_map(|amendment| match amendment.change {
+                AmendmentChange::Rent { previous_rent, .. } => Some(previous_rent),
+                _ => None,
+            })
+            .unwrap_or(self.rent)
+    }
+
+    fn initial_office(&self) -> OfficeId {
+        self.amendments
+            .iter()
+            .find_map(|amendment| match amendment.change {
+                AmendmentChange::Office {
+                    previous_office, ..
+                } => Some(previous_office),
+                _ => None,
+            })
+            .unwrap_or(self.office)
+    }
+
+    /// Records the change; Changes of a given kind must happen in order, while the contract is
+    /// running
+    fn amend(
+        &mut self,
+        effective_on: NaiveDate,
+        change: AmendmentChange,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        use ContractError::*;
+
+        if self.state.is_final() {
+            return Err(AmendedEndedContract(self.state));
+        }
+
+        // amendments take effect after the first day, while the office is still occupied
+        if !matches!(change, AmendmentChange::Extension { .. })
+            && (effective_on == self.start || !self.period().contains_date(effective_on))
+        {
+            return Err(AmendmentOutsideContract {
+                on: effective_on,
+                start: self.start,
+                end: self.occupied_until(),
+            });
+        }
+
+        let previous = self
+            .amendments
+            .iter()
+            .rev()
+            .find(|amendment| amendment.change.kind() == change.kind())
+            .map(|amendment| amendment.effective_on);
+
+        if let Some(previous) = previous.filter(|previous| effective_on < *previous) {
+            return Err(AmendmentBeforePrevious {
+                on: effective_on,
+                previous,
+            });
+        }
+
+        self.amendments.push(ContractAmendment {
+            id: ContractAmendmentId::new(),
+            created_at: crate::now(),
+            effective_on,
+            change,
+            reason,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::{
+        indexation::{IndexTable, RentPeriod},
+        office::OfficeSplit,
+        test_support::{self, date},
+        user::{Role, User},
+    };
+
+    use super::*;
+
+    /// A host, a guest, and the host's office along with its two splits
+    struct Fixture {
+        host: User,
+        guest: User,
+        office: Office,
+        first: Office,
+        second: Office,
+    }
+
+    fn fixture() -> Fixture {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+        let mut splits = office
+            .split(vec![
+                OfficeSplit::new(50, 100).unwrap(),
+                OfficeSplit::new(50, 100).unwrap(),
+            ])
+            .unwrap();
+        let second = splits.pop().unwrap();
+        let first = splits.pop().unwrap();
+
+        Fixture {
+            host,
+            guest,
+            office,
+            first,
+            second,
+        }
+    }
+
+    fn create_contract(fixture: &Fixture, office: &Office) -> Contract {
+        Contract::for_office(
+            office,
+            &fixture.host,
+            &fixture.guest,
+            10,
+            date(2025, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_extend() {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+
+        contract
+            .extend(date(2026, 6, 1), Some("Staying on".to_string()))
+            .unwrap();
+
+        assert_eq!(date(2026, 6, 1), *contract.end());
+        assert_eq!(
+            AmendmentChange::Extension {
+                previous_end: date(2026, 1, 1),
+                end: date(2026, 6, 1),
+            },
+            *contract.amendments()[0].change()
+        );
+        assert_eq!(date(2026, 1, 1), *contract.amendments()[0].effective_on());
+    }
+
+    #[rstest]
+    #[case(date(2026, 1, 1))]
+    #[case(date(2025, 12, 1))]
+    fn test_extension_must_be_later(#[case] end: NaiveDate) {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+
+        assert!(matches!(
+            contract.extend(end, None),
+            Err(ContractError::ExtensionNotLater { .. })
+        ));
+        assert!(contract.amendments().is_empty());
+    }
+
+    #[test]
+    fn test_change_rent() {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+
+        contract
+            .change_rent(400000, date(2025, 7, 1), None)
+            .unwrap();
+
+        assert_eq!(400000, *contract.rent());
+        assert_eq!(
+            vec![
+                RentPeriod::new(date(2025, 1, 1), date(2025, 7, 1), 500000),
+                RentPeriod::new(date(2025, 7, 1), date(2026, 1, 1), 400000),
+            ],
+            contract.rent_schedule(&IndexTable::new())
+        );
+    }
+
+    #[rstest]
+    #[case(date(2025, 1, 1))]
+    #[case(date(2026, 1, 1))]
+    #[case(date(2024, 6, 1))]
+    fn test_cannot_amend_outside_contract(#[case] on: NaiveDate) {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+
+        assert!(matches!(
+            contract.change_rent(4000000, on, None),
+            Err(ContractError::AmendmentOutsideContract { .. })
+        ));
+    }
+
+    #[test]
+    fn test_amendments_must_be_chronological() {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+        contract
+            .change_rent(4000000, date(2025, 7, 1), None)
+            .unwrap();
+
+        assert_eq!(
+            Err(ContractError::AmendmentBeforePrevious {
+                on: date(2025, 3, 1),
+                previous: date(2025, 7, 1)
+            }),
+            contract.change_rent(4500000, date(2025, 3, 1), None)
+        );
+    }
+
+    #[test]
+    fn test_cannot_change_to_a_smaller_office() {
+        let fixture = fixture();
+        let mut contract = Contract::for_office(
+            &fixture.office,
+            &fixture.host,
+            &fixture.guest,
+            60,
+            date(2025, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            contract.change_office(&fixture.office, &fixture.first, date(2025, 9, 1), None),
+            Err(ContractError::PositionsOutOfBounds { positions: 60, .. })
+        ));
+        assert_eq!(fixture.office.id(), contract.office());
+    }
+
+    #[test]
+    fn test_change_office() {
+        let fixture = fixture();
+        let (first, second) = (&fixture.first, &fixture.second);
+        let mut contract = create_contract(&fixture, first);
+
+        contract
+            .change_office(first, second, date(2025, 9, 1), None)
+            .unwrap();
+
+        assert_eq!(second.id(), contract.office());
+        assert_eq!(
+            vec![
+                (
+                    *first.id(),
+                    DateRange::new(date(2025, 1, 1), date(2025, 9, 1))
+                ),
+                (
+                    *second.id(),
+                    DateRange::new(date(2025, 9, 1), date(2026, 1, 1))
+                ),
+            ],
+            contract.occupancies()
+        );
+    }
+
+    #[test]
+    fn test_cannot_change_to_another_real_office() {
+        let fixture = fixture();
+        let other = self::fixture().office;
+        let mut contract = create_contract(&fixture, &fixture.first);
+
+        assert!(matches!(
+            contract.change_office(&fixture.first, &other, date(2025, 9, 1), None),
+            Err(ContractError::OfficeOutsideRealOffice { .. })
+        ));
+        assert_eq!(fixture.first.id(), contract.office());
+    }
+
+    #[test]
+    fn test_merge_following_draft() {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+        let next = Contract::for_office(
+            &fixture.office,
+            &fixture.host,
+            &fixture.guest,
+            10,
+            date(2026, 1, 1),
+            date(2026, 6, 1),
+        )
+        .unwrap();
+
+        contract.merge_following(&next, None).unwrap();
+
+        assert_eq!(date(2026, 6, 1), *contract.end());
+        assert!(contract.amendments().is_empty());
+    }
+
+    #[test]
+    fn test_merge_following_into_signed_contract() {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+        contract
+            .transition(ContractState::Signed, Utc::now(), None)
+            .unwrap();
+        let next = Contract::for_office(
+            &fixture.office,
+            &fixture.host,
+            &fixture.guest,
+            10,
+            date(2026, 1, 1),
+            date(2026, 6, 1),
+        )
+        .unwrap();
+
+        contract.merge_following(&next, None).unwrap();
+
+        assert_eq!(date(2026, 6, 1), *contract.end());
+        assert_eq!(1, contract.amendments().len());
+    }
+
+    #[rstest]
+    #[case(date(2026, 2, 1), true)]
+    #[case(date(2026, 1, 1), false)]
+    fn test_cannot_merge_unrelated_contracts(#[case] start: NaiveDate, #[case] same_guest: bool) {
+        let fixture = fixture();
+        let mut contract = create_contract(&fixture, &fixture.office);
+        let other_guest = User::new("Jules".to_string(), "Guest".to_string(), Role::Guest);
+        let guest = if same_guest {
+            &fixture.guest
+        } else {
+            &other_guest
+        };
+        let next = Contract::for_office(
+            &fixture.office,
+            &fixture.host,
+            guest,
+            10,
+            start,
+            date(2026, 6, 1),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            contract.merge_following(&next, None),
+            Err(ContractError::NotMergeable { .. })
+        ));
+    }
+}
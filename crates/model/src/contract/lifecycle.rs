@@ -0,0 +1,292 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Utc};
+
+use super::{Contract, ContractError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContractState {
+    /// Proposed, e.g. by a simulation, but not agreed upon yet
+    Draft,
+    Signed,
+    /// The guest occupies the office
+    Active,
+    /// Ended before its end date
+    Terminated,
+    /// Reached its end date
+    Expired,
+    /// Reached its end date and was followed by a new contract
+    Renewed,
+}
+
+impl ContractState {
+    pub fn can_transition_to(&self, to: ContractState) -> bool {
+        use ContractState::*;
+
+        matches!(
+            (self, to),
+            (Draft, Signed) | (Signed, Active) | (Active, Terminated | Expired | Renewed)
+        )
+    }
+
+    /// Final states cannot transition anymore
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Terminated | Self::Expired | Self::Renewed)
+    }
+}
+
+impl Display for ContractState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ContractState::Draft => "draft",
+                ContractState::Signed => "signed",
+                ContractState::Active => "active",
+                ContractState::Terminated => "terminated",
+                ContractState::Expired => "expired",
+                ContractState::Renewed => "renewed",
+            }
+        )
+    }
+}
+
+impl FromStr for ContractState {
+    type Err = ContractError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "draft" => Self::Draft,
+            "signed" => Self::Signed,
+            "active" => Self::Active,
+            "terminated" => Self::Terminated,
+            "expired" => Self::Expired,
+            "renewed" => Self::Renewed,
+            _ => return Err(ContractError::UnknownState(s.to_string())),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, derive_getters::Getters)]
+pub struct ContractTransition {
+    from: ContractState,
+    to: ContractState,
+    at: DateTime<Utc>,
+    reason: Option<String>,
+}
+
+impl ContractTransition {
+    pub fn new_unchecked(
+        from: ContractState,
+        to: ContractState,
+        at: DateTime<Utc>,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            at,
+            reason,
+        }
+    }
+}
+
+impl Contract {
+    /// Moves the contract to the given state, recording when and why in its history. Use
+    /// [`Contract::terminate`] to terminate it
+    pub fn transition(
+        &mut self,
+        to: ContractState,
+        at: DateTime<Utc>,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        use ContractError::*;
+
+        let from = self.state;
+
+        if !from.can_transition_to(to) {
+            return Err(InvalidTransition { from, to });
+        }
+
+        match to {
+            ContractState::Active if at.date_naive() < self.start => {
+                return Err(ActivatedBeforeStart {
+                    at: at.date_naive(),
+                    start: self.start,
+                });
+            }
+            ContractState::Expired | ContractState::Renewed if at.date_naive() < self.end => {
+                return Err(EndedBeforeEndDate {
+                    at: at.date_naive(),
+                    end: self.end,
+                });
+            }
+            ContractState::Terminated => return Err(TerminationWithoutNotice),
+            _ => {}
+        }
+
+        self.record_transition(to, at, reason)
+    }
+
+    pub(super) fn record_transition(
+        &mut self,
+        to: ContractState,
+        at: DateTime<Utc>,
+        reason: Option<String>,
+    ) -> Result<(), ContractError> {
+        use ContractError::*;
+
+        let from = self.state;
+
+        if !from.can_transition_to(to) {
+            return Err(InvalidTransition { from, to });
+        }
+
+        if let Some(previous) = self.transitions.last().map(|transition| transition.at) {
+            if at < previous {
+                return Err(TransitionBeforePrevious { at, previous });
+            }
+        }
+
+        self.state = to;
+        self.transitions.push(ContractTransition {
+            from,
+            to,
+            at,
+            reason,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, TimeZone};
+    use rstest::rstest;
+
+    use crate::{
+        test_support,
+        user::{Role, User},
+    };
+
+    use super::{ContractState::*, *};
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    fn create_contract() -> Contract {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+
+        Contract::new(
+            &host,
+            &guest,
+            &office,
+            10,
+            30000,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_contracts_start_as_draft() {
+        let contract = create_contract();
+
+        assert_eq!(Draft, *contract.state());
+        assert!(contract.transitions().is_empty());
+    }
+
+    #[rstest]
+    #[case(vec![Signed, Active, Expired])]
+    #[case(vec![Signed, Active, Renewed])]
+    fn test_valid_lifecycle(#[case] states: Vec<ContractState>) {
+        let mut contract = create_contract();
+
+        for state in states.iter() {
+            contract
+                .transition(*state, at(2025, 6, 1), Some("test".to_string()))
+                .unwrap();
+        }
+
+        assert_eq!(*states.last().unwrap(), *contract.state());
+        assert_eq!(states.len(), contract.transitions().len());
+        assert_eq!(Draft, *contract.transitions()[0].from());
+    }
+
+    #[rstest]
+    #[case(vec![], Active)]
+    #[case(vec![], Terminated)]
+    #[case(vec![Signed], Signed)]
+    #[case(vec![Signed], Expired)]
+    #[case(vec![Signed, Active, Expired], Active)]
+    #[case(vec![Signed, Active, Renewed], Expired)]
+    fn test_invalid_transition(#[case] states: Vec<ContractState>, #[case] to: ContractState) {
+        let mut contract = create_contract();
+
+        for state in states {
+            contract.transition(state, at(2025, 6, 1), None).unwrap();
+        }
+
+        let from = *contract.state();
+
+        assert_eq!(
+            Err(ContractError::InvalidTransition { from, to }),
+            contract.transition(to, at(2025, 6, 1), None)
+        );
+        assert_eq!(from, *contract.state());
+    }
+
+    #[test]
+    fn test_termination_requires_notice() {
+        let mut contract = create_contract();
+        contract.transition(Signed, at(2024, 12, 1), None).unwrap();
+        contract.transition(Active, at(2025, 1, 1), None).unwrap();
+
+        assert_eq!(
+            Err(ContractError::TerminationWithoutNotice),
+            contract.transition(Terminated, at(2025, 5, 1), None)
+        );
+    }
+
+    #[test]
+    fn test_transitions_must_be_chronological() {
+        let mut contract = create_contract();
+        contract.transition(Signed, at(2025, 1, 2), None).unwrap();
+
+        assert!(matches!(
+            contract.transition(Active, at(2025, 1, 1), None),
+            Err(ContractError::TransitionBeforePrevious { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cannot_activate_before_start() {
+        let mut contract = create_contract();
+        contract.transition(Signed, at(2024, 12, 1), None).unwrap();
+
+        assert!(matches!(
+            contract.transition(Active, at(2024, 12, 31), None),
+            Err(ContractError::ActivatedBeforeStart { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case(Expired)]
+    #[case(Renewed)]
+    fn test_cannot_end_before_end_date(#[case] to: ContractState) {
+        let mut contract = create_contract();
+        contract.transition(Signed, at(2024, 12, 1), None).unwrap();
+        contract.transition(Active, at(2025, 1, 1), None).unwrap();
+
+        assert!(matches!(
+            contract.transition(to, at(2025, 5, 31), None),
+            Err(ContractError::EndedBeforeEndDate { .. })
+        ));
+    }
+}
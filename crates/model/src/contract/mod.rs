@@ -0,0 +1,678 @@
+pub use amendment::{AmendmentChange, ContractAmendment, ContractAmendmentId};
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+pub use lifecycle::{ContractState, ContractTransition};
+pub use termination::{Termination, TerminationPolicy};
+
+use crate::{
+    id::Identifier,
+    indexation::{IndexTable, IndexationClause, RentPeriod},
+    model_id,
+    office::{Office, OfficeId, RealOfficeId},
+    user::{User, UserId},
+    Object,
+};
+
+mod amendment;
+mod lifecycle;
+mod termination;
+
+model_id!(ContractId, "agr");
+
+#[derive(Debug, Clone, PartialEq, derive_getters::Getters)]
+pub struct Contract {
+    id: ContractId,
+    #[getter(skip)]
+    created_at: DateTime<Utc>,
+    host: UserId,
+    guest: UserId,
+    office: OfficeId,
+    /// Positions of the office reserved for the guest
+    positions: usize,
+    rent: usize,
+    start: NaiveDate,
+    end: NaiveDate,
+    indexation: Option<IndexationClause>,
+    state: ContractState,
+    transitions: Vec<ContractTransition>,
+    termination: Option<Termination>,
+    amendments: Vec<ContractAmendment>,
+}
+
+impl Object for Contract {
+    fn uuid(&self) -> &uuid::Uuid {
+        &self.id.0
+    }
+
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+impl Contract {
+    /// The host must own the office and be allowed to host, the guest must be allowed to rent and
+    /// be someone else. The office must have the reserved positions
+    pub fn new(
+        host: &User,
+        guest: &User,
+        office: &Office,
+        positions: usize,
+        rent: usize,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Self, ContractError> {
+        use ContractError::*;
+
+        if office.owner() != host.id() {
+            return Err(HostDoesNotOwnOffice {
+                host: *host.id(),
+                office: *office.id(),
+            });
+        }
+
+        if !host.role().can_host() {
+            return Err(NotAHost(*host.id()));
+        }
+
+        if !guest.role().can_rent() {
+            return Err(NotAGuest(*guest.id()));
+        }
+
+        if host.id() == guest.id() {
+            return Err(HostIsGuest(*host.id()));
+        }
+
+        validate_positions(positions, office)?;
+
+        let minimum_end = Self::minimum_end(start);
+
+        if end < minimum_end {
+            return Err(TooShort { end, minimum_end });
+        }
+
+        Ok(Self {
+            id: ContractId::new(),
+            created_at: crate::now(),
+            host: *host.id(),
+            guest: *guest.id(),
+            office: *office.id(),
+            positions,
+            rent,
+            start,
+            end,
+            indexation: None,
+            state: ContractState::Draft,
+            transitions: Vec::new(),
+            termination: None,
+            amendments: Vec::new(),
+        })
+    }
+
+    /// The earliest end date for a contract starting on `start`, i.e.
+    /// [`CONTRACT_DURATION_MINIMUM_MONTHS`] calendar months later; `2024-01-31` gives `2024-05-31`
+    /// and `2024-10-31` gives `2025-02-28`
+    pub fn minimum_end(start: NaiveDate) -> NaiveDate {
+        start
+            .checked_add_months(Months::new(CONTRACT_DURATION_MINIMUM_MONTHS))
+            .unwrap()
+    }
+
+    /// A contract reserving `positions` of the office at its list price
+    pub fn for_office(
+        office: &Office,
+        host: &User,
+        guest: &User,
+        positions: usize,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Self, ContractError> {
+        Self::negotiated_for_office(
+            office,
+            host,
+            guest,
+            positions,
+            start,
+            end,
+            RentNegotiation::ListPrice,
+        )
+    }
+
+    /// Same as [`Contract::for_office`], with a rent negotiated against the office's list price
+    pub fn negotiated_for_office(
+        office: &Office,
+        host: &User,
+        guest: &User,
+        positions: usize,
+        start: NaiveDate,
+        end: NaiveDate,
+        negotiation: RentNegotiation,
+    ) -> Result<Self, ContractError> {
+        Self::new(
+            host,
+            guest,
+            office,
+            positions,
+            negotiation.apply(positions * office.position_price())?,
+            start,
+            end,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_unchecked(
+        id: ContractId,
+        created_at: DateTime<Utc>,
+        host: UserId,
+        guest: UserId,
+        office: OfficeId,
+        positions: usize,
+        rent: usize,
+        start: NaiveDate,
+        end: NaiveDate,
+        indexation: Option<IndexationClause>,
+        state: ContractState,
+        transitions: Vec<ContractTransition>,
+        termination: Option<Termination>,
+        amendments: Vec<ContractAmendment>,
+    ) -> Self {
+        Self {
+            id,
+            created_at,
+            host,
+            guest,
+            office,
+            positions,
+            rent,
+            start,
+            end,
+            indexation,
+            state,
+            transitions,
+            termination,
+            amendments,
+        }
+    }
+
+    pub fn with_indexation(mut self, indexation: IndexationClause) -> Self {
+        self.indexation = Some(indexation);
+
+        self
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Monthly rent for each year of the contract, also split on rent amendments; A single period
+    /// when the rent isn't indexed nor amended
+    pub fn rent_schedule(&self, index_table: &IndexTable) -> Vec<RentPeriod> {
+        let revision_dates = self
+            .indexation
+            .map(|indexation| indexation.revision_dates(self.end))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|date| *date > self.start)
+            .collect::<Vec<_>>();
+
+        let mut starts = std::iter::once(self.start)
+            .chain(revision_dates.iter().copied())
+            .chain(
+                self.rent_change_dates()
+                    .filter(|date| *date > self.start && *date < self.end),
+            )
+            .collect::<Vec<_>>();
+        starts.sort();
+        starts.dedup();
+
+        starts
+            .iter()
+            .copied()
+            .zip(
+                starts
+                    .iter()
+                    .skip(1)
+                    .copied()
+                    .chain(std::iter::once(self.end)),
+            )
+            .map(|(start, end)| {
+                let rent = self.base_rent_on(start);
+
+                RentPeriod::new(
+                    start,
+                    end,
+                    match self.indexation {
+                        Some(indexation) => {
+                            let revision = revision_dates.iter().filter(|date| **date <= start);
+
+                            indexation.revised_rent(rent, revision.count() as u32, index_table)
+                        }
+                        None => rent,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn rent_on(&self, date: NaiveDate, index_table: &IndexTable) -> usize {
+        self.rent_schedule(index_table)
+            .into_iter()
+            .rev()
+            .find(|period| *period.start() <= date)
+            .map(|period| *period.rent())
+            .unwrap_or(self.rent)
+    }
+
+    /// Rent owed over the whole contract
+    pub fn total_rent(&self, index_table: &IndexTable) -> usize {
+        self.rent_schedule(index_table)
+            .iter()
+            .map(RentPeriod::total)
+            .sum()
+    }
+}
+
+/// A contract reserves at least one position, and no more than the office has
+fn validate_positions(positions: usize, office: &Office) -> Result<(), ContractError> {
+    if positions == 0 || positions > *office.available_positions() {
+        return Err(ContractError::PositionsOutOfBounds {
+            positions,
+            office: *office.id(),
+            available: *office.available_positions(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Contracts run from their `start` date (inclusive) to their `end` date (exclusive), for at least
+/// this many calendar months: `2024-01-01` to `2024-05-01` is the shortest contract starting on
+/// `2024-01-01`
+pub const CONTRACT_DURATION_MINIMUM_MONTHS: u32 = 4;
+
+/// Discounts are expressed in basis points, `1000` being 10%
+pub const DISCOUNT_MAXIMUM: usize = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RentNegotiation {
+    /// The office's positions price
+    ListPrice,
+    /// A discount on the list price, in basis points
+    Discount(usize),
+    /// A monthly rent agreed upon regardless of the list price, in cents
+    Override(usize),
+}
+
+impl RentNegotiation {
+    pub fn apply(&self, list_rent: usize) -> Result<usize, ContractError> {
+        use ContractError::*;
+
+        match *self {
+            Self::ListPrice => Ok(list_rent),
+            Self::Discount(discount) if discount >= DISCOUNT_MAXIMUM => {
+                Err(DiscountOutOfBounds(discount))
+            }
+            Self::Discount(discount) => {
+                Ok(list_rent - (list_rent * discount + DISCOUNT_MAXIMUM / 2) / DISCOUNT_MAXIMUM)
+            }
+            Self::Override(0) => Err(NoRent),
+            Self::Override(rent) => Ok(rent),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ContractError {
+    #[error("User {host} does not own office {office}")]
+    HostDoesNotOwnOffice { host: UserId, office: OfficeId },
+    #[error("User {0} is not a host")]
+    NotAHost(UserId),
+    #[error("User {0} is not a guest")]
+    NotAGuest(UserId),
+    #[error("User {0} cannot rent their own office")]
+    HostIsGuest(UserId),
+    #[error("Cannot reserve {positions} positions in office {office}, which has {available}")]
+    PositionsOutOfBounds {
+        positions: usize,
+        office: OfficeId,
+        available: usize,
+    },
+    #[error("A contract must last at least {CONTRACT_DURATION_MINIMUM_MONTHS} months, so end on {minimum_end} or later, but tried to create one ending on {end}")]
+    TooShort {
+        end: NaiveDate,
+        minimum_end: NaiveDate,
+    },
+    #[error("Given discount ({0}) is out of bounds.")]
+    DiscountOutOfBounds(usize),
+    #[error("A contract's rent cannot be free")]
+    NoRent,
+    #[error(r#"Unknown contract state "{0}""#)]
+    UnknownState(String),
+    #[error("A {from} contract cannot become {to}")]
+    InvalidTransition {
+        from: ContractState,
+        to: ContractState,
+    },
+    #[error("A transition cannot happen ({at}) before the previous one ({previous})")]
+    TransitionBeforePrevious {
+        at: DateTime<Utc>,
+        previous: DateTime<Utc>,
+    },
+    #[error("A contract cannot be activated ({at}) before it starts ({start})")]
+    ActivatedBeforeStart { at: NaiveDate, start: NaiveDate },
+    #[error("A contract cannot expire or be renewed ({at}) before its end date ({end})")]
+    EndedBeforeEndDate { at: NaiveDate, end: NaiveDate },
+    #[error("Contracts are terminated with a notice period, see `Contract::terminate`")]
+    TerminationWithoutNotice,
+    #[error("A contract cannot be terminated ({on}) before its minimum duration ({minimum_end})")]
+    TerminatedBeforeMinimumDuration {
+        on: NaiveDate,
+        minimum_end: NaiveDate,
+    },
+    #[error("A contract cannot be terminated ({on}) after its end date ({end}), let it expire")]
+    TerminatedAfterEndDate { on: NaiveDate, end: NaiveDate },
+    #[error(
+        "A notice of {notice_months} months given on {at} ends beyond the last supported date"
+    )]
+    NoticeBeyondSupportedDates { at: NaiveDate, notice_months: u32 },
+    #[error("A {0} contract cannot be amended")]
+    AmendedEndedContract(ContractState),
+    #[error("A contract can only be extended past its end date ({current_end}), not to {end}")]
+    ExtensionNotLater {
+        end: NaiveDate,
+        current_end: NaiveDate,
+    },
+    #[error("An amendment must take effect ({on}) while the contract runs ({start} to {end})")]
+    AmendmentOutsideContract {
+        on: NaiveDate,
+        start: NaiveDate,
+        end: NaiveDate,
+    },
+    #[error("An amendment cannot take effect ({on}) before the previous one ({previous})")]
+    AmendmentBeforePrevious { on: NaiveDate, previous: NaiveDate },
+    #[error("The contract is for office {expected}, not {given}")]
+    OfficeMismatch { expected: OfficeId, given: OfficeId },
+    #[error("Office {office} is not part of office {real_office}")]
+    OfficeOutsideRealOffice {
+        office: OfficeId,
+        real_office: RealOfficeId,
+    },
+    #[error("Contract {next} cannot be merged into contract {contract}")]
+    NotMergeable {
+        contract: ContractId,
+        next: ContractId,
+    },
+    #[error(r#"Unknown amendment kind "{0}""#)]
+    UnknownAmendmentKind(String),
+}
+
+#[cfg(test)]
+#[allow(clippy::zero_prefixed_literal, clippy::assertions_on_constants)]
+mod duration_test {
+    use chrono::Datelike;
+    use rstest::rstest;
+
+    use crate::{test_support, user::Role};
+
+    use super::*;
+
+    fn create_contract(start: NaiveDate, end: NaiveDate) -> Result<Contract, ContractError> {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+
+        Contract::new(&host, &guest, &office, 10, 30000, start, end)
+    }
+
+    #[rstest]
+    #[case((01, 01), (05, 01))]
+    #[case((01, 01), (12, 31))]
+    #[case((2, 1), (6, 1))]
+    #[case((1, 31), (5, 31))]
+    #[case((8, 31), (12, 31))]
+    pub fn test_valid_duration(#[case] start: (u32, u32), #[case] end: (u32, u32)) {
+        let contract = create_contract(
+            NaiveDate::from_ymd_opt(2024, start.0, start.1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, end.0, end.1).unwrap(),
+        );
+
+        if let Ok(contract) = contract {
+            assert_eq!(start.0, contract.start.month());
+            assert_eq!(start.1 - 1, contract.start.day0());
+            assert_eq!(end.0, contract.end.month());
+            assert_eq!(end.1 - 1, contract.end.day0());
+        } else {
+            assert!(false);
+        }
+    }
+
+    #[rstest]
+    #[case((01, 01), (04, 29))]
+    #[case((1, 1), (4, 30))]
+    #[case((2, 2), (6, 1))]
+    #[case((1, 31), (5, 30))]
+    #[case((01, 01), (01, 01))]
+    #[case((01, 01), (01, 02))]
+    pub fn test_invalid_duration(#[case] start: (u32, u32), #[case] end: (u32, u32)) {
+        let contract = create_contract(
+            NaiveDate::from_ymd_opt(2024, start.0, start.1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, end.0, end.1).unwrap(),
+        );
+
+        if let Err(err) = contract {
+            assert!(matches!(err, ContractError::TooShort { .. }));
+        } else {
+            assert!(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod indexation_test {
+    use crate::{
+        indexation::{IndexationClause, Quarter, RentIndex},
+        test_support::{self, date},
+        user::Role,
+    };
+
+    use super::*;
+
+    fn create_contract(indexation: Option<IndexationClause>) -> Contract {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+
+        let contract = Contract::new(
+            &host,
+            &guest,
+            &office,
+            10,
+            100000,
+            date(2024, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap();
+
+        match indexation {
+            Some(indexation) => contract.with_indexation(indexation),
+            None => contract,
+        }
+    }
+
+    fn index_table() -> IndexTable {
+        let mut table = IndexTable::new();
+        table.insert(RentIndex::Ilat, Quarter::new(2024, 1).unwrap(), 10000);
+        table.insert(RentIndex::Ilat, Quarter::new(2025, 1).unwrap(), 10300);
+
+        table
+    }
+
+    #[test]
+    fn test_schedule_without_indexation() {
+        let schedule = create_contract(None).rent_schedule(&index_table());
+
+        assert_eq!(
+            vec![RentPeriod::new(date(2024, 1, 1), date(2026, 1, 1), 100000)],
+            schedule
+        );
+    }
+
+    #[test]
+    fn test_schedule_with_indexation() {
+        let contract = create_contract(Some(IndexationClause::yearly_from(
+            RentIndex::Ilat,
+            date(2024, 1, 1),
+        )));
+        let schedule = contract.rent_schedule(&index_table());
+
+        assert_eq!(
+            vec![
+                RentPeriod::new(date(2024, 1, 1), date(2025, 1, 1), 100000),
+                RentPeriod::new(date(2025, 1, 1), date(2026, 1, 1), 103000),
+            ],
+            schedule
+        );
+        assert_eq!(100000, contract.rent_on(date(2024, 12, 31), &index_table()));
+        assert_eq!(103000, contract.rent_on(date(2025, 1, 1), &index_table()));
+    }
+}
+
+#[cfg(test)]
+mod negotiation_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(RentNegotiation::ListPrice, Ok(100000))]
+    #[case(RentNegotiation::Discount(1000), Ok(90000))]
+    #[case(RentNegotiation::Discount(333), Ok(96670))]
+    #[case(RentNegotiation::Override(75000), Ok(75000))]
+    #[case(
+        RentNegotiation::Discount(10000),
+        Err(ContractError::DiscountOutOfBounds(10000))
+    )]
+    #[case(RentNegotiation::Override(0), Err(ContractError::NoRent))]
+    fn test_negotiated_rent(
+        #[case] negotiation: RentNegotiation,
+        #[case] expected: Result<usize, ContractError>,
+    ) {
+        assert_eq!(expected, negotiation.apply(100000));
+    }
+}
+
+#[cfg(test)]
+mod role_test {
+    use rstest::rstest;
+
+    use crate::{test_support, user::Role};
+
+    use super::*;
+
+    fn create_contract(
+        host: &User,
+        guest: &User,
+        office: &Office,
+    ) -> Result<Contract, ContractError> {
+        Contract::for_office(
+            office,
+            host,
+            guest,
+            10,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+        )
+    }
+
+    #[rstest]
+    #[case(Role::Host, Role::Guest)]
+    #[case(Role::HostAndGuest, Role::Guest)]
+    #[case(Role::Host, Role::HostAndGuest)]
+    fn test_valid_roles(#[case] host_role: Role, #[case] guest_role: Role) {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), host_role);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), guest_role);
+
+        assert!(create_contract(&host, &guest, &test_support::office(&host)).is_ok());
+    }
+
+    #[test]
+    fn test_host_must_own_office() {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let owner = User::new("Julie".to_string(), "Owner".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&owner);
+
+        assert_eq!(
+            Err(ContractError::HostDoesNotOwnOffice {
+                host: *host.id(),
+                office: *office.id()
+            }),
+            create_contract(&host, &guest, &office)
+        );
+    }
+
+    #[rstest]
+    #[case(Role::Guest, Role::Guest, ContractError::NotAHost)]
+    #[case(Role::Host, Role::Host, ContractError::NotAGuest)]
+    fn test_invalid_roles(
+        #[case] host_role: Role,
+        #[case] guest_role: Role,
+        #[case] error: fn(UserId) -> ContractError,
+    ) {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), host_role);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), guest_role);
+        let office = test_support::office(&host);
+
+        let expected = if host_role.can_host() {
+            error(*guest.id())
+        } else {
+            error(*host.id())
+        };
+        assert_eq!(Err(expected), create_contract(&host, &guest, &office));
+    }
+
+    #[test]
+    fn test_host_cannot_rent_own_office() {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::HostAndGuest);
+        let office = test_support::office(&host);
+
+        assert_eq!(
+            Err(ContractError::HostIsGuest(*host.id())),
+            create_contract(&host, &host, &office)
+        );
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(101)]
+    fn test_positions_out_of_bounds(#[case] positions: usize) {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+
+        assert_eq!(
+            Err(ContractError::PositionsOutOfBounds {
+                positions,
+                office: *office.id(),
+                available: 100
+            }),
+            Contract::for_office(
+                &office,
+                &host,
+                &guest,
+                positions,
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            )
+        );
+    }
+
+    #[test]
+    fn test_rent_is_priced_by_position() {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+
+        let contract = create_contract(&host, &guest, &test_support::office(&host)).unwrap();
+
+        assert_eq!(10, *contract.positions());
+        assert_eq!(10 * 50000, *contract.rent());
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Months, NaiveDate, Utc};
+
+use crate::{
+    indexation::{IndexTable, RentPeriod},
+    interval::DateRange,
+};
+
+use super::{Contract, ContractError, ContractState};
+
+/// Notice a guest must give before leaving, and the rent they still owe for leaving early
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct TerminationPolicy {
+    notice_months: u32,
+    penalty_months: u32,
+}
+
+impl TerminationPolicy {
+    pub fn new(notice_months: u32, penalty_months: u32) -> Self {
+        Self {
+            notice_months,
+            penalty_months,
+        }
+    }
+}
+
+impl Default for TerminationPolicy {
+    fn default() -> Self {
+        Self::new(3, 3)
+    }
+}
+
+/// The office is freed `on` that date (exclusive), `penalty` being owed in cents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct Termination {
+    on: NaiveDate,
+    penalty: usize,
+}
+
+impl Termination {
+    pub fn new_unchecked(on: NaiveDate, penalty: usize) -> Self {
+        Self { on, penalty }
+    }
+}
+
+impl Contract {
+    /// Terminates an active contract with a notice given `at` that date. The office is freed once
+    /// the notice period is over, the guest owing the `penalty_months` of rent following it at
+    /// most, revised rents included
+    pub fn terminate(
+        &mut self,
+        at: DateTime<Utc>,
+        reason: Option<String>,
+        policy: &TerminationPolicy,
+        index_table: &IndexTable,
+    ) -> Result<Termination, ContractError> {
+        use ContractError::*;
+
+        let on = at
+            .date_naive()
+            .checked_add_months(Months::new(policy.notice_months))
+            .ok_or(NoticeBeyondSupportedDates {
+                at: at.date_naive(),
+                notice_months: policy.notice_months,
+            })?;
+        let minimum_end = Self::minimum_end(self.start);
+
+        if !self.state.can_transition_to(ContractState::Terminated) {
+            return Err(InvalidTransition {
+                from: self.state,
+                to: ContractState::Terminated,
+            });
+        }
+
+        if on < minimum_end {
+            return Err(TerminatedBeforeMinimumDuration { on, minimum_end });
+        }
+
+        if on >= self.end {
+            return Err(TerminatedAfterEndDate { on, end: self.end });
+        }
+
+        // the penalty never runs past the end date, however long it is
+        let penalty_end = on
+            .checked_add_months(Months::new(policy.penalty_months))
+            .map_or(self.end, |end| end.min(self.end));
+        let penalty = self
+            .rent_schedule(index_table)
+            .iter()
+            .filter(|period| *period.end() > on && *period.start() < penalty_end)
+            .map(|period| {
+                RentPeriod::new(
+                    on.max(*period.start()),
+                    penalty_end.min(*period.end()),
+                    *period.rent(),
+                )
+            })
+            .map(|period| period.total())
+            .sum::<usize>();
+
+        self.record_transition(ContractState::Terminated, at, reason)?;
+
+        let termination = Termination { on, penalty };
+        self.termination = Some(termination);
+
+        Ok(termination)
+    }
+
+    /// The date the office is freed at, earlier than `end` when terminated
+    pub fn occupied_until(&self) -> NaiveDate {
+        self.termination
+            .map(|termination| termination.on)
+            .unwrap_or(self.end)
+    }
+
+    /// Dates the guest occupies an office during
+    pub fn period(&self) -> DateRange {
+        DateRange::new(self.start, self.occupied_until())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    use crate::{
+        indexation::{IndexationClause, Quarter, RentIndex},
+        test_support::{self, date},
+        user::{Role, User},
+    };
+
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    fn create_contract() -> Contract {
+        let host = User::new("Jeanne".to_string(), "Host".to_string(), Role::Host);
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), Role::Guest);
+        let office = test_support::office(&host);
+
+        Contract::new(
+            &host,
+            &guest,
+            &office,
+            10,
+            100000,
+            date(2025, 1, 1),
+            date(2026, 1, 1),
+        )
+        .unwrap()
+    }
+
+    fn create_active_contract() -> Contract {
+        let mut contract = create_contract();
+        contract
+            .transition(ContractState::Signed, at(2024, 12, 1), None)
+            .unwrap();
+        contract
+            .transition(ContractState::Active, at(2025, 1, 1), None)
+            .unwrap();
+
+        contract
+    }
+
+    #[test]
+    fn test_terminate() {
+        let mut contract = create_active_contract();
+
+        let termination = contract
+            .terminate(
+                at(2025, 3, 15),
+                Some("Moving out".to_string()),
+                &TerminationPolicy::default(),
+                &IndexTable::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            Termination::new_unchecked(date(2025, 6, 15), 302466),
+            termination
+        );
+        assert_eq!(ContractState::Terminated, *contract.state());
+        assert_eq!(Some(termination), *contract.termination());
+        assert_eq!(date(2025, 6, 15), contract.occupied_until());
+        assert_eq!(date(2026, 1, 1), *contract.end());
+    }
+
+    #[rstest]
+    #[case(TerminationPolicy::new(3, 12), 101918)]
+    #[case(TerminationPolicy::new(3, 0), 0)]
+    #[case(TerminationPolicy::new(3, u32::MAX), 101918)]
+    fn test_penalty_is_capped_by_remaining_rent(
+        #[case] policy: TerminationPolicy,
+        #[case] penalty: usize,
+    ) {
+        let mut contract = create_active_contract();
+
+        let termination = contract
+            .terminate(at(2025, 9, 1), None, &policy, &IndexTable::new())
+            .unwrap();
+
+        assert_eq!(penalty, *termination.penalty());
+    }
+
+    #[rstest]
+    #[case(TerminationPolicy::default(), at(2025, 1, 15))]
+    #[case(TerminationPolicy::new(0, 3), at(2025, 4, 30))]
+    fn test_cannot_terminate_before_minimum_duration(
+        #[case] policy: TerminationPolicy,
+        #[case] at: DateTime<Utc>,
+    ) {
+        let mut contract = create_active_contract();
+
+        assert!(matches!(
+            contract.terminate(at, None, &policy, &IndexTable::new()),
+            Err(ContractError::TerminatedBeforeMinimumDuration { .. })
+        ));
+        assert_eq!(ContractState::Active, *contract.state());
+        assert_eq!(None, *contract.termination());
+    }
+
+    #[test]
+    fn test_cannot_give_notice_beyond_supported_dates() {
+        let mut contract = create_active_contract();
+
+        assert_eq!(
+            Err(ContractError::NoticeBeyondSupportedDates {
+                at: date(2025, 6, 1),
+                notice_months: u32::MAX
+            }),
+            contract.terminate(
+                at(2025, 6, 1),
+                None,
+                &TerminationPolicy::new(u32::MAX, 3),
+                &IndexTable::new()
+            )
+        );
+        assert_eq!(ContractState::Active, *contract.state());
+    }
+
+    #[test]
+    fn test_cannot_terminate_after_end_date() {
+        let mut contract = create_active_contract();
+
+        assert!(matches!(
+            contract.terminate(
+                at(2025, 10, 1),
+                None,
+                &TerminationPolicy::default(),
+                &IndexTable::new()
+            ),
+            Err(ContractError::TerminatedAfterEndDate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cannot_terminate_inactive_contract() {
+        let mut contract = create_contract();
+
+        assert_eq!(
+            Err(ContractError::InvalidTransition {
+                from: ContractState::Draft,
+                to: ContractState::Terminated
+            }),
+            contract.terminate(
+                at(2025, 6, 1),
+                None,
+                &TerminationPolicy::default(),
+                &IndexTable::new()
+            )
+        );
+    }
+
+    #[test]
+    fn test_penalty_follows_revised_rent() {
+        let mut table = IndexTable::new();
+        table.insert(RentIndex::Ilat, Quarter::new(2025, 1).unwrap(), 10000);
+        table.insert(RentIndex::Ilat, Quarter::new(2026, 1).unwrap(), 11000);
+        let mut contract = create_active_contract();
+        contract.end = date(2027, 1, 1);
+        let mut contract = contract.with_indexation(IndexationClause::yearly_from(
+            RentIndex::Ilat,
+            date(2025, 1, 1),
+        ));
+
+        let termination = contract
+            .terminate(at(2025, 9, 15), None, &TerminationPolicy::default(), &table)
+            .unwrap();
+
+        // half a month at the base rent, then two and a half at the revised one
+        let base = RentPeriod::new(date(2025, 12, 15), date(2026, 1, 1), 100000);
+        let revised = RentPeriod::new(date(2026, 1, 1), date(2026, 3, 15), 110000);
+        assert_eq!(base.total() + revised.total(), *termination.penalty());
+    }
+}
@@ -0,0 +1,382 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// Mean Earth radius, in kilometers
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Length of a degree of latitude, or of longitude at the equator
+const KM_PER_DEGREE: f64 = EARTH_RADIUS_KM * std::f64::consts::PI / 180.0;
+
+/// Spatial index cells span half a degree both ways, about 55 km by 37 km in France
+const CELL_DEGREES: f64 = 0.5;
+const LONGITUDE_CELLS: i32 = (360.0 / CELL_DEGREES) as i32;
+
+#[derive(Debug, Clone, Copy, PartialEq, derive_getters::Getters)]
+pub struct Coordinates {
+    latitude: f32,
+    longitude: f32,
+}
+
+impl Coordinates {
+    pub fn new(latitude: f32, longitude: f32) -> Result<Self, GeoError> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(GeoError::LatitudeOutOfBounds(latitude));
+        }
+
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(GeoError::LongitudeOutOfBounds(longitude));
+        }
+
+        Ok(Self::new_unchecked(latitude, longitude))
+    }
+
+    pub fn new_unchecked(latitude: f32, longitude: f32) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Great-circle distance, using the haversine formula
+    pub fn distance_km(&self, other: &Coordinates) -> f64 {
+        let (latitude, other_latitude) = (
+            (self.latitude as f64).to_radians(),
+            (other.latitude as f64).to_radians(),
+        );
+        let latitude_delta = other_latitude - latitude;
+        let longitude_delta = (other.longitude as f64 - self.longitude as f64).to_radians();
+
+        let haversine = (latitude_delta / 2.0).sin().powi(2)
+            + latitude.cos() * other_latitude.cos() * (longitude_delta / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_KM * haversine.sqrt().asin()
+    }
+
+    /// Point reached by travelling `distance_km` from here, along a great circle starting with the
+    /// given bearing (clockwise from north, in degrees)
+    pub fn offset(&self, distance_km: f64, bearing: f64) -> Coordinates {
+        let angle = distance_km / EARTH_RADIUS_KM;
+        let bearing = bearing.to_radians();
+        let latitude = (self.latitude as f64).to_radians();
+
+        let destination_latitude =
+            (latitude.sin() * angle.cos() + latitude.cos() * angle.sin() * bearing.cos()).asin();
+        let longitude_delta = (bearing.sin() * angle.sin() * latitude.cos())
+            .atan2(angle.cos() - latitude.sin() * destination_latitude.sin());
+
+        Coordinates::new_unchecked(
+            destination_latitude.to_degrees() as f32,
+            // normalized to -180..180
+            ((self.longitude as f64 + longitude_delta.to_degrees() + 540.0) % 360.0 - 180.0) as f32,
+        )
+    }
+}
+
+/// A distance, parsed from kilometers ("5km", or "5") or meters ("500m")
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Distance {
+    km: f64,
+}
+
+impl Distance {
+    pub fn from_km(km: f64) -> Self {
+        Self { km }
+    }
+
+    pub fn km(&self) -> f64 {
+        self.km
+    }
+}
+
+impl Display for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.km < 1.0 {
+            write!(f, "{:.0}m", self.km * 1000.0)
+        } else {
+            write!(f, "{:.1}km", self.km)
+        }
+    }
+}
+
+impl FromStr for Distance {
+    type Err = GeoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .ok_or_else(|| GeoError::InvalidDistance(s.to_string()))
+        };
+
+        Ok(Self::from_km(if let Some(km) = s.strip_suffix("km") {
+            parse(km)?
+        } else if let Some(meters) = s.strip_suffix('m') {
+            parse(meters)? / 1000.0
+        } else {
+            parse(&s)?
+        }))
+    }
+}
+
+/// Items located on a grid of cells, so that radius searches only measure the items of the cells
+/// around their center
+#[derive(Debug)]
+pub struct SpatialIndex<T> {
+    cells: HashMap<(i32, i32), Vec<(Coordinates, T)>>,
+}
+
+impl<T> Default for SpatialIndex<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(Coordinates, T)> for SpatialIndex<T> {
+    fn from_iter<I: IntoIterator<Item = (Coordinates, T)>>(iter: I) -> Self {
+        let mut index = Self::default();
+        for (coordinates, item) in iter {
+            index.insert(coordinates, item);
+        }
+
+        index
+    }
+}
+
+impl<T> SpatialIndex<T> {
+    pub fn insert(&mut self, coordinates: Coordinates, item: T) {
+        self.cells
+            .entry(Self::cell(&coordinates))
+            .or_default()
+            .push((coordinates, item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Items at most `radius` away from `center`, nearest first
+    pub fn within(&self, center: &Coordinates, radius: Distance) -> Vec<(Distance, &T)> {
+        let latitude_span = radius.km / KM_PER_DEGREE;
+        let (south, north) = (
+            center.latitude as f64 - latitude_span,
+            center.latitude as f64 + latitude_span,
+        );
+        // narrowest parallel of the search area, the one closest to a pole
+        let longitude_span =
+            radius.km / (KM_PER_DEGREE * south.abs().max(north.abs()).min(90.0).to_radians().cos());
+
+        let candidates: Box<dyn Iterator<Item = &(Coordinates, T)>> = if south <= -90.0
+            || north >= 90.0
+            || !longitude_span.is_finite()
+            || longitude_span >= 180.0
+        {
+            Box::new(self.cells.values().flatten())
+        } else {
+            let rows = Self::row(south)..=Self::row(north);
+            let columns = ((center.longitude as f64 - longitude_span + 180.0) / CELL_DEGREES)
+                .floor() as i32
+                ..=((center.longitude as f64 + longitude_span + 180.0) / CELL_DEGREES).floor()
+                    as i32;
+
+            Box::new(rows.flat_map(move |row| {
+                columns.clone().flat_map(move |column| {
+                    self.cells
+                        .get(&(row, column.rem_euclid(LONGITUDE_CELLS)))
+                        .into_iter()
+                        .flatten()
+                })
+            }))
+        };
+
+        let mut found = candidates
+            .map(|(coordinates, item)| (Distance::from_km(center.distance_km(coordinates)), item))
+            .filter(|(distance, _)| *distance <= radius)
+            .collect::<Vec<_>>();
+        found.sort_by(|(a, _), (b, _)| a.km.total_cmp(&b.km));
+
+        found
+    }
+
+    fn row(latitude: f64) -> i32 {
+        ((latitude + 90.0) / CELL_DEGREES).floor() as i32
+    }
+
+    /// The 180th meridian is the -180th one, both falling in the first column
+    fn cell(coordinates: &Coordinates) -> (i32, i32) {
+        (
+            Self::row(coordinates.latitude as f64),
+            (((coordinates.longitude as f64 + 180.0) / CELL_DEGREES).floor() as i32)
+                .rem_euclid(LONGITUDE_CELLS),
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum GeoError {
+    #[error("Latitude ({0}) is out of bounds")]
+    LatitudeOutOfBounds(f32),
+    #[error("Longitude ({0}) is out of bounds")]
+    LongitudeOutOfBounds(f32),
+    #[error("Invalid distance {0}, expected e.g. \"5km\" or \"500m\"")]
+    InvalidDistance(String),
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    // Paris, Notre-Dame to the Eiffel Tower
+    #[case((48.853, 2.3499), (48.8584, 2.2945), 4.1)]
+    // Paris to Lyon
+    #[case((48.8566, 2.3522), (45.764, 4.8357), 391.5)]
+    #[case((48.8566, 2.3522), (48.8566, 2.3522), 0.0)]
+    fn test_distance_km(#[case] from: (f32, f32), #[case] to: (f32, f32), #[case] expected: f64) {
+        let from = Coordinates::new(from.0, from.1).unwrap();
+        let to = Coordinates::new(to.0, to.1).unwrap();
+
+        assert!((from.distance_km(&to) - expected).abs() < 0.5);
+        assert!((to.distance_km(&from) - expected).abs() < 0.5);
+    }
+
+    #[rstest]
+    #[case(90.0, 180.0, true)]
+    #[case(-90.0, -180.0, true)]
+    #[case(90.01, 0.0, false)]
+    #[case(0.0, -180.01, false)]
+    fn test_new(#[case] latitude: f32, #[case] longitude: f32, #[case] is_valid: bool) {
+        assert_eq!(is_valid, Coordinates::new(latitude, longitude).is_ok());
+    }
+
+    #[test]
+    fn test_within_across_the_antimeridian() {
+        let index = SpatialIndex::from_iter([(Coordinates::new(0.0, 180.0).unwrap(), ())]);
+
+        assert_eq!(
+            1,
+            index
+                .within(
+                    &Coordinates::new(0.0, -179.99).unwrap(),
+                    Distance::from_km(5.0)
+                )
+                .len()
+        );
+    }
+
+    #[rstest]
+    #[case((48.8566, 2.3522), 3.0, 0.0, (48.8836, 2.3522))]
+    #[case((48.8566, 2.3522), 3.0, 90.0, (48.8566, 2.3932))]
+    #[case((48.8566, 179.99), 10.0, 90.0, (48.8565, -179.8733))]
+    fn test_offset(
+        #[case] from: (f32, f32),
+        #[case] distance_km: f64,
+        #[case] bearing: f64,
+        #[case] expected: (f32, f32),
+    ) {
+        let from = Coordinates::new(from.0, from.1).unwrap();
+        let to = from.offset(distance_km, bearing);
+
+        assert!((to.latitude - expected.0).abs() < 0.001, "{to:?}");
+        assert!((to.longitude - expected.1).abs() < 0.001, "{to:?}");
+        assert!((from.distance_km(&to) - distance_km).abs() < 0.01);
+    }
+}
+
+#[cfg(test)]
+mod distance_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("5km", Ok(5.0))]
+    #[case("5 km", Ok(5.0))]
+    #[case("2.5KM", Ok(2.5))]
+    #[case("500m", Ok(0.5))]
+    #[case("12", Ok(12.0))]
+    #[case("-1km", Err(()))]
+    #[case("five", Err(()))]
+    fn test_parse(#[case] input: &str, #[case] expected: Result<f64, ()>) {
+        assert_eq!(
+            expected,
+            Distance::from_str(input)
+                .map(|distance| distance.km())
+                .map_err(|_| ())
+        );
+    }
+}
+
+#[cfg(test)]
+mod spatial_index_properties {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn coordinates() -> impl Strategy<Value = Coordinates> {
+        (-89.99f32..89.99, -179.99f32..179.99)
+            .prop_map(|(latitude, longitude)| Coordinates::new_unchecked(latitude, longitude))
+    }
+
+    /// Points around a center, some of them across the antimeridian or close to a pole
+    fn cluster() -> impl Strategy<Value = (Coordinates, Vec<Coordinates>)> {
+        (
+            prop_oneof![
+                coordinates(),
+                Just(Coordinates::new_unchecked(48.85, 179.9)),
+                Just(Coordinates::new_unchecked(89.5, 0.0)),
+            ],
+            prop::collection::vec((-2.0f32..2.0, -2.0f32..2.0), 0..40),
+        )
+            .prop_map(|(center, offsets)| {
+                let points = offsets
+                    .into_iter()
+                    .map(|(latitude, longitude)| {
+                        Coordinates::new_unchecked(
+                            (center.latitude + latitude).clamp(-89.99, 89.99),
+                            (center.longitude + longitude + 180.0).rem_euclid(360.0) - 180.0,
+                        )
+                    })
+                    .collect();
+
+                (center, points)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_within_matches_a_full_scan(
+            (center, points) in cluster(),
+            radius in 0.0f64..300.0,
+        ) {
+            let index = points.iter().copied().enumerate().map(|(i, point)| (point, i)).collect::<SpatialIndex<_>>();
+            let radius = Distance::from_km(radius);
+
+            let mut expected = points
+                .iter()
+                .enumerate()
+                .filter(|(_, point)| center.distance_km(point) <= radius.km())
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            expected.sort();
+
+            let found = index.within(&center, radius);
+            prop_assert!(found.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+            let mut found = found.into_iter().map(|(_, i)| *i).collect::<Vec<_>>();
+            found.sort();
+            prop_assert_eq!(expected, found);
+            prop_assert_eq!(points.len(), index.len());
+        }
+    }
+}
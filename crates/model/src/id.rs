@@ -3,6 +3,17 @@ pub trait Identifier {
     fn uuid(&self) -> &uuid::Uuid;
 }
 
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum IdentifierError {
+    #[error(r#"Expected an identifier prefixed with "{expected}", got "{given}""#)]
+    InvalidPrefix {
+        expected: &'static str,
+        given: String,
+    },
+    #[error(transparent)]
+    InvalidUuid(#[from] uuid::Error),
+}
+
 /// Generate an X struct holding an uuid and generating it's default implementation
 /// ```ignore
 /// crate::model_id!(UserId, "usr");
@@ -10,7 +21,7 @@ pub trait Identifier {
 ///
 /// Will expand to
 /// ```
-/// #[derive(Clone, Copy, PartialEq, Eq)]
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 /// pub struct UserId(uuid::Uuid);
 ///
 /// impl std::fmt::Display for UserId {
@@ -40,11 +51,26 @@ pub trait Identifier {
 ///        Self(value)
 ///     }
 /// }
+///
+/// impl std::str::FromStr for UserId {
+///     type Err = sora_model::id::IdentifierError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         let uuid = s.strip_prefix("usr-").ok_or_else(|| {
+///             sora_model::id::IdentifierError::InvalidPrefix {
+///                 expected: "usr",
+///                 given: s.to_string(),
+///             }
+///         })?;
+///
+///         Ok(Self(uuid::Uuid::parse_str(uuid)?))
+///     }
+/// }
 /// ```
 #[macro_export]
 macro_rules! model_id {
     ($model_name:ident, $repr:literal) => {
-        #[derive(Clone, Copy, PartialEq, Eq)]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
         pub struct $model_name(uuid::Uuid);
 
         impl std::fmt::Display for $model_name {
@@ -74,6 +100,21 @@ macro_rules! model_id {
                 Self(value)
             }
         }
+
+        impl std::str::FromStr for $model_name {
+            type Err = $crate::id::IdentifierError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let uuid = s.strip_prefix(concat!($repr, "-")).ok_or_else(|| {
+                    $crate::id::IdentifierError::InvalidPrefix {
+                        expected: $repr,
+                        given: s.to_string(),
+                    }
+                })?;
+
+                Ok(Self(uuid::Uuid::parse_str(uuid)?))
+            }
+        }
     };
 }
 
@@ -81,11 +122,12 @@ macro_rules! model_id {
 mod test {
     use uuid::Uuid;
 
-    use super::Identifier;
+    use super::{Identifier, IdentifierError};
 
     model_id!(TestId, "tst");
 
     #[test]
+    #[allow(clippy::clone_on_copy, clippy::no_effect)]
     pub fn test_identifiers_generation() {
         // Ensure this compiles and doesn't panic
         let id = TestId::new();
@@ -96,4 +138,19 @@ mod test {
         id.0;
         _ = TestId::from(Uuid::now_v7());
     }
+
+    #[test]
+    pub fn test_identifiers_parsing() {
+        let id = TestId::new();
+
+        assert_eq!(Ok(id), id.to_string().parse::<TestId>());
+        assert!(matches!(
+            id.uuid().to_string().parse::<TestId>(),
+            Err(IdentifierError::InvalidPrefix { .. })
+        ));
+        assert!(matches!(
+            "tst-not-an-uuid".parse::<TestId>(),
+            Err(IdentifierError::InvalidUuid(_))
+        ));
+    }
 }
@@ -0,0 +1,362 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use chrono::{Datelike, Months, NaiveDate};
+
+/// Reference indices commercial leases are revised against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RentIndex {
+    /// Indice des loyers des activités tertiaires
+    Ilat,
+    /// Indice des loyers commerciaux
+    Ilc,
+    /// Indice du coût de la construction
+    Icc,
+}
+
+impl FromStr for RentIndex {
+    type Err = IndexationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "ilat" => Self::Ilat,
+            "ilc" => Self::Ilc,
+            "icc" => Self::Icc,
+            _ => return Err(IndexationError::UnknownIndex(s.to_string())),
+        })
+    }
+}
+
+impl Display for RentIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                RentIndex::Ilat => "ILAT",
+                RentIndex::Ilc => "ILC",
+                RentIndex::Icc => "ICC",
+            }
+        )
+    }
+}
+
+/// Indices are published quarterly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quarter {
+    year: i32,
+    /// From 1 to 4
+    quarter: u32,
+}
+
+impl Quarter {
+    pub fn new(year: i32, quarter: u32) -> Result<Self, IndexationError> {
+        if !(1..=4).contains(&quarter) {
+            return Err(IndexationError::QuarterOutOfBounds(quarter));
+        }
+
+        Ok(Self { year, quarter })
+    }
+
+    pub fn containing(date: NaiveDate) -> Self {
+        Self {
+            year: date.year(),
+            quarter: date.month0() / 3 + 1,
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn quarter(&self) -> u32 {
+        self.quarter
+    }
+
+    /// Same quarter, `years` later
+    pub fn years_later(&self, years: u32) -> Self {
+        Self {
+            year: self.year + years as i32,
+            quarter: self.quarter,
+        }
+    }
+}
+
+impl Display for Quarter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-T{}", self.year, self.quarter)
+    }
+}
+
+/// Published index values, in hundredths (`13369` is 133.69)
+#[derive(Debug, Default)]
+pub struct IndexTable {
+    values: BTreeMap<(RentIndex, Quarter), usize>,
+}
+
+impl IndexTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects an `index,year,quarter,value` header followed by one value per line, e.g.
+    /// `ILAT,2024,1,135.30`. Empty lines and lines starting with `#` are ignored
+    pub fn from_csv(csv: &str) -> Result<Self, IndexationError> {
+        let mut table = Self::new();
+
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        if let Some((line_number, header)) = lines.next() {
+            if header.replace(' ', "") != "index,year,quarter,value" {
+                return Err(IndexationError::MalformedCsvLine(line_number));
+            }
+        }
+
+        for (line_number, line) in lines {
+            let malformed = |_| IndexationError::MalformedCsvLine(line_number);
+
+            let [index, year, quarter, value] = line
+                .split(',')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| IndexationError::MalformedCsvLine(line_number))?;
+
+            let quarter = Quarter::new(
+                year.parse().map_err(malformed)?,
+                quarter.parse().map_err(malformed)?,
+            )?;
+            let value = value
+                .parse::<f64>()
+                .map_err(|_| IndexationError::MalformedCsvLine(line_number))?;
+
+            table.insert(
+                RentIndex::from_str(index)?,
+                quarter,
+                (value * 100.0).round() as usize,
+            );
+        }
+
+        Ok(table)
+    }
+
+    pub fn insert(&mut self, index: RentIndex, quarter: Quarter, value: usize) {
+        self.values.insert((index, quarter), value);
+    }
+
+    /// Whether a value was published for this very quarter
+    pub fn is_published(&self, index: RentIndex, quarter: Quarter) -> bool {
+        self.values.contains_key(&(index, quarter))
+    }
+
+    /// The latest value published at or before the given quarter
+    pub fn value_at(&self, index: RentIndex, quarter: Quarter) -> Option<usize> {
+        self.values
+            .range((index, Quarter::new(i32::MIN, 1).unwrap())..=(index, quarter))
+            .next_back()
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Rent is revised every year on the `anniversary` date, by the variation of `index` between the
+/// `reference` quarter and the same quarter of the revision year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct IndexationClause {
+    index: RentIndex,
+    reference: Quarter,
+    anniversary: NaiveDate,
+}
+
+impl IndexationClause {
+    pub fn new(index: RentIndex, reference: Quarter, anniversary: NaiveDate) -> Self {
+        Self {
+            index,
+            reference,
+            anniversary,
+        }
+    }
+
+    /// First revision a year after `start`, against the index known when the contract started
+    pub fn yearly_from(index: RentIndex, start: NaiveDate) -> Self {
+        Self::new(
+            index,
+            Quarter::containing(start),
+            start.checked_add_months(Months::new(12)).unwrap(),
+        )
+    }
+
+    /// `base_rent` revised for the `revision`th anniversary; Falls back to the base rent when the
+    /// index values are missing
+    pub fn revised_rent(&self, base_rent: usize, revision: u32, table: &IndexTable) -> usize {
+        let reference_value = table.value_at(self.index, self.reference);
+        let revision_value = table.value_at(self.index, self.reference.years_later(revision));
+
+        match (reference_value, revision_value) {
+            (Some(reference_value), Some(revision_value)) if reference_value > 0 => {
+                ((base_rent * revision_value + reference_value / 2) / reference_value).max(1)
+            }
+            _ => base_rent,
+        }
+    }
+
+    /// Quarters the rent is revised against before `end` without a published value, revisions
+    /// using the latest earlier value instead
+    pub fn unpublished_quarters(&self, end: NaiveDate, table: &IndexTable) -> Vec<Quarter> {
+        let revisions = self.revision_dates(end).len() as u32;
+
+        (0..=revisions)
+            .map(|revision| self.reference.years_later(revision))
+            .filter(|quarter| !table.is_published(self.index, *quarter))
+            .collect()
+    }
+
+    /// Dates the rent is revised at, strictly before `end`
+    pub fn revision_dates(&self, end: NaiveDate) -> Vec<NaiveDate> {
+        (0..)
+            .map(|year| self.anniversary.checked_add_months(Months::new(12 * year)))
+            .take_while(|date| date.is_some_and(|date| date < end))
+            .flatten()
+            .collect()
+    }
+}
+
+/// Monthly rent applicable from `start` (inclusive) to `end` (exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct RentPeriod {
+    start: NaiveDate,
+    end: NaiveDate,
+    rent: usize,
+}
+
+impl RentPeriod {
+    pub fn new(start: NaiveDate, end: NaiveDate, rent: usize) -> Self {
+        Self { start, end, rent }
+    }
+
+    /// Rent owed for the whole period, prorated by day over an average month
+    pub fn total(&self) -> usize {
+        let days = (self.end - self.start).num_days().max(0) as usize;
+
+        (self.rent * days * 12 + 365 / 2) / 365
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum IndexationError {
+    #[error(r#"Unknown rent index "{0}""#)]
+    UnknownIndex(String),
+    #[error("Given quarter ({0}) is out of bounds.")]
+    QuarterOutOfBounds(u32),
+    #[error("Malformed index values csv at line {0}")]
+    MalformedCsvLine(usize),
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_support::date;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn quarter(y: i32, q: u32) -> Quarter {
+        Quarter::new(y, q).unwrap()
+    }
+
+    const CSV: &str = "# sample values
+index,year,quarter,value
+ILAT,2023,4,130.00
+ILAT,2024,4,132.60
+
+ILAT,2025,4,127.40
+ILC,2024,4,135.00
+";
+
+    #[test]
+    fn test_from_csv() {
+        let table = IndexTable::from_csv(CSV).unwrap();
+
+        assert_eq!(
+            Some(13000),
+            table.value_at(RentIndex::Ilat, quarter(2023, 4))
+        );
+        assert_eq!(
+            Some(13500),
+            table.value_at(RentIndex::Ilc, quarter(2024, 4))
+        );
+        assert_eq!(None, table.value_at(RentIndex::Icc, quarter(2024, 4)));
+    }
+
+    #[rstest]
+    #[case("index,year,quarter,value\nILAT,2024,4")]
+    #[case("index,year,quarter,value\nILAT,2024,5,130.0")]
+    #[case("index,year,quarter,value\nILAT,2024,4,abc")]
+    #[case("year,quarter,value\n2024,4,130.0")]
+    fn test_malformed_csv(#[case] csv: &str) {
+        assert!(IndexTable::from_csv(csv).is_err());
+    }
+
+    #[test]
+    fn test_value_falls_back_to_latest_published() {
+        let table = IndexTable::from_csv(CSV).unwrap();
+
+        assert_eq!(
+            Some(13260),
+            table.value_at(RentIndex::Ilat, quarter(2025, 2))
+        );
+        assert_eq!(None, table.value_at(RentIndex::Ilat, quarter(2023, 3)));
+    }
+
+    #[rstest]
+    #[case(0, 100000)]
+    #[case(1, 102000)]
+    #[case(2, 98000)]
+    #[case(3, 98000)]
+    fn test_revised_rent(#[case] revision: u32, #[case] expected_rent: usize) {
+        let table = IndexTable::from_csv(CSV).unwrap();
+        let clause = IndexationClause::yearly_from(RentIndex::Ilat, date(2023, 11, 15));
+
+        assert_eq!(expected_rent, clause.revised_rent(100000, revision, &table));
+    }
+
+    #[test]
+    fn test_revised_rent_without_values() {
+        let clause = IndexationClause::yearly_from(RentIndex::Icc, date(2023, 11, 15));
+
+        assert_eq!(100000, clause.revised_rent(100000, 1, &IndexTable::new()));
+    }
+
+    #[test]
+    fn test_unpublished_quarters() {
+        let table = IndexTable::from_csv(CSV).unwrap();
+        let clause = IndexationClause::yearly_from(RentIndex::Ilat, date(2023, 11, 15));
+
+        assert!(clause
+            .unpublished_quarters(date(2025, 11, 15), &table)
+            .is_empty());
+        assert_eq!(
+            vec![quarter(2026, 4), quarter(2027, 4)],
+            clause.unpublished_quarters(date(2027, 12, 1), &table)
+        );
+    }
+
+    #[test]
+    fn test_revision_dates() {
+        let clause = IndexationClause::yearly_from(RentIndex::Ilat, date(2024, 1, 1));
+
+        assert_eq!(
+            vec![date(2025, 1, 1), date(2026, 1, 1)],
+            clause.revision_dates(date(2026, 6, 1))
+        );
+        assert!(clause.revision_dates(date(2025, 1, 1)).is_empty());
+    }
+
+    #[rstest]
+    #[case(date(2025, 1, 1), date(2026, 1, 1), 120000)]
+    #[case(date(2025, 1, 1), date(2025, 1, 1), 0)]
+    fn test_period_total(#[case] start: NaiveDate, #[case] end: NaiveDate, #[case] total: usize) {
+        assert_eq!(total, RentPeriod::new(start, end, 10000).total());
+    }
+}
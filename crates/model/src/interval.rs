@@ -0,0 +1,474 @@
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+
+/// Dates from `start` (inclusive) to `end` (exclusive), empty when `end` is not after `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, derive_getters::Getters)]
+pub struct DateRange {
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+impl DateRange {
+    pub const fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Number of days in the range, 0 when empty
+    pub fn days(&self) -> usize {
+        (self.end - self.start).num_days().max(0) as usize
+    }
+
+    pub fn contains_date(&self, date: NaiveDate) -> bool {
+        self.start <= date && date < self.end
+    }
+
+    /// This representation will return true
+    ///       |====self===|
+    /// |=======other=======|
+    pub fn is_contained_in(&self, other: &Self) -> bool {
+        self.is_empty() || (other.start <= self.start && self.end <= other.end)
+    }
+
+    /// Ranges sharing at least a day, back to back ranges don't overlap
+    /// |=====self=======|
+    ///      |======other======|
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+impl Display for DateRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// A set of dates, stored as sorted, disjoint and non adjacent ranges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<DateRange>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[DateRange] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of days in the set
+    pub fn total_days(&self) -> usize {
+        self.ranges.iter().map(DateRange::days).sum()
+    }
+
+    pub fn contains_date(&self, date: NaiveDate) -> bool {
+        self.ranges.iter().any(|range| range.contains_date(date))
+    }
+
+    pub fn overlaps(&self, range: &DateRange) -> bool {
+        self.ranges.iter().any(|existing| existing.overlaps(range))
+    }
+
+    pub fn insert(&mut self, range: DateRange) {
+        if range.is_empty() {
+            return;
+        }
+
+        // ranges ending before the inserted one starts are left untouched, as are ranges starting
+        // after it ends; the others are merged into it
+        let first = self
+            .ranges
+            .partition_point(|existing| existing.end < range.start);
+        let last = self
+            .ranges
+            .partition_point(|existing| existing.start <= range.end);
+
+        let merged = self.ranges[first..last]
+            .iter()
+            .fold(range, |merged, existing| {
+                DateRange::new(
+                    merged.start.min(existing.start),
+                    merged.end.max(existing.end),
+                )
+            });
+
+        self.ranges.splice(first..last, std::iter::once(merged));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in other.ranges.iter() {
+            union.insert(*range);
+        }
+
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Vec::new();
+        let (mut left, mut right) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            let range = a.intersection(b);
+            if !range.is_empty() {
+                intersection.push(range);
+            }
+
+            // the range ending first cannot intersect any further range of the other set
+            if a.end < b.end {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+
+        Self {
+            ranges: intersection,
+        }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        match self.bounds() {
+            Some(bounds) => self.intersection(&other.complement(bounds)),
+            None => Self::new(),
+        }
+    }
+
+    /// Dates of `within` that are not part of the set
+    pub fn complement(&self, within: DateRange) -> Self {
+        let mut complement = Vec::new();
+        let mut current_start = within.start;
+
+        for range in self.ranges.iter() {
+            if range.start >= within.end {
+                break;
+            }
+
+            if current_start < range.start {
+                complement.push(DateRange::new(current_start, range.start));
+            }
+
+            current_start = current_start.max(range.end);
+        }
+
+        if current_start < within.end {
+            complement.push(DateRange::new(current_start, within.end));
+        }
+
+        Self { ranges: complement }
+    }
+
+    /// Smallest range containing the whole set
+    pub fn bounds(&self) -> Option<DateRange> {
+        Some(DateRange::new(
+            self.ranges.first()?.start,
+            self.ranges.last()?.end,
+        ))
+    }
+}
+
+impl FromIterator<DateRange> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = DateRange>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod date_range_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn create_date_range(start: (i32, u32, u32), end: (i32, u32, u32)) -> DateRange {
+        DateRange::new(
+            NaiveDate::from_ymd_opt(start.0, start.1, start.2).unwrap(),
+            NaiveDate::from_ymd_opt(end.0, end.1, end.2).unwrap(),
+        )
+    }
+
+    #[rstest]
+    #[case(
+        (2024, 1, 1), (2024, 1, 10),
+        (2024, 1, 11), (2024, 1, 20),
+        false
+    )]
+    #[case(
+        (2024, 1, 5), (2024, 1, 15),
+        (2024, 1, 10), (2024, 1, 20),
+        true
+    )]
+    #[case(
+        (2024, 1, 5), (2024, 1, 15),
+        (2024, 1, 5), (2024, 1, 15),
+        true
+    )]
+    #[case(
+        (2024, 1, 5), (2024, 1, 15),
+        (2024, 1, 7), (2024, 1, 10),
+        true
+    )]
+    // back to back
+    #[case(
+        (2024, 1, 1), (2024, 1, 10),
+        (2024, 1, 10), (2024, 1, 20),
+        false
+    )]
+    // empty
+    #[case(
+        (2024, 1, 7), (2024, 1, 7),
+        (2024, 1, 5), (2024, 1, 15),
+        false
+    )]
+    fn test_overlaps(
+        #[case] start1: (i32, u32, u32),
+        #[case] end1: (i32, u32, u32),
+        #[case] start2: (i32, u32, u32),
+        #[case] end2: (i32, u32, u32),
+        #[case] expected: bool,
+    ) {
+        let range1 = create_date_range(start1, end1);
+        let range2 = create_date_range(start2, end2);
+
+        assert_eq!(expected, range1.overlaps(&range2));
+        assert_eq!(expected, range2.overlaps(&range1));
+    }
+
+    #[rstest]
+    #[case(
+        (2024, 1, 3), (2024, 1, 7),
+        (2024, 1, 1), (2024, 1, 10),
+        true
+    )]
+    #[case(
+        (2024, 1, 1), (2024, 1, 10),
+        (2024, 1, 3), (2024, 1, 7),
+        false
+    )]
+    #[case(
+        (2024, 1, 1), (2024, 1, 5),
+        (2024, 1, 3), (2024, 1, 10),
+        false
+    )]
+    #[case(
+        (2024, 1, 3), (2024, 1, 10),
+        (2024, 1, 1), (2024, 1, 5),
+        false
+    )]
+    fn test_is_contained_in(
+        #[case] start1: (i32, u32, u32),
+        #[case] end1: (i32, u32, u32),
+        #[case] start2: (i32, u32, u32),
+        #[case] end2: (i32, u32, u32),
+        #[case] expected: bool,
+    ) {
+        let range1 = create_date_range(start1, end1);
+        let range2 = create_date_range(start2, end2);
+
+        assert_eq!(expected, range1.is_contained_in(&range2));
+    }
+}
+
+#[cfg(test)]
+mod interval_set_test {
+    use crate::test_support::date;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn range(start: u32, end: u32) -> DateRange {
+        DateRange::new(date(2023, 1, start), date(2023, 1, end))
+    }
+
+    #[rstest]
+    #[case(
+        "Single gap before and after a range",
+        vec![range(10, 15)],
+        range(5, 20),
+        vec![range(5, 10), range(15, 20)]
+    )]
+    #[case(
+        "Multiple gaps between non-overlapping ranges",
+        vec![range(10, 15), range(20, 25)],
+        range(5, 30),
+        vec![range(5, 10), range(15, 20), range(25, 30)]
+    )]
+    #[case(
+        "No gaps when the range covers the entire period",
+        vec![range(5, 30)],
+        range(5, 30),
+        vec![]
+    )]
+    #[case(
+        "Empty input results in one large range",
+        vec![],
+        range(5, 30),
+        vec![range(5, 30)]
+    )]
+    #[case(
+        "Overlapping ranges are merged",
+        vec![range(10, 15), range(12, 20), range(1, 7)],
+        range(5, 25),
+        vec![range(7, 10), range(20, 25)]
+    )]
+    fn test_complement(
+        #[case] scenario: &'static str,
+        #[case] ranges: Vec<DateRange>,
+        #[case] within: DateRange,
+        #[case] expected: Vec<DateRange>,
+    ) {
+        let set = ranges.into_iter().collect::<IntervalSet>();
+
+        assert_eq!(expected, set.complement(within).ranges(), "{scenario}");
+    }
+
+    #[test]
+    fn test_adjacent_ranges_are_merged() {
+        let set = [range(10, 15), range(1, 5), range(5, 10)]
+            .into_iter()
+            .collect::<IntervalSet>();
+
+        assert_eq!(&[range(1, 15)], set.ranges());
+        assert_eq!(14, set.total_days());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = [range(1, 10), range(20, 25)]
+            .into_iter()
+            .collect::<IntervalSet>();
+        let b = [range(5, 22)].into_iter().collect::<IntervalSet>();
+
+        assert_eq!(&[range(1, 25)], a.union(&b).ranges());
+        assert_eq!(&[range(5, 10), range(20, 22)], a.intersection(&b).ranges());
+        assert_eq!(&[range(1, 5), range(22, 25)], a.difference(&b).ranges());
+        assert_eq!(&[range(10, 20)], b.difference(&a).ranges());
+    }
+}
+
+#[cfg(test)]
+mod interval_set_properties {
+    use chrono::Days;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Dates are picked within a few weeks so that generated ranges often overlap
+    const DAYS: u64 = 60;
+
+    fn day(offset: u64) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .checked_add_days(Days::new(offset))
+            .unwrap()
+    }
+
+    fn domain() -> impl Iterator<Item = NaiveDate> {
+        (0..DAYS).map(day)
+    }
+
+    fn date_range() -> impl Strategy<Value = DateRange> {
+        (0..DAYS, 0..DAYS).prop_map(|(start, end)| DateRange::new(day(start), day(end)))
+    }
+
+    fn interval_set() -> impl Strategy<Value = IntervalSet> {
+        prop::collection::vec(date_range(), 0..8).prop_map(IntervalSet::from_iter)
+    }
+
+    fn assert_normalized(set: &IntervalSet) {
+        for range in set.ranges() {
+            assert!(!range.is_empty(), "{range} is empty");
+        }
+
+        for pair in set.ranges().windows(2) {
+            assert!(
+                pair[0].end < pair[1].start,
+                "{} and {} should be merged",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_from_ranges(ranges in prop::collection::vec(date_range(), 0..8)) {
+            let set = ranges.iter().copied().collect::<IntervalSet>();
+
+            assert_normalized(&set);
+            for date in domain() {
+                prop_assert_eq!(
+                    ranges.iter().any(|range| range.contains_date(date)),
+                    set.contains_date(date)
+                );
+            }
+            prop_assert_eq!(
+                domain().filter(|date| set.contains_date(*date)).count(),
+                set.total_days()
+            );
+        }
+
+        #[test]
+        fn test_union(a in interval_set(), b in interval_set()) {
+            let union = a.union(&b);
+
+            assert_normalized(&union);
+            for date in domain() {
+                prop_assert_eq!(a.contains_date(date) || b.contains_date(date), union.contains_date(date));
+            }
+        }
+
+        #[test]
+        fn test_intersection(a in interval_set(), b in interval_set()) {
+            let intersection = a.intersection(&b);
+
+            assert_normalized(&intersection);
+            for date in domain() {
+                prop_assert_eq!(a.contains_date(date) && b.contains_date(date), intersection.contains_date(date));
+            }
+        }
+
+        #[test]
+        fn test_difference(a in interval_set(), b in interval_set()) {
+            let difference = a.difference(&b);
+
+            assert_normalized(&difference);
+            for date in domain() {
+                prop_assert_eq!(a.contains_date(date) && !b.contains_date(date), difference.contains_date(date));
+            }
+            prop_assert_eq!(a.total_days(), difference.total_days() + a.intersection(&b).total_days());
+        }
+
+        #[test]
+        fn test_complement(a in interval_set(), within in date_range()) {
+            let complement = a.complement(within);
+
+            assert_normalized(&complement);
+            for date in domain() {
+                prop_assert_eq!(within.contains_date(date) && !a.contains_date(date), complement.contains_date(date));
+            }
+        }
+    }
+}
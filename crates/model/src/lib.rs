@@ -1,9 +1,19 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
 use uuid::Uuid;
 
+pub mod address;
+pub mod calendar;
 pub mod contract;
+pub mod geo;
 pub mod id;
+pub mod indexation;
+pub mod interval;
 pub mod office;
+pub mod preference;
+pub mod tax;
+pub mod team;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod user;
 
 pub trait Object {
@@ -15,3 +25,10 @@ pub trait Object {
 
     fn created_at(&self) -> &DateTime<Utc>;
 }
+
+/// Creation dates are truncated to the microsecond, the precision they are persisted with
+pub(crate) fn now() -> DateTime<Utc> {
+    Utc::now()
+        .duration_trunc(TimeDelta::microseconds(1))
+        .unwrap()
+}
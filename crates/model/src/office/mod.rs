@@ -1,17 +1,29 @@
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
 
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use validator::{validate_available_positions_for_surface, AvailablePositionsError};
+pub use window::{OfficeWindow, OfficeWindowError, OfficeWindowId, WindowKind};
 
-use crate::{id::Identifier, model_id, user::UserId, Object};
+use crate::{
+    address::Address,
+    geo::{Coordinates, GeoError},
+    id::{Identifier, IdentifierError},
+    model_id,
+    user::UserId,
+    Object,
+};
 
 mod validator;
+mod window;
 
 model_id!(RealOfficeId, "ofc");
 model_id!(OfficeSplitId, "spl");
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OfficeId {
     RealOffice(RealOfficeId),
     OfficeSplit(OfficeSplitId),
@@ -41,11 +53,24 @@ impl OfficeId {
     }
 }
 
+/// Parses both real offices ("ofc-...") and splits ("spl-...") identifiers
+impl FromStr for OfficeId {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("spl-") {
+            Ok(OfficeId::OfficeSplit(OfficeSplitId::from_str(s)?))
+        } else {
+            Ok(OfficeId::RealOffice(RealOfficeId::from_str(s)?))
+        }
+    }
+}
+
 #[derive(Debug, derive_getters::Getters)]
 pub struct Office {
     id: OfficeId,
     name: String,
-    address: String,
+    address: Address,
     latitude: f32,
     longitude: f32,
     owner: UserId,
@@ -58,9 +83,10 @@ pub struct Office {
 }
 
 impl Office {
+    #[allow(clippy::too_many_arguments)]
     pub fn new_real(
         name: String,
-        address: String,
+        address: Address,
         latitude: f32,
         longitude: f32,
         owner: UserId,
@@ -70,13 +96,7 @@ impl Office {
     ) -> Result<Self, OfficeError> {
         use OfficeError::*;
 
-        if !(-90.0..90.0).contains(&latitude) {
-            return Err(LatitudeOutOfBounds(latitude));
-        }
-
-        if !(-180.0..180.0).contains(&longitude) {
-            return Err(LongitudeOutOfBounds(longitude));
-        }
+        Coordinates::new(latitude, longitude)?;
 
         if let Some(error) = validate_available_positions_for_surface(available_positions, surface)
         {
@@ -89,7 +109,7 @@ impl Office {
 
         Ok(Self {
             id: OfficeId::RealOffice(RealOfficeId::new()),
-            created_at: Utc::now(),
+            created_at: crate::now(),
             name,
             address,
             latitude,
@@ -102,11 +122,16 @@ impl Office {
         })
     }
 
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates::new_unchecked(self.latitude, self.longitude)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new_unchecked(
         id: OfficeId,
         created_at: DateTime<Utc>,
         name: String,
-        address: String,
+        address: Address,
         latitude: f32,
         longitude: f32,
         owner: UserId,
@@ -162,7 +187,7 @@ impl Office {
         for split in splits {
             offices.push(Self {
                 id: OfficeId::OfficeSplit(OfficeSplitId::new()),
-                created_at: Utc::now(),
+                created_at: crate::now(),
                 name: self.name.clone(),
                 address: self.address.clone(),
                 latitude: self.latitude,
@@ -178,6 +203,16 @@ impl Office {
         Ok(offices)
     }
 
+    /// The real office this office is a split of, or itself
+    pub fn real_office(&self) -> RealOfficeId {
+        match self.id {
+            OfficeId::RealOffice(id) => id,
+            OfficeId::OfficeSplit(_) => self
+                .parent_office
+                .expect("Office splits always have a parent office"),
+        }
+    }
+
     fn ensure_is_real_office(&self) -> Result<RealOfficeId, ()> {
         match self.id {
             OfficeId::RealOffice(id) => Ok(id),
@@ -200,10 +235,8 @@ pub enum OfficeSplitError {
 
 #[derive(Debug, thiserror::Error)]
 pub enum OfficeError {
-    #[error("Given latitude ({0}) is out of bounds.")]
-    LatitudeOutOfBounds(f32),
-    #[error("Given longitude ({0}) is out of bounds.")]
-    LongitudeOutOfBounds(f32),
+    #[error(transparent)]
+    CoordinatesOutOfBounds(#[from] GeoError),
     #[error("Given available positions ({0}) is out of bounds.")]
     AvailablePositionsOutOfBounds(usize),
     #[error("Given price per position ({0}) is out of bounds.")]
@@ -249,11 +282,36 @@ impl Object for Office {
 }
 
 #[cfg(test)]
+#[allow(clippy::excessive_precision, clippy::assertions_on_constants)]
 mod test {
     use rstest::rstest;
 
+    use crate::test_support;
+
     use super::*;
 
+    #[rstest]
+    #[case(180.0, 90.0)]
+    #[case(-180.0, -90.0)]
+    fn test_extreme_coordinates(#[case] longitude: f32, #[case] latitude: f32) {
+        let office = Office::new_real(
+            "yo".to_string(),
+            test_support::address(),
+            latitude,
+            longitude,
+            UserId::new(),
+            120,
+            500,
+            30000,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Coordinates::new(latitude, longitude),
+            Ok(office.coordinates())
+        );
+    }
+
     #[rstest]
     #[case(-193.61427, 63.272813)]
     #[case(193.61427, 63.272813)]
@@ -262,7 +320,7 @@ mod test {
     fn test_invalid_coordinates(#[case] longitude: f32, #[case] latitude: f32) {
         let office = Office::new_real(
             "yo".to_string(),
-            "10 my address".to_string(),
+            test_support::address(),
             latitude,
             longitude,
             UserId::new(),
@@ -274,7 +332,9 @@ mod test {
         if let Err(err) = office {
             assert!(matches!(
                 err,
-                OfficeError::LatitudeOutOfBounds(_) | OfficeError::LongitudeOutOfBounds(_)
+                OfficeError::CoordinatesOutOfBounds(
+                    GeoError::LatitudeOutOfBounds(_) | GeoError::LongitudeOutOfBounds(_)
+                )
             ));
         } else {
             assert!(false);
@@ -0,0 +1,112 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Utc};
+
+use crate::{id::Identifier, interval::DateRange, model_id, Object};
+
+use super::{Office, OfficeId};
+
+model_id!(OfficeWindowId, "win");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowKind {
+    /// The office is only rentable during its listing periods, if it has any
+    Listing,
+    /// The office is not rentable, e.g. during renovations or while its host uses it
+    Blackout,
+}
+
+impl Display for WindowKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                WindowKind::Listing => "listing",
+                WindowKind::Blackout => "blackout",
+            }
+        )
+    }
+}
+
+impl FromStr for WindowKind {
+    type Err = OfficeWindowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "listing" => Self::Listing,
+            "blackout" => Self::Blackout,
+            _ => return Err(OfficeWindowError::UnknownKind(s.to_string())),
+        })
+    }
+}
+
+/// A period during which a host lists an office, or takes it off the market
+#[derive(Debug, derive_getters::Getters)]
+pub struct OfficeWindow {
+    id: OfficeWindowId,
+    #[getter(skip)]
+    created_at: DateTime<Utc>,
+    office: OfficeId,
+    kind: WindowKind,
+    period: DateRange,
+    reason: Option<String>,
+}
+
+impl Object for OfficeWindow {
+    fn uuid(&self) -> &uuid::Uuid {
+        &self.id.0
+    }
+
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+impl OfficeWindow {
+    pub fn new(
+        office: &Office,
+        kind: WindowKind,
+        period: DateRange,
+        reason: Option<String>,
+    ) -> Result<Self, OfficeWindowError> {
+        if period.is_empty() {
+            return Err(OfficeWindowError::EmptyPeriod(period));
+        }
+
+        Ok(Self {
+            id: OfficeWindowId::new(),
+            created_at: crate::now(),
+            office: *office.id(),
+            kind,
+            period,
+            reason,
+        })
+    }
+
+    pub fn new_unchecked(
+        id: OfficeWindowId,
+        created_at: DateTime<Utc>,
+        office: OfficeId,
+        kind: WindowKind,
+        period: DateRange,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            created_at,
+            office,
+            kind,
+            period,
+            reason,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum OfficeWindowError {
+    #[error("Unknown window kind {0}")]
+    UnknownKind(String),
+    #[error("Period {0} is empty")]
+    EmptyPeriod(DateRange),
+}
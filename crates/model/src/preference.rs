@@ -0,0 +1,380 @@
+use std::fmt::Display;
+
+use crate::{
+    geo::Coordinates,
+    office::Office,
+    user::{User, UserId},
+};
+
+/// Required preferences rule offices out, preferred ones only rank them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    Required,
+    Preferred,
+}
+
+impl Display for Strictness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Strictness::Required => "required",
+                Strictness::Preferred => "preferred",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, derive_getters::Getters)]
+pub struct Preference<T> {
+    value: T,
+    strictness: Strictness,
+}
+
+impl<T> Preference<T> {
+    pub fn new(value: T, strictness: Strictness) -> Self {
+        Self { value, strictness }
+    }
+}
+
+/// Offices at most `max_distance_km` away from `coordinates`
+#[derive(Debug, Clone, Copy, PartialEq, derive_getters::Getters)]
+pub struct Location {
+    coordinates: Coordinates,
+    max_distance_km: f64,
+}
+
+impl Location {
+    pub fn new(coordinates: Coordinates, max_distance_km: f64) -> Self {
+        Self {
+            coordinates,
+            max_distance_km,
+        }
+    }
+}
+
+/// Offices of `min` to `max` positions, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct OfficeSize {
+    min: usize,
+    max: usize,
+}
+
+impl OfficeSize {
+    pub fn new(min: usize, max: usize) -> Self {
+        Self { min, max }
+    }
+}
+
+/// What a guest is looking for; Budgets are monthly rents excluding VAT, in cents
+#[derive(Debug, derive_getters::Getters)]
+pub struct GuestPreferences {
+    guest: UserId,
+    location: Option<Preference<Location>>,
+    budget: Option<Preference<usize>>,
+    office_size: Option<Preference<OfficeSize>>,
+}
+
+impl GuestPreferences {
+    pub fn new(
+        guest: &User,
+        location: Option<Preference<Location>>,
+        budget: Option<Preference<usize>>,
+        office_size: Option<Preference<OfficeSize>>,
+    ) -> Result<Self, PreferenceError> {
+        use PreferenceError::*;
+
+        if !guest.role().can_rent() {
+            return Err(NotAGuest(*guest.id()));
+        }
+
+        if let Some(location) = location.filter(|location| location.value.max_distance_km <= 0.0) {
+            return Err(NoDistance(location.value.max_distance_km));
+        }
+
+        if budget.is_some_and(|budget| budget.value == 0) {
+            return Err(NoBudget);
+        }
+
+        if let Some(size) = office_size.filter(|size| size.value.min > size.value.max) {
+            return Err(InvalidOfficeSize {
+                min: size.value.min,
+                max: size.value.max,
+            });
+        }
+
+        Ok(Self::new_unchecked(
+            *guest.id(),
+            location,
+            budget,
+            office_size,
+        ))
+    }
+
+    pub fn new_unchecked(
+        guest: UserId,
+        location: Option<Preference<Location>>,
+        budget: Option<Preference<usize>>,
+        office_size: Option<Preference<OfficeSize>>,
+    ) -> Self {
+        Self {
+            guest,
+            location,
+            budget,
+            office_size,
+        }
+    }
+
+    /// How well renting the office for the given monthly `rent` meets each preference
+    pub fn evaluate(&self, office: &Office, rent: usize) -> Evaluation {
+        let location = self.location.map(|location| Outcome::Location {
+            distance_km: location
+                .value
+                .coordinates
+                .distance_km(&office.coordinates()),
+            max_distance_km: location.value.max_distance_km,
+            strictness: location.strictness,
+        });
+        let budget = self.budget.map(|budget| Outcome::Budget {
+            rent,
+            budget: budget.value,
+            strictness: budget.strictness,
+        });
+        let office_size = self.office_size.map(|size| Outcome::OfficeSize {
+            positions: *office.available_positions(),
+            min: size.value.min,
+            max: size.value.max,
+            strictness: size.strictness,
+        });
+
+        Evaluation {
+            outcomes: [location, budget, office_size]
+                .into_iter()
+                .flatten()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, derive_getters::Getters)]
+pub struct Evaluation {
+    outcomes: Vec<Outcome>,
+}
+
+impl Evaluation {
+    /// Every required preference is met
+    pub fn is_acceptable(&self) -> bool {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.strictness() == Strictness::Required)
+            .all(Outcome::is_met)
+    }
+
+    /// Share of the preferred preferences met, from 0 to 1; 1 without any
+    pub fn score(&self) -> f64 {
+        let preferred = self
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.strictness() == Strictness::Preferred)
+            .collect::<Vec<_>>();
+
+        if preferred.is_empty() {
+            return 1.0;
+        }
+
+        preferred.iter().filter(|outcome| outcome.is_met()).count() as f64 / preferred.len() as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Location {
+        distance_km: f64,
+        max_distance_km: f64,
+        strictness: Strictness,
+    },
+    Budget {
+        rent: usize,
+        budget: usize,
+        strictness: Strictness,
+    },
+    OfficeSize {
+        positions: usize,
+        min: usize,
+        max: usize,
+        strictness: Strictness,
+    },
+}
+
+impl Outcome {
+    pub fn is_met(&self) -> bool {
+        match *self {
+            Outcome::Location {
+                distance_km,
+                max_distance_km,
+                ..
+            } => distance_km <= max_distance_km,
+            Outcome::Budget { rent, budget, .. } => rent <= budget,
+            Outcome::OfficeSize {
+                positions,
+                min,
+                max,
+                ..
+            } => (min..=max).contains(&positions),
+        }
+    }
+
+    pub fn strictness(&self) -> Strictness {
+        match *self {
+            Outcome::Location { strictness, .. }
+            | Outcome::Budget { strictness, .. }
+            | Outcome::OfficeSize { strictness, .. } => strictness,
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Outcome::Location {
+                distance_km,
+                max_distance_km,
+                strictness,
+            } => write!(
+                f,
+                "location: {distance_km:.1} km away, at most {max_distance_km:.1} km ({strictness})"
+            )?,
+            Outcome::Budget {
+                rent,
+                budget,
+                strictness,
+            } => write!(
+                f,
+                "budget: {}€/month, at most {}€/month ({strictness})",
+                rent as f32 / 100.0,
+                budget as f32 / 100.0
+            )?,
+            Outcome::OfficeSize {
+                positions,
+                min,
+                max,
+                strictness,
+            } => write!(
+                f,
+                "office size: {positions} positions, from {min} to {max} ({strictness})"
+            )?,
+        }
+
+        write!(f, " {}", if self.is_met() { "met" } else { "NOT met" })
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PreferenceError {
+    #[error("User {0} is not a guest")]
+    NotAGuest(UserId),
+    #[error("The maximum distance ({0} km) must be positive")]
+    NoDistance(f64),
+    #[error("A budget cannot be empty")]
+    NoBudget,
+    #[error("An office cannot have from {min} to {max} positions")]
+    InvalidOfficeSize { min: usize, max: usize },
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::{
+        test_support::{self, user},
+        user::Role,
+    };
+
+    use super::*;
+
+    /// 100 positions at 500€ each, next to Notre-Dame
+    fn create_office() -> Office {
+        test_support::office(&user(Role::Host))
+    }
+
+    /// Near the Eiffel Tower, about 4.1 km away from the office
+    fn location(max_distance_km: f64, strictness: Strictness) -> Option<Preference<Location>> {
+        Some(Preference::new(
+            Location::new(Coordinates::new(48.8584, 2.2945).unwrap(), max_distance_km),
+            strictness,
+        ))
+    }
+
+    #[rstest]
+    #[case(location(5.0, Strictness::Required), None, None, true, 1.0)]
+    #[case(location(3.0, Strictness::Required), None, None, false, 1.0)]
+    #[case(location(3.0, Strictness::Preferred), None, None, true, 0.0)]
+    #[case(
+        None,
+        Some(Preference::new(500000, Strictness::Required)),
+        None,
+        true,
+        1.0
+    )]
+    #[case(
+        None,
+        Some(Preference::new(499999, Strictness::Required)),
+        None,
+        false,
+        1.0
+    )]
+    #[case(
+        location(5.0, Strictness::Preferred),
+        Some(Preference::new(100000, Strictness::Preferred)),
+        Some(Preference::new(OfficeSize::new(50, 150), Strictness::Required)),
+        true,
+        0.5
+    )]
+    #[case(
+        None,
+        None,
+        Some(Preference::new(OfficeSize::new(10, 60), Strictness::Required)),
+        false,
+        1.0
+    )]
+    fn test_evaluate(
+        #[case] location: Option<Preference<Location>>,
+        #[case] budget: Option<Preference<usize>>,
+        #[case] office_size: Option<Preference<OfficeSize>>,
+        #[case] is_acceptable: bool,
+        #[case] score: f64,
+    ) {
+        let preferences =
+            GuestPreferences::new(&user(Role::Guest), location, budget, office_size).unwrap();
+
+        let evaluation = preferences.evaluate(&create_office(), 500000);
+
+        assert_eq!(is_acceptable, evaluation.is_acceptable());
+        assert_eq!(score, evaluation.score());
+    }
+
+    #[rstest]
+    #[case(Role::Host, None, None, None)]
+    #[case(Role::Guest, location(0.0, Strictness::Required), None, None)]
+    #[case(
+        Role::Guest,
+        None,
+        Some(Preference::new(0, Strictness::Preferred)),
+        None
+    )]
+    #[case(
+        Role::Guest,
+        None,
+        None,
+        Some(Preference::new(OfficeSize::new(60, 10), Strictness::Required))
+    )]
+    fn test_invalid_preferences(
+        #[case] role: Role,
+        #[case] location: Option<Preference<Location>>,
+        #[case] budget: Option<Preference<usize>>,
+        #[case] office_size: Option<Preference<OfficeSize>>,
+    ) {
+        assert!(GuestPreferences::new(&user(role), location, budget, office_size).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::{BTreeSet, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{id::Identifier, indexation::RentPeriod, model_id, user::UserId, Object};
+
+model_id!(TaxRuleId, "tax");
+
+/// Rates are expressed in basis points, `2000` being 20%
+pub const TAX_RATE_MAXIMUM: usize = 10000;
+
+/// A VAT rate applied to rents between `start` (inclusive) and `end` (exclusive). A rule without
+/// an `end` stays effective until replaced
+#[derive(Debug, derive_getters::Getters)]
+pub struct TaxRule {
+    id: TaxRuleId,
+    #[getter(skip)]
+    created_at: DateTime<Utc>,
+    rate: usize,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+}
+
+impl Object for TaxRule {
+    fn uuid(&self) -> &uuid::Uuid {
+        &self.id.0
+    }
+
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+impl TaxRule {
+    pub fn new(rate: usize, start: NaiveDate, end: Option<NaiveDate>) -> Result<Self, TaxError> {
+        use TaxError::*;
+
+        if rate > TAX_RATE_MAXIMUM {
+            return Err(RateOutOfBounds(rate));
+        }
+
+        if let Some(end) = end.filter(|end| *end <= start) {
+            return Err(EndBeforeStart { start, end });
+        }
+
+        Ok(Self {
+            id: TaxRuleId::new(),
+            created_at: crate::now(),
+            rate,
+            start,
+            end,
+        })
+    }
+
+    pub fn new_unchecked(
+        id: TaxRuleId,
+        created_at: DateTime<Utc>,
+        rate: usize,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            id,
+            created_at,
+            rate,
+            start,
+            end,
+        }
+    }
+
+    pub fn is_effective_on(&self, date: NaiveDate) -> bool {
+        self.start <= date && self.end.is_none_or(|end| date < end)
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.end.is_none_or(|end| other.start < end) && other.end.is_none_or(|end| self.start < end)
+    }
+}
+
+/// Net, tax and gross amounts, in cents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct RentBreakdown {
+    net: usize,
+    tax: usize,
+    gross: usize,
+}
+
+impl RentBreakdown {
+    pub fn new(net: usize, rate: usize) -> Self {
+        // rounded half-up to the nearest cent
+        let tax = (net * rate + TAX_RATE_MAXIMUM / 2) / TAX_RATE_MAXIMUM;
+
+        Self {
+            net,
+            tax,
+            gross: net + tax,
+        }
+    }
+}
+
+/// Rents are stored net of tax; this set decides which rate applies to a given host at a given
+/// date. Hosts that did not opt in to VAT are never taxed
+#[derive(Debug, Default)]
+pub struct TaxRuleSet {
+    rules: Vec<TaxRule>,
+    opted_in_hosts: HashSet<UserId>,
+}
+
+impl TaxRuleSet {
+    pub fn new(rules: Vec<TaxRule>, opted_in_hosts: HashSet<UserId>) -> Result<Self, TaxError> {
+        let mut rule_set = Self {
+            rules: Vec::with_capacity(rules.len()),
+            opted_in_hosts,
+        };
+        for rule in rules {
+            rule_set.add(rule)?;
+        }
+
+        Ok(rule_set)
+    }
+
+    /// Adds a rule, unless another one is effective on some of its dates
+    pub fn add(&mut self, rule: TaxRule) -> Result<&TaxRule, TaxError> {
+        if let Some(other) = self.rules.iter().find(|other| other.overlaps(&rule)) {
+            return Err(TaxError::OverlappingRules(other.id, rule.id));
+        }
+
+        self.rules.push(rule);
+
+        Ok(&self.rules[self.rules.len() - 1])
+    }
+
+    pub fn rules(&self) -> &[TaxRule] {
+        &self.rules
+    }
+
+    pub fn opted_in_hosts(&self) -> &HashSet<UserId> {
+        &self.opted_in_hosts
+    }
+
+    pub fn is_opted_in(&self, host: &UserId) -> bool {
+        self.opted_in_hosts.contains(host)
+    }
+
+    pub fn rate_for(&self, host: &UserId, date: NaiveDate) -> usize {
+        if !self.is_opted_in(host) {
+            return 0;
+        }
+
+        self.rules
+            .iter()
+            .find(|rule| rule.is_effective_on(date))
+            .map(|rule| rule.rate)
+            .unwrap_or_default()
+    }
+
+    pub fn breakdown(&self, host: &UserId, net: usize, date: NaiveDate) -> RentBreakdown {
+        RentBreakdown::new(net, self.rate_for(host, date))
+    }
+
+    /// The rent period split wherever a rule starts or ends during it, each part along with the
+    /// breakdown of its monthly rent
+    pub fn breakdowns(
+        &self,
+        host: &UserId,
+        period: &RentPeriod,
+    ) -> Vec<(RentPeriod, RentBreakdown)> {
+        let mut boundaries = self
+            .rules
+            .iter()
+            .flat_map(|rule| [Some(rule.start), rule.end])
+            .flatten()
+            .filter(|date| period.start() < date && date < period.end())
+            .collect::<BTreeSet<_>>();
+        boundaries.extend([*period.start(), *period.end()]);
+
+        boundaries
+            .iter()
+            .zip(boundaries.iter().skip(1))
+            .map(|(start, end)| {
+                (
+                    RentPeriod::new(*start, *end, *period.rent()),
+                    self.breakdown(host, *period.rent(), *start),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TaxError {
+    #[error("Given tax rate ({0}) is out of bounds.")]
+    RateOutOfBounds(usize),
+    #[error("A tax rule cannot end ({end}) before it starts ({start})")]
+    EndBeforeStart { start: NaiveDate, end: NaiveDate },
+    #[error("Tax rules {0} and {1} are effective on the same dates")]
+    OverlappingRules(TaxRuleId, TaxRuleId),
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_support::date;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn rule_set(host: UserId) -> TaxRuleSet {
+        TaxRuleSet::new(
+            vec![
+                TaxRule::new(1960, date(2000, 4, 1), Some(date(2014, 1, 1))).unwrap(),
+                TaxRule::new(2000, date(2014, 1, 1), None).unwrap(),
+            ],
+            HashSet::from([host]),
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(100000, 2000, 20000)]
+    #[case(100000, 0, 0)]
+    #[case(33333, 1960, 6533)]
+    #[case(1, 5000, 1)]
+    fn test_breakdown(#[case] net: usize, #[case] rate: usize, #[case] expected_tax: usize) {
+        let breakdown = RentBreakdown::new(net, rate);
+
+        assert_eq!(net, breakdown.net);
+        assert_eq!(expected_tax, breakdown.tax);
+        assert_eq!(net + expected_tax, breakdown.gross);
+    }
+
+    #[rstest]
+    #[case(date(2013, 12, 31), 1960)]
+    #[case(date(2014, 1, 1), 2000)]
+    #[case(date(2025, 6, 1), 2000)]
+    #[case(date(1999, 1, 1), 0)]
+    fn test_rate_depends_on_effective_dates(#[case] on: NaiveDate, #[case] expected_rate: usize) {
+        let host = UserId::new();
+
+        assert_eq!(expected_rate, rule_set(host).rate_for(&host, on));
+    }
+
+    #[test]
+    fn test_hosts_not_opted_in_are_not_taxed() {
+        let rule_set = rule_set(UserId::new());
+        let breakdown = rule_set.breakdown(&UserId::new(), 50000, date(2025, 1, 1));
+
+        assert_eq!(0, breakdown.tax);
+        assert_eq!(50000, breakdown.gross);
+    }
+
+    #[test]
+    fn test_breakdowns_split_where_the_rate_changes() {
+        let host = UserId::new();
+        let period = RentPeriod::new(date(2013, 10, 1), date(2014, 4, 1), 100000);
+
+        assert_eq!(
+            vec![
+                (
+                    RentPeriod::new(date(2013, 10, 1), date(2014, 1, 1), 100000),
+                    RentBreakdown::new(100000, 1960)
+                ),
+                (
+                    RentPeriod::new(date(2014, 1, 1), date(2014, 4, 1), 100000),
+                    RentBreakdown::new(100000, 2000)
+                ),
+            ],
+            rule_set(host).breakdowns(&host, &period)
+        );
+    }
+
+    #[test]
+    fn test_breakdowns_keep_periods_within_a_rule() {
+        let host = UserId::new();
+        let period = RentPeriod::new(date(2025, 1, 1), date(2026, 1, 1), 100000);
+
+        assert_eq!(
+            vec![(period, RentBreakdown::new(100000, 2000))],
+            rule_set(host).breakdowns(&host, &period)
+        );
+    }
+
+    #[rstest]
+    #[case(10001, date(2024, 1, 1), None)]
+    #[case(2000, date(2024, 1, 1), Some(date(2024, 1, 1)))]
+    fn test_invalid_rule(
+        #[case] rate: usize,
+        #[case] start: NaiveDate,
+        #[case] end: Option<NaiveDate>,
+    ) {
+        assert!(TaxRule::new(rate, start, end).is_err());
+    }
+
+    #[test]
+    fn test_overlapping_rules_are_rejected() {
+        let rule_set = TaxRuleSet::new(
+            vec![
+                TaxRule::new(2000, date(2014, 1, 1), None).unwrap(),
+                TaxRule::new(1000, date(2024, 1, 1), Some(date(2025, 1, 1))).unwrap(),
+            ],
+            HashSet::new(),
+        );
+
+        assert!(matches!(rule_set, Err(TaxError::OverlappingRules(..))));
+    }
+
+    #[test]
+    fn test_add_rule() {
+        let host = UserId::new();
+        let mut rule_set = rule_set(host);
+        rule_set.rules[1].end = Some(date(2030, 1, 1));
+
+        assert!(matches!(
+            rule_set.add(TaxRule::new(1000, date(2029, 1, 1), None).unwrap()),
+            Err(TaxError::OverlappingRules(..))
+        ));
+        assert_eq!(
+            2100,
+            *rule_set
+                .add(TaxRule::new(2100, date(2030, 1, 1), None).unwrap())
+                .unwrap()
+                .rate()
+        );
+        assert_eq!(2100, rule_set.rate_for(&host, date(2030, 6, 1)));
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    id::Identifier,
+    interval::DateRange,
+    model_id,
+    user::{User, UserId},
+    Object,
+};
+
+model_id!(TeamId, "tem");
+
+/// A team has `people` members from the given date on, until its next headcount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_getters::Getters)]
+pub struct Headcount {
+    from: NaiveDate,
+    people: usize,
+}
+
+impl Headcount {
+    pub fn new(from: NaiveDate, people: usize) -> Self {
+        Self { from, people }
+    }
+}
+
+/// An organisation renting offices through its guest, whose headcount changes over time
+#[derive(Debug, derive_getters::Getters)]
+pub struct Team {
+    id: TeamId,
+    #[getter(skip)]
+    created_at: DateTime<Utc>,
+    name: String,
+    guest: UserId,
+    /// Sorted by date, the first headcount also applies before its date
+    headcounts: Vec<Headcount>,
+}
+
+impl Object for Team {
+    fn uuid(&self) -> &uuid::Uuid {
+        &self.id.0
+    }
+
+    fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+impl Team {
+    pub fn new(name: String, guest: &User, headcounts: Vec<Headcount>) -> Result<Self, TeamError> {
+        use TeamError::*;
+
+        if !guest.role().can_rent() {
+            return Err(NotAGuest(*guest.id()));
+        }
+
+        if headcounts.is_empty() {
+            return Err(NoHeadcount);
+        }
+
+        if let Some(headcount) = headcounts.iter().find(|headcount| headcount.people == 0) {
+            return Err(EmptyHeadcount(headcount.from));
+        }
+
+        if let Some(pair) = headcounts
+            .windows(2)
+            .find(|pair| pair[0].from >= pair[1].from)
+        {
+            return Err(UnsortedHeadcounts {
+                previous: pair[0].from,
+                from: pair[1].from,
+            });
+        }
+
+        Ok(Self {
+            id: TeamId::new(),
+            created_at: crate::now(),
+            name,
+            guest: *guest.id(),
+            headcounts,
+        })
+    }
+
+    pub fn new_unchecked(
+        id: TeamId,
+        created_at: DateTime<Utc>,
+        name: String,
+        guest: UserId,
+        headcounts: Vec<Headcount>,
+    ) -> Self {
+        Self {
+            id,
+            created_at,
+            name,
+            guest,
+            headcounts,
+        }
+    }
+
+    pub fn headcount_on(&self, date: NaiveDate) -> usize {
+        self.headcounts
+            .iter()
+            .rev()
+            .find(|headcount| headcount.from <= date)
+            .or(self.headcounts.first())
+            .map(|headcount| headcount.people)
+            .unwrap_or_default()
+    }
+
+    /// Largest headcount during the period, i.e. the positions the team needs
+    pub fn peak_headcount(&self, period: &DateRange) -> usize {
+        self.headcounts
+            .iter()
+            .filter(|headcount| period.contains_date(headcount.from))
+            .map(|headcount| headcount.people)
+            .chain(std::iter::once(self.headcount_on(*period.start())))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Smallest headcount during the period
+    pub fn lowest_headcount(&self, period: &DateRange) -> usize {
+        self.headcounts
+            .iter()
+            .filter(|headcount| period.contains_date(headcount.from))
+            .map(|headcount| headcount.people)
+            .chain(std::iter::once(self.headcount_on(*period.start())))
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// First date after `after` from which the team no longer fits in `positions`
+    pub fn outgrows_on(&self, positions: usize, after: NaiveDate) -> Option<NaiveDate> {
+        self.headcounts
+            .iter()
+            .find(|headcount| headcount.from > after && headcount.people > positions)
+            .map(|headcount| headcount.from)
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TeamError {
+    #[error("User {0} is not a guest")]
+    NotAGuest(UserId),
+    #[error("A team needs at least one headcount")]
+    NoHeadcount,
+    #[error("A team cannot be empty, as it is from {0}")]
+    EmptyHeadcount(NaiveDate),
+    #[error("Headcounts must be sorted, but the one from {from} follows the one from {previous}")]
+    UnsortedHeadcounts {
+        previous: NaiveDate,
+        from: NaiveDate,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::test_support::date;
+    use crate::user::Role;
+
+    use super::*;
+
+    /// 45 people in 2024, growing to 90 in 2025
+    fn create_team() -> Team {
+        Team::new(
+            "Équipe".to_string(),
+            &User::new("Jean".to_string(), "Guest".to_string(), Role::Guest),
+            vec![
+                Headcount::new(date(2024, 1, 1), 45),
+                Headcount::new(date(2025, 1, 1), 90),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(date(2023, 6, 1), 45)]
+    #[case(date(2024, 1, 1), 45)]
+    #[case(date(2024, 12, 31), 45)]
+    #[case(date(2025, 1, 1), 90)]
+    fn test_headcount_on(#[case] date: NaiveDate, #[case] expected: usize) {
+        assert_eq!(expected, create_team().headcount_on(date));
+    }
+
+    #[rstest]
+    #[case(date(2024, 3, 1), date(2024, 12, 31), 45, 45)]
+    #[case(date(2024, 3, 1), date(2025, 1, 1), 45, 45)]
+    #[case(date(2024, 3, 1), date(2025, 1, 2), 90, 45)]
+    #[case(date(2025, 3, 1), date(2025, 6, 1), 90, 90)]
+    fn test_peak_and_lowest_headcount(
+        #[case] start: NaiveDate,
+        #[case] end: NaiveDate,
+        #[case] peak: usize,
+        #[case] lowest: usize,
+    ) {
+        let period = DateRange::new(start, end);
+
+        assert_eq!(peak, create_team().peak_headcount(&period));
+        assert_eq!(lowest, create_team().lowest_headcount(&period));
+    }
+
+    #[rstest]
+    #[case(50, date(2024, 3, 1), Some(date(2025, 1, 1)))]
+    #[case(90, date(2024, 3, 1), None)]
+    #[case(50, date(2025, 1, 1), None)]
+    fn test_outgrows_on(
+        #[case] positions: usize,
+        #[case] after: NaiveDate,
+        #[case] expected: Option<NaiveDate>,
+    ) {
+        assert_eq!(expected, create_team().outgrows_on(positions, after));
+    }
+
+    #[rstest]
+    #[case(Role::Host, vec![Headcount::new(date(2024, 1, 1), 45)])]
+    #[case(Role::Guest, vec![])]
+    #[case(Role::Guest, vec![Headcount::new(date(2024, 1, 1), 0)])]
+    #[case(Role::Guest, vec![
+        Headcount::new(date(2025, 1, 1), 45),
+        Headcount::new(date(2024, 1, 1), 90),
+    ])]
+    fn test_invalid_team(#[case] role: Role, #[case] headcounts: Vec<Headcount>) {
+        let guest = User::new("Jean".to_string(), "Guest".to_string(), role);
+
+        assert!(Team::new("Équipe".to_string(), &guest, headcounts).is_err());
+    }
+}
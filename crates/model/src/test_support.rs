@@ -0,0 +1,70 @@
+//! Fixtures shared by tests, available to other crates through the `test-support` feature
+
+use chrono::NaiveDate;
+
+use crate::{
+    address::Address,
+    office::Office,
+    user::{Role, User},
+};
+
+pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// Jean Dupont, with the given role
+pub fn user(role: Role) -> User {
+    User::new("Jean".to_string(), "Dupont".to_string(), role)
+}
+
+/// 10 rue de Rivoli, 75001 Paris
+pub fn address() -> Address {
+    Address::french(
+        Some("10".to_string()),
+        "rue de Rivoli".to_string(),
+        "75001".to_string(),
+        "Paris".to_string(),
+    )
+    .unwrap()
+}
+
+/// A real office of 100 positions at 500€ each in Paris, owned by `host`
+pub fn office(host: &User) -> Office {
+    office_with_positions(host, 100)
+}
+
+/// A real office of `positions` at 500€ each and 2m² per position in Paris, owned by `host`
+pub fn office_with_positions(host: &User, positions: usize) -> Office {
+    Office::new_real(
+        "Bureau".to_string(),
+        address(),
+        48.85,
+        2.35,
+        *host.id(),
+        positions,
+        positions * 2,
+        50000,
+    )
+    .unwrap()
+}
+
+/// A real office of 100 positions at 400€ each in Lyon, about 390 km away from [`office`]
+pub fn lyon_office(host: &User) -> Office {
+    Office::new_real(
+        "Bureau".to_string(),
+        Address::french(
+            Some("1".to_string()),
+            "place Bellecour".to_string(),
+            "69002".to_string(),
+            "Lyon".to_string(),
+        )
+        .unwrap(),
+        45.76,
+        4.83,
+        *host.id(),
+        100,
+        200,
+        40000,
+    )
+    .unwrap()
+}
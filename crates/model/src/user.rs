@@ -1,10 +1,58 @@
+use std::{fmt::Display, str::FromStr};
+
 use chrono::{DateTime, Utc};
 
 use crate::{id::Identifier, model_id, Object};
 
 model_id!(UserId, "usr");
 
-/// Making the assumption an user can be both a host and a guest at the same time
+/// An user is either a host or a guest, unless opted in to be both
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// Owns offices
+    Host,
+    /// Rents offices
+    Guest,
+    HostAndGuest,
+}
+
+impl Role {
+    pub fn can_host(&self) -> bool {
+        matches!(self, Self::Host | Self::HostAndGuest)
+    }
+
+    pub fn can_rent(&self) -> bool {
+        matches!(self, Self::Guest | Self::HostAndGuest)
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Role::Host => "host",
+                Role::Guest => "guest",
+                Role::HostAndGuest => "host_and_guest",
+            }
+        )
+    }
+}
+
+impl FromStr for Role {
+    type Err = UserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "host" => Self::Host,
+            "guest" => Self::Guest,
+            "host_and_guest" => Self::HostAndGuest,
+            _ => return Err(UserError::UnknownRole(s.to_string())),
+        })
+    }
+}
+
 #[derive(Debug, derive_getters::Getters)]
 pub struct User {
     id: UserId,
@@ -12,6 +60,7 @@ pub struct User {
     created_at: DateTime<Utc>,
     first_name: String,
     last_name: String,
+    role: Role,
 }
 
 impl Object for User {
@@ -25,12 +74,13 @@ impl Object for User {
 }
 
 impl User {
-    pub fn new(first_name: String, last_name: String) -> Self {
+    pub fn new(first_name: String, last_name: String, role: Role) -> Self {
         Self {
             id: UserId::new(),
-            created_at: Utc::now(),
+            created_at: crate::now(),
             first_name,
             last_name,
+            role,
         }
     }
 
@@ -39,12 +89,20 @@ impl User {
         created_at: DateTime<Utc>,
         first_name: String,
         last_name: String,
+        role: Role,
     ) -> Self {
         Self {
             id,
             created_at,
             first_name,
             last_name,
+            role,
         }
     }
 }
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum UserError {
+    #[error(r#"Unknown user role "{0}""#)]
+    UnknownRole(String),
+}
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+drop table tax_opt_ins;
+drop table tax_rules;
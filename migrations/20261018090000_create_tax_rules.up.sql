@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+create table tax_rules (
+    id uuid primary key unique not null,
+    created_at timestamptz not null default now(),
+    rate integer not null,
+    start date not null,
+    "end" date
+);
+
+create table tax_opt_ins (
+    host_id uuid primary key unique references users(id) not null,
+    created_at timestamptz not null default now()
+);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+alter table contracts
+    drop column indexation_index,
+    drop column indexation_reference_year,
+    drop column indexation_reference_quarter,
+    drop column indexation_anniversary;
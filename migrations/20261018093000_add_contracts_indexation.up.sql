@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+alter table contracts
+    add column indexation_index varchar,
+    add column indexation_reference_year integer,
+    add column indexation_reference_quarter integer,
+    add column indexation_anniversary date;
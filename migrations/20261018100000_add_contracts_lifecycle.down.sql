@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+drop table contract_transitions;
+alter table contracts drop column state;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+
+-- contracts persisted so far were agreed upon
+alter table contracts add column state varchar not null default 'signed';
+alter table contracts alter column state drop default;
+
+create table contract_transitions (
+    contract_id uuid references contracts(id) on delete cascade not null,
+    position integer not null,
+    "from" varchar not null,
+    "to" varchar not null,
+    at timestamptz not null,
+    reason varchar,
+    primary key (contract_id, position)
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+alter table contracts drop column termination_penalty;
+alter table contracts drop column terminated_on;
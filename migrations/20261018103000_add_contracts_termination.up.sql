@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- both set once the contract is terminated
+alter table contracts add column terminated_on date;
+alter table contracts add column termination_penalty integer;
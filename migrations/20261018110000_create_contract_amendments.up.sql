@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+-- contracts hold their amended terms, amendments keep the previous ones
+create table contract_amendments (
+    id uuid primary key not null,
+    created_at timestamptz not null,
+    contract_id uuid references contracts(id) on delete cascade not null,
+    position integer not null,
+    effective_on date not null,
+    kind varchar not null,
+    previous_end date,
+    "end" date,
+    previous_rent integer,
+    rent integer,
+    previous_office_id uuid references offices(id),
+    office_id uuid references offices(id),
+    reason varchar,
+    unique (contract_id, position)
+);
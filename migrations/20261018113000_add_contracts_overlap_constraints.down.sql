@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+alter table contracts drop constraint contracts_guest_overlap;
+alter table contracts drop constraint contracts_office_overlap;
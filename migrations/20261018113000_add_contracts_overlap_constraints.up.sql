@@ -0,0 +1,15 @@
+-- Add up migration script here
+
+create extension if not exists btree_gist;
+
+-- safety net behind the office calendar: a same office, or a same guest, cannot be booked twice at
+-- once. Terminated contracts free the office on their termination date
+alter table contracts add constraint contracts_office_overlap exclude using gist (
+    office_id with =,
+    daterange(start, coalesce(terminated_on, "end")) with &&
+);
+
+alter table contracts add constraint contracts_guest_overlap exclude using gist (
+    guest_id with =,
+    daterange(start, coalesce(terminated_on, "end")) with &&
+);
@@ -0,0 +1,70 @@
+-- Add up migration script here
+
+-- offices a contract occupies over time, an office move splitting its period in two; Written
+-- along with the contract, as a safety net behind the office calendar
+create table contract_occupancies (
+    contract_id uuid references contracts(id) on delete cascade not null,
+    office_id uuid references offices(id) not null,
+    -- the office itself for a real office, its parent for a split
+    real_office_id uuid references offices(id) not null,
+    is_split_office boolean not null,
+    during daterange not null check (not isempty(during)),
+    -- a real office cannot be booked along with one of its splits
+    constraint contract_occupancies_related_overlap exclude using gist (
+        real_office_id with =,
+        is_split_office with <>,
+        during with &&
+    )
+);
+
+create index contract_occupancies_contract_id on contract_occupancies (contract_id);
+
+-- existing contracts occupy their initial office until their first move, then the office each
+-- move leads to, until they end or are terminated
+with moves as (
+    select
+        contract_id,
+        effective_on,
+        previous_office_id,
+        office_id,
+        row_number() over (partition by contract_id order by position) as rank
+    from contract_amendments
+    where kind = 'office'
+),
+starts as (
+    select
+        contracts.id as contract_id,
+        coalesce(first_move.previous_office_id, contracts.office_id) as office_id,
+        contracts.start as "from",
+        0 as rank
+    from contracts
+    left join moves first_move on first_move.contract_id = contracts.id and first_move.rank = 1
+    union all
+    select contract_id, office_id, effective_on, rank from moves
+),
+segments as (
+    select
+        starts.contract_id,
+        starts.office_id,
+        starts."from",
+        least(
+            lead(starts."from") over (
+                partition by starts.contract_id order by starts.rank
+            ),
+            coalesce(contracts.terminated_on, contracts."end")
+        ) as until
+    from starts
+    inner join contracts on contracts.id = starts.contract_id
+)
+insert into contract_occupancies (
+    contract_id, office_id, real_office_id, is_split_office, during
+)
+select
+    segments.contract_id,
+    segments.office_id,
+    coalesce(offices.parent_office_id, offices.id),
+    offices.parent_office_id is not null,
+    daterange(segments."from", segments.until)
+from segments
+inner join offices on offices.id = segments.office_id
+where segments."from" < segments.until;
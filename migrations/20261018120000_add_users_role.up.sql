@@ -0,0 +1,5 @@
+-- Add up migration script here
+
+-- users persisted so far could both host and rent
+alter table users add column role varchar not null default 'host_and_guest';
+alter table users alter column role drop default;
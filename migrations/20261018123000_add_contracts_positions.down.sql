@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+alter table contracts add constraint contracts_office_overlap exclude using gist (
+    office_id with =,
+    daterange(start, coalesce(terminated_on, "end")) with &&
+);
+
+alter table contracts drop column positions;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+
+-- contracts persisted so far rented every position of their office
+alter table contracts add column positions integer check (positions > 0);
+update contracts set positions = offices.available_positions
+from offices where offices.id = contracts.office_id;
+alter table contracts alter column positions set not null;
+
+-- guests now share offices, positions left are checked by the office calendar
+alter table contracts drop constraint contracts_office_overlap;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+drop trigger contract_occupancies_capacity on contract_occupancies;
+drop function check_contract_occupancy_capacity;
+
+alter table contract_occupancies drop column positions;
@@ -0,0 +1,45 @@
+-- Add up migration script here
+
+-- occupancies take the positions of their contract
+alter table contract_occupancies add column positions integer check (positions > 0);
+update contract_occupancies set positions = contracts.positions
+from contracts where contracts.id = contract_occupancies.contract_id;
+alter table contract_occupancies alter column positions set not null;
+
+-- guests share offices as long as the positions they book fit in them, checked on every date a
+-- booking of the office starts within the new occupancy, the positions booked only rising then
+create function check_contract_occupancy_capacity() returns trigger as $$
+declare
+    capacity integer;
+    overbooked_on date;
+begin
+    -- serializes the checks of concurrent bookings of a same office
+    select available_positions into capacity from offices where id = new.office_id for update;
+
+    select dates.day into overbooked_on
+    from (
+        select lower(new.during) as day
+        union
+        select lower(during) from contract_occupancies
+        where office_id = new.office_id and during && new.during
+            and lower(during) > lower(new.during)
+    ) dates
+    where (
+        select sum(positions) from contract_occupancies
+        where office_id = new.office_id and during @> dates.day
+    ) > capacity
+    limit 1;
+
+    if overbooked_on is not null then
+        raise exception 'Office % has fewer than the positions booked on %',
+            new.office_id, overbooked_on
+            using errcode = 'check_violation', constraint = 'contract_occupancies_capacity';
+    end if;
+
+    return null;
+end;
+$$ language plpgsql;
+
+create constraint trigger contract_occupancies_capacity
+after insert or update on contract_occupancies
+for each row execute function check_contract_occupancy_capacity();
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+drop table team_headcounts;
+drop table teams;
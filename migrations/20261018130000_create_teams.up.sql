@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+-- organisations renting offices through their guest
+create table teams (
+    id uuid primary key unique not null,
+    created_at timestamptz not null default now(),
+    name varchar not null,
+    guest_id uuid references users(id) unique not null
+);
+
+create table team_headcounts (
+    team_id uuid references teams(id) not null,
+    "from" date not null,
+    people integer not null check (people > 0),
+    primary key (team_id, "from")
+);
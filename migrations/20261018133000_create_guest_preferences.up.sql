@@ -0,0 +1,25 @@
+-- Add up migration script here
+
+-- each preference is either required (hard constraint) or only preferred (soft constraint)
+create table guest_preferences (
+    guest_id uuid primary key references users(id) not null,
+    latitude real,
+    longitude real,
+    max_distance_km double precision check (max_distance_km > 0),
+    location_required boolean,
+    budget integer check (budget > 0),
+    budget_required boolean,
+    min_positions integer,
+    max_positions integer check (max_positions >= min_positions),
+    office_size_required boolean,
+    check (
+        (latitude is null) = (longitude is null)
+        and (latitude is null) = (max_distance_km is null)
+        and (latitude is null) = (location_required is null)
+    ),
+    check ((budget is null) = (budget_required is null)),
+    check (
+        (min_positions is null) = (max_positions is null)
+        and (min_positions is null) = (office_size_required is null)
+    )
+);
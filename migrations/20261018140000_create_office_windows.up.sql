@@ -0,0 +1,14 @@
+-- Add up migration script here
+
+-- listing periods and blackouts declared by hosts, offices with listing periods are only rentable
+-- during them
+create table office_windows (
+    id uuid primary key unique not null,
+    created_at timestamptz not null default now(),
+    office_id uuid references offices(id) not null,
+    kind varchar not null check (kind in ('listing', 'blackout')),
+    start date not null,
+    "end" date not null,
+    reason varchar,
+    check (start < "end")
+);
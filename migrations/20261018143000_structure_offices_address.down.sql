@@ -0,0 +1,21 @@
+-- Add down migration script here
+
+drop index offices_address_postal_code;
+drop index offices_address_city;
+
+alter table offices add column address varchar;
+
+update offices set address = concat_ws(' ', address_number, address_street)
+    || case
+        when address_city = '' and address_postal_code = '' then ''
+        else ', ' || address_city || ' ' || address_postal_code
+    end
+    || case when address_country <> 'FR' then ', ' || address_country else '' end;
+
+alter table offices
+    alter column address set not null,
+    drop column address_number,
+    drop column address_street,
+    drop column address_postal_code,
+    drop column address_city,
+    drop column address_country;
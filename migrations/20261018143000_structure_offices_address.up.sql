@@ -0,0 +1,53 @@
+-- Add up migration script here
+
+alter table offices
+    add column address_number varchar,
+    add column address_street varchar,
+    add column address_postal_code varchar,
+    add column address_city varchar,
+    add column address_country varchar(2) not null default 'FR';
+
+-- addresses were formatted as "number street, city postal_code" so far
+update offices set
+    address_number = parsed.parts[1],
+    address_street = parsed.parts[2],
+    address_city = parsed.parts[3],
+    address_postal_code = parsed.parts[4]
+from (
+    select id, regexp_match(address, '^(\d+\S*) (.+), (.+) (\S+)$') as parts from offices
+) as parsed
+where parsed.id = offices.id and parsed.parts is not null;
+
+update offices set
+    address_street = parsed.parts[1],
+    address_city = parsed.parts[2],
+    address_postal_code = parsed.parts[3]
+from (
+    select id, regexp_match(address, '^(.+), (.+) (\S+)$') as parts from offices
+) as parsed
+where parsed.id = offices.id and parsed.parts is not null and address_street is null;
+
+-- other addresses have to be fixed by hand first, rather than losing their city and postal code
+do $$
+declare
+    unparsed record;
+begin
+    select id, address into unparsed from offices where address_street is null limit 1;
+
+    if found then
+        raise exception 'Office % has an address that cannot be parsed: "%"',
+            unparsed.id, unparsed.address
+            using hint = 'Format it as "number street, city postal_code" and migrate again';
+    end if;
+end $$;
+
+alter table offices
+    alter column address_street set not null,
+    alter column address_postal_code set not null,
+    alter column address_city set not null,
+    alter column address_country drop default,
+    drop column address;
+
+-- offices are searched by city, or by postal code prefix (e.g. "75" for Paris)
+create index offices_address_city on offices (lower(address_city));
+create index offices_address_postal_code on offices (address_postal_code varchar_pattern_ops);
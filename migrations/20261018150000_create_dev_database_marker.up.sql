@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+-- a row marks the database as a development one, whose data fixtures can delete
+create table dev_database_marker (
+    marked boolean primary key default true check (marked),
+    marked_at timestamptz not null default now()
+);
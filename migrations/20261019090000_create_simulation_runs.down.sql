@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+drop table simulation_run_extensions;
+alter table contract_amendments drop column simulation_run_id;
+alter table contracts drop column simulation_run_id;
+drop table simulation_runs;
@@ -0,0 +1,37 @@
+-- Add up migration script here
+
+-- simulations persisted with `simulate --persist`, with the parameters to replay them and their
+-- outcome
+create table simulation_runs (
+    id uuid primary key unique not null,
+    created_at timestamptz not null default now(),
+    name varchar not null,
+    start date not null,
+    months integer not null check (months > 0),
+    guest_positions integer not null check (guest_positions > 0),
+    indexation_index varchar,
+    strategy varchar not null,
+    -- u64 seeds are stored bit for bit, possibly negative
+    seed bigint,
+    objective varchar not null,
+    -- simulations explored, the best one being kept
+    runs integer not null check (runs > 0),
+    score double precision not null,
+    feasible boolean not null,
+    created_contracts integer not null,
+    extended_contracts integer not null
+);
+
+-- contracts and amendments created by a simulation run, deleted along with it when discarded
+alter table contracts add column simulation_run_id uuid references simulation_runs(id);
+alter table contract_amendments add column simulation_run_id uuid references simulation_runs(id);
+
+-- existing contracts a run lengthened, restored to their previous end when discarded
+create table simulation_run_extensions (
+    simulation_run_id uuid references simulation_runs(id) on delete cascade not null,
+    contract_id uuid references contracts(id) on delete cascade not null,
+    previous_end date not null,
+    "end" date not null,
+    primary key (simulation_run_id, contract_id),
+    check (previous_end < "end")
+);